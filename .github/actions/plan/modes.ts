@@ -12,19 +12,19 @@ export const code = {
   clippy: {
     name: "clippy",
     cargoCommand: "clippy",
-    cargoArgs: "--locked --workspace --all-targets -- -D warnings",
+    cargoArgs: "--locked --workspace --all-targets --all-features -- -D warnings",
     cargoCacheKey: "clippy",
   },
   test: {
     name: "test",
     cargoCommand: "test",
-    cargoArgs: "--locked --workspace",
+    cargoArgs: "--locked --workspace --all-features",
     cargoCacheKey: "test",
   },
   build: {
     name: "build",
     cargoCommand: "build",
-    cargoArgs: "--locked --workspace",
+    cargoArgs: "--locked --workspace --all-features",
     cargoCacheKey: "build",
   },
   fmt: {
@@ -37,7 +37,7 @@ export const code = {
   docs: {
     name: "doc",
     cargoCommand: "doc",
-    cargoArgs: "--locked --workspace --document-private-items",
+    cargoArgs: "--locked --workspace --all-features --document-private-items",
     platformIndependent: true,
     cargoCacheKey: "doc",
   },
@@ -0,0 +1,99 @@
+//! Compare the [`axum_request_validator::Layer`] against the equivalent middleware-fn.
+//!
+//! The layer allocates once per validated request, for the
+//! [`axum_request_validator::ValidationFuture`], while the middleware-fn also boxes its own
+//! future and the inner service call; the requests the layer skips are not allocated for at all.
+
+use axum::{
+    body::Body,
+    http::{Method, Request},
+    routing::post,
+    Router,
+};
+use criterion::{criterion_group, criterion_main, Criterion};
+use tower::ServiceExt as _;
+
+/// Accept any request.
+#[derive(Debug, Clone, Copy)]
+struct NoopValidator;
+
+impl<Data: bytes::Buf + Send + Sync> http_request_validator::Validator<Data> for NoopValidator {
+    type Error = &'static str;
+
+    async fn validate<'a>(
+        &'a self,
+        _parts: &'a axum::http::request::Parts,
+        _buffered_body: &'a Data,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// The app with the validation layer.
+fn layer_app() -> Router {
+    Router::new()
+        .route("/", post(|| async { "Hello, World!" }))
+        .route_layer(axum_request_validator::new(NoopValidator))
+}
+
+/// The app with the validation layer that skips the requests.
+fn skipping_layer_app() -> Router {
+    Router::new()
+        .route("/", post(|| async { "Hello, World!" }))
+        .route_layer(
+            axum_request_validator::new(NoopValidator)
+                .when(http_request_validator::predicate::Methods([Method::GET])),
+        )
+}
+
+/// The app with the validation done via the middleware-fn.
+fn middleware_fn_app() -> Router {
+    let state = axum_request_validator::State {
+        validator: NoopValidator,
+        error_handler: axum_request_validator::PlainDisplayErrorRenderer,
+    };
+    Router::new()
+        .route("/", post(|| async { "Hello, World!" }))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state,
+            axum_request_validator::middleware,
+        ))
+}
+
+/// Send a request through the app.
+async fn roundtrip(app: Router) {
+    let req = Request::post("/")
+        .body(Body::from("Hello, World!"))
+        .unwrap();
+    let res = app.oneshot(req).await.unwrap();
+    assert!(res.status().is_success());
+}
+
+/// The benchmarks.
+fn bench(c: &mut Criterion) {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    let mut group = c.benchmark_group("validate");
+
+    let app = layer_app();
+    group.bench_function("layer", |b| {
+        b.to_async(&rt).iter(|| roundtrip(app.clone()));
+    });
+
+    let app = skipping_layer_app();
+    group.bench_function("layer_skipped", |b| {
+        b.to_async(&rt).iter(|| roundtrip(app.clone()));
+    });
+
+    let app = middleware_fn_app();
+    group.bench_function("middleware_fn", |b| {
+        b.to_async(&rt).iter(|| roundtrip(app.clone()));
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
@@ -0,0 +1,219 @@
+//! Decoding of the request bodies; see [`crate::Layer::decompress`].
+
+use std::sync::OnceLock;
+
+use axum::{body::Body, extract::Request, http};
+use http_body_request_validator::{
+    buffered::Buffered, convert::BufferedToBody as _, decompression,
+};
+
+/// The bufferer that decodes the body according to the `Content-Encoding` of the request head.
+///
+/// The decoding runs on the blocking thread pool, as it can take a while for the large bodies.
+pub(crate) struct Bufferer {
+    /// The `Content-Encoding` headers of the request head.
+    encoding: http::HeaderMap,
+
+    /// The limit of the decoded body size, in bytes.
+    max_decoded_size: usize,
+
+    /// Whether to keep the body that can not be decoded, to let the request through as is.
+    lenient: bool,
+
+    /// The length of the decoded body, set once it is decoded.
+    decoded_len: OnceLock<usize>,
+
+    /// The body that could not be decoded, kept in the lenient mode.
+    undecodable: OnceLock<crate::BuffererBuffered>,
+}
+
+impl Bufferer {
+    /// Create the bufferer for the request with the given head, if its body is encoded.
+    pub(crate) fn new(
+        headers: &http::HeaderMap,
+        max_decoded_size: usize,
+        lenient: bool,
+    ) -> Option<Self> {
+        if !decompression::is_encoded(headers) {
+            return None;
+        }
+
+        let mut encoding = http::HeaderMap::new();
+        for value in headers.get_all(http::header::CONTENT_ENCODING) {
+            encoding.append(http::header::CONTENT_ENCODING, value.clone());
+        }
+
+        Some(Self {
+            encoding,
+            max_decoded_size,
+            lenient,
+            decoded_len: OnceLock::new(),
+            undecodable: OnceLock::new(),
+        })
+    }
+
+    /// Describe the decoded body in the request head: remove the `Content-Encoding` and set
+    /// the `Content-Length`.
+    fn describe_decoded(&self, headers: &mut http::HeaderMap) {
+        if let Some(&decoded_len) = self.decoded_len.get() {
+            decompression::adjust_headers(headers, decoded_len);
+        }
+    }
+}
+
+impl http_body_request_validator::Bufferer<Body> for Bufferer {
+    type Buffered = crate::BuffererBuffered;
+    type Error = axum::Error;
+
+    async fn buffer(&self, body: Body) -> Result<Self::Buffered, Self::Error> {
+        let Buffered { data, trailers } = crate::Bufferer::new().buffer(body).await?;
+
+        let encoding = self.encoding.clone();
+        let raw = data.clone();
+        let max_decoded_size = self.max_decoded_size;
+        let decoded = tokio::task::spawn_blocking(move || {
+            decompression::decode(&encoding, &raw, max_decoded_size)
+        })
+        .await
+        .map_err(axum::Error::new)?;
+
+        match decoded {
+            Ok(data) => {
+                let _ = self.decoded_len.set(data.len());
+                Ok(Buffered { data, trailers })
+            }
+            Err(error) => {
+                if self.lenient {
+                    let _ = self.undecodable.set(Buffered { data, trailers });
+                }
+                Err(axum::Error::new(error))
+            }
+        }
+    }
+}
+
+/// The validator that gets the request head describing the decoded body.
+///
+/// The pre-validation gets the head as it came, describing the encoded body that is about to
+/// be buffered.
+struct DecodedHead<'a, Validator> {
+    /// The validator to run.
+    inner: Validator,
+
+    /// The bufferer that has decoded the body.
+    bufferer: &'a Bufferer,
+}
+
+impl<Validator> http_request_validator::Validator<crate::Data> for DecodedHead<'_, Validator>
+where
+    Validator: http_request_validator::Validator<crate::Data> + Sync,
+{
+    type Error = Validator::Error;
+
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::request::Parts,
+        buffered_body: &'a crate::Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + Send + 'a {
+        let mut parts = parts.clone();
+        self.bufferer.describe_decoded(&mut parts.headers);
+        async move { self.inner.validate(&parts, buffered_body).await }
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        self.inner.pre_validate(parts)
+    }
+}
+
+/// Validate the request over the decoded body; see [`crate::validate_with_observer`].
+pub(crate) async fn validate_with_observer<Validator, Observer>(
+    validator: Validator,
+    observer: Observer,
+    req: Request,
+    max_decoded_size: usize,
+) -> Result<Request, crate::Error<Validator::Error>>
+where
+    Validator: http_request_validator::Validator<crate::Data> + Sync,
+    Observer: http_body_request_validator::Observer<Validator::Error>,
+{
+    let Some(bufferer) = Bufferer::new(req.headers(), max_decoded_size, false) else {
+        return crate::validate_with_observer(validator, observer, req).await;
+    };
+    let buffering_validator =
+        crate::validation::buffering_validator(bufferer).with_observer(observer);
+    let validator = DecodedHead {
+        inner: validator,
+        bufferer: &buffering_validator.bufferer,
+    };
+    let mut req = buffering_validator.validate(validator, req).await?;
+    buffering_validator
+        .bufferer
+        .describe_decoded(req.headers_mut());
+    Ok(req)
+}
+
+/// Validate the request over the decoded body, but let it through even if the validation
+/// fails; see [`crate::validate_report_only_with_observer`].
+///
+/// The body that can not be decoded is reported to the observer as a buffering failure, and
+/// the request is let through as is, with the [`crate::DecodingFailure`] in the extensions.
+pub(crate) async fn validate_report_only_with_observer<Validator, Observer>(
+    validator: Validator,
+    observer: Observer,
+    req: Request,
+    max_decoded_size: usize,
+) -> Result<(Request, Result<(), Validator::Error>), axum::Error>
+where
+    Validator: http_request_validator::Validator<crate::Data> + Sync,
+    Observer: http_body_request_validator::Observer<Validator::Error>,
+{
+    let Some(bufferer) = Bufferer::new(req.headers(), max_decoded_size, true) else {
+        return crate::validate_report_only_with_observer(validator, observer, req).await;
+    };
+    // Kept to let the request through if the body can not be decoded.
+    let (parts, body) = req.into_parts();
+    let head = parts.clone();
+
+    let mut buffering_validator =
+        crate::validation::buffering_validator(bufferer).with_observer(observer);
+    let validator = DecodedHead {
+        inner: validator,
+        bufferer: &buffering_validator.bufferer,
+    };
+    let validated = buffering_validator
+        .validate_report_only(validator, Request::from_parts(parts, body))
+        .await;
+
+    match validated {
+        Ok((mut req, result)) => {
+            buffering_validator
+                .bufferer
+                .describe_decoded(req.headers_mut());
+            Ok((req, result))
+        }
+        Err(error) => {
+            let Some(body) = buffering_validator.bufferer.undecodable.take() else {
+                return Err(error);
+            };
+            let error = match error.into_inner().downcast::<decompression::Error>() {
+                Ok(error) => *error,
+                Err(error) => return Err(axum::Error::new(error)),
+            };
+            let mut req = Request::from_parts(
+                head,
+                crate::validation::CustomBufferedToBody::buffered_to_body(body),
+            );
+            req.extensions_mut()
+                .insert(crate::DecodingFailure(std::sync::Arc::new(error)));
+            Ok((req, Ok(())))
+        }
+    }
+}
+
+/// The kind of the body buffering error, if it is a decoding error.
+pub(crate) fn error_kind(error: &axum::Error) -> Option<http_request_validator::ErrorKind> {
+    use http_request_validator::ValidationErrorKind as _;
+
+    let source = std::error::Error::source(error)?;
+    Some(source.downcast_ref::<decompression::Error>()?.kind())
+}
@@ -1,26 +1,365 @@
 //! An axum layer for HTTP request validation.
 
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
 use axum::{
     extract::Request,
     http,
     middleware::Next,
-    response::{IntoResponse as _, Response},
+    response::{IntoResponse, Response},
 };
 
 use crate::Error;
 
+/// A future that buffers and validates the request.
+///
+/// Resolves to the buffered request if it passes the validation, or to the error response
+/// otherwise.
+///
+/// This future is boxed, so every validated request costs one allocation: the futures
+/// returned by the [`http_request_validator::Validator`] and the [`ErrorHandler`] can't be
+/// named on stable Rust, and they borrow the request parts and body they are validating.
+/// The rest of the [`ServiceFuture`] is not boxed, and the requests skipped with
+/// [`Layer::when`] are passed to the inner service without any allocation.
+pub type ValidationFuture =
+    Pin<Box<dyn Future<Output = Result<Request, Response>> + Send + 'static>>;
+
 /// A future that returns [`Response`].
-pub type ResponseFuture =
-    core::pin::Pin<Box<dyn core::future::Future<Output = Response> + Send + 'static>>;
+#[deprecated(note = "the layer is not built on the middleware-fn anymore, see `ServiceFuture`")]
+pub type ResponseFuture = Pin<Box<dyn Future<Output = Response> + Send + 'static>>;
 
 /// The type alias for the fn used in the layer.
+#[deprecated(note = "the layer is not built on the middleware-fn anymore, see `Service`")]
+#[allow(deprecated)]
 pub type Fn<S> = fn(axum::extract::State<S>, Request, Next) -> ResponseFuture;
 
 /// The type alias for the extractors used in the layer.
+#[deprecated(note = "the layer is not built on the middleware-fn anymore, see `Service`")]
 pub type Extractors<S> = (axum::extract::State<S>, Request);
 
 /// The layer type.
-pub type Layer<State> = axum::middleware::FromFnLayer<Fn<State>, State, Extractors<State>>;
+#[derive(Debug, Clone)]
+pub struct Layer<State, Observer = ()> {
+    /// The layer state.
+    state: State,
+
+    /// The observer of the buffering and validation.
+    observer: Observer,
+}
+
+impl<State, Observer> Layer<State, Observer> {
+    /// Set the [`http_body_request_validator::Observer`] to report the buffering and validation
+    /// progress to.
+    pub fn with_observer<New>(self, observer: New) -> Layer<State, New> {
+        let Self { state, observer: _ } = self;
+        Layer { state, observer }
+    }
+
+    /// Let the requests that fail the validation through.
+    ///
+    /// The validation error is inserted into the request extensions as [`ValidationFailure`]
+    /// instead of being handled by the error handler, so it can be recorded further down the
+    /// stack.
+    /// The body buffering errors are still handled by the error handler.
+    pub fn report_only(self) -> Layer<ReportOnly<State>, Observer> {
+        let Self { state, observer } = self;
+        Layer {
+            state: ReportOnly(state),
+            observer,
+        }
+    }
+
+    /// Only validate the requests matching the predicate.
+    ///
+    /// The requests that do not match are passed through as is, without buffering the body.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # #[derive(Clone)]
+    /// # struct MyValidator;
+    /// #
+    /// # impl<Data: bytes::Buf + Send + Sync> http_request_validator::Validator<Data> for MyValidator {
+    /// #    type Error = &'static str;
+    /// #
+    /// #    async fn validate<'a>(
+    /// #        &'a self,
+    /// #        _parts: &'a axum::http::request::Parts,
+    /// #        buffered_body: &'a Data,
+    /// #    ) -> Result<(), Self::Error> {
+    /// #        unimplemented!();
+    /// #    }
+    /// # }
+    /// #
+    /// use axum::{http::Method, routing::get, Router};
+    /// use http_request_validator::predicate::{Methods, PathGlob, Predicate as _};
+    ///
+    /// let app = Router::new()
+    ///     .route("/webhook", get(|| async { "challenge" }).post(|| async { "ok" }))
+    ///     .route("/health", get(|| async { "ok" }))
+    ///     .layer(
+    ///         axum_request_validator::new(MyValidator)
+    ///             .when(Methods([Method::POST]).and(PathGlob("/webhook"))),
+    ///     );
+    /// # let _: Router<()> = app;
+    /// ```
+    pub fn when<Predicate>(
+        self,
+        predicate: Predicate,
+    ) -> Layer<http_request_validator::When<Predicate, State>, Observer>
+    where
+        Predicate: http_request_validator::Predicate,
+    {
+        let Self { state, observer } = self;
+        Layer {
+            state: http_request_validator::When::new(predicate, state),
+            observer,
+        }
+    }
+}
+
+#[cfg(feature = "decompression")]
+impl<Validator, ErrorHandler, Observer> Layer<State<Validator, ErrorHandler>, Observer> {
+    /// Decode the request body according to the `Content-Encoding` while buffering it.
+    ///
+    /// The validators and the inner service get the decoded body, and the request head
+    /// describing it, with the `Content-Encoding` removed and the decoded `Content-Length` set;
+    /// the pre-validation gets the head as it came, as the body is not decoded yet.
+    /// This applies to all the validators, so none of them can check the raw bytes: leave
+    /// the decoding out and wrap the validators that need the decoded body into
+    /// [`http_body_request_validator::decompression::Decoded`] to mix the two.
+    /// The decoding runs on the tokio blocking thread pool.
+    ///
+    /// The body that can not be decoded, or is decoded into more than `max_decoded_size` bytes,
+    /// fails the buffering, and the error is handled by the error handler like the other body
+    /// buffering errors.
+    pub fn decompress(
+        self,
+        max_decoded_size: usize,
+    ) -> Layer<Decompress<State<Validator, ErrorHandler>>, Observer> {
+        let Self { state, observer } = self;
+        Layer {
+            state: Decompress {
+                inner: state,
+                max_decoded_size,
+            },
+            observer,
+        }
+    }
+}
+
+#[cfg(feature = "decompression")]
+impl<Validator, ErrorHandler, Observer>
+    Layer<ReportOnly<State<Validator, ErrorHandler>>, Observer>
+{
+    /// Decode the request body according to the `Content-Encoding` while buffering it; see
+    /// [`Layer::decompress`].
+    ///
+    /// In the report-only mode, the body that can not be decoded is reported to the observer as
+    /// a buffering failure, and the request is let through as is, without running the
+    /// validators, with the [`DecodingFailure`] in the extensions.
+    pub fn decompress(
+        self,
+        max_decoded_size: usize,
+    ) -> Layer<ReportOnly<Decompress<State<Validator, ErrorHandler>>>, Observer> {
+        let Self {
+            state: ReportOnly(state),
+            observer,
+        } = self;
+        Layer {
+            state: ReportOnly(Decompress {
+                inner: state,
+                max_decoded_size,
+            }),
+            observer,
+        }
+    }
+}
+
+impl<Validator, Observer> Layer<State<Validator, PlainDisplayErrorRenderer>, Observer> {
+    /// Render the validation errors with the [`ClassifyingRenderer`] instead, picking
+    /// the status code by their [`http_request_validator::ErrorKind`].
+    pub fn with_error_kinds(self) -> Layer<State<Validator, ClassifyingRenderer>, Observer> {
+        let Self { state, observer } = self;
+        Layer {
+            state: State {
+                validator: state.validator,
+                error_handler: ClassifyingRenderer,
+            },
+            observer,
+        }
+    }
+}
+
+impl<State, Observer, Inner> tower_layer::Layer<Inner> for Layer<State, Observer>
+where
+    State: Clone,
+    Observer: Clone,
+{
+    type Service = Service<State, Inner, Observer>;
+
+    fn layer(&self, inner: Inner) -> Self::Service {
+        Service {
+            state: self.state.clone(),
+            observer: self.observer.clone(),
+            inner,
+        }
+    }
+}
+
+/// The service produced by the [`Layer`].
+#[derive(Debug, Clone)]
+pub struct Service<State, Inner, Observer = ()> {
+    /// The layer state.
+    state: State,
+
+    /// The observer of the buffering and validation.
+    observer: Observer,
+
+    /// The inner service.
+    inner: Inner,
+}
+
+impl<State, Observer, Inner> tower_service::Service<Request> for Service<State, Inner, Observer>
+where
+    State: Validate<Observer>,
+    Observer: Clone,
+    Inner: tower_service::Service<Request> + Clone,
+    Inner::Response: IntoResponse,
+{
+    type Response = Response;
+    type Error = Inner::Error;
+    type Future = ServiceFuture<Inner>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        // The instance that was driven to readiness has to be the one handling the request.
+        let not_ready_inner = self.inner.clone();
+        let mut ready_inner = core::mem::replace(&mut self.inner, not_ready_inner);
+
+        let (parts, body) = req.into_parts();
+        let applies = self.state.applies_to(&parts);
+        let req = Request::from_parts(parts, body);
+
+        if !applies {
+            return ServiceFuture {
+                phase: Phase::Running {
+                    future: ready_inner.call(req),
+                },
+            };
+        }
+
+        ServiceFuture {
+            phase: Phase::Validating {
+                validation: self.state.validate(self.observer.clone(), req),
+                inner: Some(ready_inner),
+            },
+        }
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The response future of the [`Service`].
+    ///
+    /// Drives the [`ValidationFuture`], the only boxed part, and then the inner service future
+    /// in place.
+    pub struct ServiceFuture<Inner>
+    where
+        Inner: tower_service::Service<Request>,
+    {
+        #[pin]
+        phase: Phase<Inner>,
+    }
+}
+
+pin_project_lite::pin_project! {
+    /// The phase of the [`ServiceFuture`].
+    #[project = PhaseProj]
+    enum Phase<Inner>
+    where
+        Inner: tower_service::Service<Request>,
+    {
+        /// The request is being buffered and validated.
+        Validating {
+            validation: ValidationFuture,
+            inner: Option<Inner>,
+        },
+        /// The validated request is being handled by the inner service.
+        Running {
+            #[pin]
+            future: Inner::Future,
+        },
+    }
+}
+
+impl<Inner> Future for ServiceFuture<Inner>
+where
+    Inner: tower_service::Service<Request>,
+    Inner::Response: IntoResponse,
+{
+    type Output = Result<Response, Inner::Error>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut phase = self.project().phase;
+        loop {
+            match phase.as_mut().project() {
+                PhaseProj::Validating { validation, inner } => {
+                    let req = match core::task::ready!(validation.as_mut().poll(cx)) {
+                        Ok(req) => req,
+                        Err(response) => return Poll::Ready(Ok(response)),
+                    };
+                    let future = inner
+                        .take()
+                        .expect("response future polled after completion")
+                        .call(req);
+                    phase.set(Phase::Running { future });
+                }
+                PhaseProj::Running { future } => {
+                    return future.poll(cx).map_ok(IntoResponse::into_response);
+                }
+            }
+        }
+    }
+}
+
+/// The validation the [`Service`] runs the requests through before passing them on.
+///
+/// Implemented for the [`State`], that rejects the invalid requests, and for the
+/// [`ReportOnly`] state, that lets them through.
+pub trait Validate<Observer> {
+    /// Whether the request has to be validated.
+    ///
+    /// The requests that don't are passed to the inner service right away, without buffering.
+    fn applies_to(&self, _parts: &http::request::Parts) -> bool {
+        true
+    }
+
+    /// Buffer and validate the request.
+    fn validate(&self, observer: Observer, req: Request) -> ValidationFuture;
+}
+
+/// Validate only the requests matching the predicate; see [`Layer::when`].
+impl<Predicate, State, Observer> Validate<Observer>
+    for http_request_validator::When<Predicate, State>
+where
+    Predicate: http_request_validator::Predicate,
+    State: Validate<Observer>,
+{
+    fn applies_to(&self, parts: &http::request::Parts) -> bool {
+        self.predicate.matches(parts) && self.inner.applies_to(parts)
+    }
+
+    fn validate(&self, observer: Observer, req: Request) -> ValidationFuture {
+        self.inner.validate(observer, req)
+    }
+}
 
 /// The layer state.
 #[derive(Debug, Clone)]
@@ -32,8 +371,146 @@ pub struct State<Validator, ErrorHandler> {
     pub error_handler: ErrorHandler,
 }
 
+impl<Validator, ErrorHandler, Observer> Validate<Observer> for State<Validator, ErrorHandler>
+where
+    Validator: http_request_validator::Validator<super::Data, Error: Send> + Clone + Send + 'static,
+    ErrorHandler: self::ErrorHandler<Validator::Error> + Clone + Send + 'static,
+    Observer: http_body_request_validator::Observer<Validator::Error, State: Send>
+        + Send
+        + Sync
+        + 'static,
+{
+    fn validate(&self, observer: Observer, req: Request) -> ValidationFuture {
+        let State {
+            validator,
+            error_handler,
+        } = self.clone();
+        Box::pin(validate_or_reject(error_handler, req, |req| {
+            super::validate_with_observer(validator, observer, req)
+        }))
+    }
+}
+
+/// The layer state that decodes the request body while buffering it; see [`Layer::decompress`].
+#[cfg(feature = "decompression")]
+#[derive(Debug, Clone)]
+pub struct Decompress<State> {
+    /// The state to validate the request with.
+    pub inner: State,
+
+    /// The limit of the decoded body size, in bytes.
+    pub max_decoded_size: usize,
+}
+
+#[cfg(feature = "decompression")]
+impl<Validator, ErrorHandler, Observer> Validate<Observer>
+    for Decompress<State<Validator, ErrorHandler>>
+where
+    Validator:
+        http_request_validator::Validator<super::Data, Error: Send> + Clone + Send + Sync + 'static,
+    ErrorHandler: self::ErrorHandler<Validator::Error> + Clone + Send + 'static,
+    Observer: http_body_request_validator::Observer<Validator::Error, State: Send>
+        + Send
+        + Sync
+        + 'static,
+{
+    fn validate(&self, observer: Observer, req: Request) -> ValidationFuture {
+        let State {
+            validator,
+            error_handler,
+        } = self.inner.clone();
+        let max_decoded_size = self.max_decoded_size;
+        Box::pin(validate_or_reject(error_handler, req, move |req| {
+            crate::decompression::validate_with_observer(validator, observer, req, max_decoded_size)
+        }))
+    }
+}
+
+/// The layer state for the report-only mode.
+///
+/// See [`Layer::report_only`].
+#[derive(Debug, Clone)]
+pub struct ReportOnly<State>(pub State);
+
+impl<Validator, ErrorHandler, Observer> Validate<Observer>
+    for ReportOnly<State<Validator, ErrorHandler>>
+where
+    Validator: http_request_validator::Validator<super::Data, Error: Send + Sync + 'static>
+        + Clone
+        + Send
+        + 'static,
+    ErrorHandler: self::ErrorHandler<Validator::Error> + Clone + Send + 'static,
+    Observer: http_body_request_validator::Observer<Validator::Error, State: Send>
+        + Send
+        + Sync
+        + 'static,
+{
+    fn validate(&self, observer: Observer, req: Request) -> ValidationFuture {
+        let State {
+            validator,
+            error_handler,
+        } = self.0.clone();
+        Box::pin(validate_or_report(error_handler, req, |req| {
+            super::validate_report_only_with_observer(validator, observer, req)
+        }))
+    }
+}
+
+#[cfg(feature = "decompression")]
+impl<Validator, ErrorHandler, Observer> Validate<Observer>
+    for ReportOnly<Decompress<State<Validator, ErrorHandler>>>
+where
+    Validator: http_request_validator::Validator<super::Data, Error: Send + Sync + 'static>
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+    ErrorHandler: self::ErrorHandler<Validator::Error> + Clone + Send + 'static,
+    Observer: http_body_request_validator::Observer<Validator::Error, State: Send>
+        + Send
+        + Sync
+        + 'static,
+{
+    fn validate(&self, observer: Observer, req: Request) -> ValidationFuture {
+        let State {
+            validator,
+            error_handler,
+        } = self.0.inner.clone();
+        let max_decoded_size = self.0.max_decoded_size;
+        Box::pin(validate_or_report(error_handler, req, move |req| {
+            crate::decompression::validate_report_only_with_observer(
+                validator,
+                observer,
+                req,
+                max_decoded_size,
+            )
+        }))
+    }
+}
+
+/// The validation error of the request let through in the report-only mode.
+///
+/// Available in the request extensions.
+#[derive(Debug)]
+pub struct ValidationFailure<E>(pub std::sync::Arc<E>);
+
+impl<E> Clone for ValidationFailure<E> {
+    fn clone(&self) -> Self {
+        Self(std::sync::Arc::clone(&self.0))
+    }
+}
+
+/// The decoding error of the request body let through as is in the report-only mode.
+///
+/// Available in the request extensions; see [`Layer::decompress`].
+#[cfg(feature = "decompression")]
+#[derive(Debug, Clone)]
+pub struct DecodingFailure(pub std::sync::Arc<http_body_request_validator::decompression::Error>);
+
 /// Create a new HTTP request validating layer.
 ///
+/// The errors are rendered with the [`PlainDisplayErrorRenderer`].
+///
 /// ## Examples
 ///
 /// ```
@@ -127,13 +604,13 @@ where
     Validator: http_request_validator::Validator<super::Data, Error: Send> + Send + 'static,
     ErrorHandler: self::ErrorHandler<Validator::Error> + Send + 'static,
 {
-    axum::middleware::from_fn_with_state(
-        State {
+    Layer {
+        state: State {
             validator,
             error_handler,
         },
-        |state, req, next| Box::pin(middleware(state, req, next)),
-    )
+        observer: (),
+    }
 }
 
 /// The error handler for the validation errors.
@@ -148,7 +625,14 @@ pub trait ErrorHandler<V> {
     ) -> impl std::future::Future<Output = Self::Response> + Send + Sync;
 }
 
-/// A an error renderer that will simply.
+/// The error renderer that responds with the error message in plain text.
+///
+/// The body buffering errors get `400 Bad Request`, and the validation errors get
+/// `403 Forbidden` whatever their [`http_request_validator::ErrorKind`] is: this renderer only
+/// requires the errors to be [`std::fmt::Display`], so it keeps working with the validators
+/// whose errors are not classified. Use [`Layer::with_error_kinds`] to switch to the
+/// [`ClassifyingRenderer`], which picks the status code by the error kind and adds the
+/// `WWW-Authenticate` challenge.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 pub struct PlainDisplayErrorRenderer;
 
@@ -173,7 +657,139 @@ where
     }
 }
 
+/// The [`PlainDisplayErrorRenderer`] that picks the status code for the validation errors
+/// according to their [`http_request_validator::ErrorKind`], and adds their
+/// `WWW-Authenticate` challenge.
+///
+/// See [`Layer::with_error_kinds`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ClassifyingRenderer;
+
+impl<V> ErrorHandler<V> for ClassifyingRenderer
+where
+    V: std::fmt::Display + http_request_validator::ValidationErrorKind + Send + Sync,
+    for<'a> V: 'a,
+{
+    type Response = Response;
+
+    async fn handle_error(&self, error: Error<V>) -> Self::Response {
+        match error {
+            Error::BodyBuffering(error) => (
+                body_buffering_status(&error),
+                format!("Unable to buffer the request: {error}"),
+            )
+                .into_response(),
+            Error::Validation(error) => {
+                let response = (
+                    error.kind().status_code(),
+                    format!("Invalid request: {error}"),
+                )
+                    .into_response();
+                with_www_authenticate(response, &error)
+            }
+        }
+    }
+}
+
+/// The status code for the body buffering error: the one of its kind for the decoding errors,
+/// and `400 Bad Request` otherwise.
+#[cfg_attr(not(feature = "decompression"), allow(unused_variables))]
+fn body_buffering_status(error: &axum::Error) -> http::StatusCode {
+    #[cfg(feature = "decompression")]
+    if let Some(kind) = crate::decompression::error_kind(error) {
+        return kind.status_code();
+    }
+    http::StatusCode::BAD_REQUEST
+}
+
+/// Add the `WWW-Authenticate` challenge of the validation error to the response, if any.
+pub(crate) fn with_www_authenticate(
+    mut response: Response,
+    error: &impl http_request_validator::ValidationErrorKind,
+) -> Response {
+    if let Some(challenge) = error.www_authenticate() {
+        response
+            .headers_mut()
+            .insert(http::header::WWW_AUTHENTICATE, challenge);
+    }
+    response
+}
+
+/// Run the buffering and validation, rendering the error response if the request does not
+/// pass.
+async fn validate_or_reject<ValidationError, ErrorHandler, Validation>(
+    error_handler: ErrorHandler,
+    req: Request,
+    validate: impl FnOnce(Request) -> Validation,
+) -> Result<Request, Response>
+where
+    ErrorHandler: self::ErrorHandler<ValidationError>,
+    Validation: Future<Output = Result<Request, Error<ValidationError>>>,
+{
+    #[cfg(feature = "tracing")]
+    let span = crate::trace::span(&req);
+
+    let validation = async move {
+        match validate(req).await {
+            Ok(req) => Ok(req),
+            Err(error) => Err(error_handler.handle_error(error).await.into_response()),
+        }
+    };
+
+    #[cfg(feature = "tracing")]
+    let validation = crate::trace::instrument(span, validation, |_| false);
+
+    validation.await
+}
+
+/// Run the buffering and validation, recording the validation error in the request extensions
+/// if the request does not pass.
+async fn validate_or_report<ValidationError, ErrorHandler, Validation>(
+    error_handler: ErrorHandler,
+    req: Request,
+    validate: impl FnOnce(Request) -> Validation,
+) -> Result<Request, Response>
+where
+    ValidationError: Send + Sync + 'static,
+    ErrorHandler: self::ErrorHandler<ValidationError>,
+    Validation: Future<Output = Result<(Request, Result<(), ValidationError>), axum::Error>>,
+{
+    #[cfg(feature = "tracing")]
+    let span = crate::trace::span(&req);
+
+    let validation = async move {
+        match validate(req).await {
+            Ok((req, Ok(()))) => Ok(req),
+            Ok((mut req, Err(error))) => {
+                req.extensions_mut()
+                    .insert(ValidationFailure(std::sync::Arc::new(error)));
+                Ok(req)
+            }
+            Err(error) => Err(error_handler
+                .handle_error(Error::BodyBuffering(error))
+                .await
+                .into_response()),
+        }
+    };
+
+    #[cfg(feature = "tracing")]
+    let validation = crate::trace::instrument(span, validation, |req| {
+        #[cfg(feature = "decompression")]
+        if req.extensions().get::<DecodingFailure>().is_some() {
+            return true;
+        }
+        req.extensions()
+            .get::<ValidationFailure<ValidationError>>()
+            .is_some()
+    });
+
+    validation.await
+}
+
 /// [`axum`] middleware-fn implementation.
+///
+/// Can be used with [`axum::middleware::from_fn_with_state`] if you need to compose the
+/// validation with other [`axum`] middleware-fn logic; otherwise prefer the [`Layer`].
 pub fn middleware<Validator, ErrorHandler>(
     state: axum::extract::State<State<Validator, ErrorHandler>>,
     req: Request,
@@ -183,15 +799,18 @@ where
     Validator: http_request_validator::Validator<super::Data, Error: Send> + Send,
     ErrorHandler: self::ErrorHandler<Validator::Error> + Send,
 {
-    let axum::extract::State(State {
-        validator,
-        error_handler,
-    }) = state;
+    let axum::extract::State(state) = state;
     async move {
-        let req = match super::validate(validator, req).await {
-            Ok(req) => req,
-            Err(error) => return error_handler.handle_error(error).await.into_response(),
-        };
-        next.run(req).await
+        let State {
+            validator,
+            error_handler,
+        } = state;
+        let validation = validate_or_reject(error_handler, req, |req| {
+            super::validate_with_observer(validator, (), req)
+        });
+        match validation.await {
+            Ok(req) => next.run(req).await,
+            Err(response) => response,
+        }
     }
 }
@@ -1,10 +1,27 @@
 //! [`axum`] integration for the [`http_request_validator`].
 
 mod layer;
+mod response_signing;
 mod validation;
 
+#[cfg(feature = "decompression")]
+mod decompression;
+
+#[cfg(feature = "metrics")]
+pub mod metrics;
+
+#[cfg(feature = "problem-details")]
+mod problem_details;
+
+#[cfg(feature = "tracing")]
+mod trace;
+
 pub use self::layer::*;
+pub use self::response_signing::*;
 pub use self::validation::*;
 
+#[cfg(feature = "problem-details")]
+pub use self::problem_details::*;
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,127 @@
+//! [`metrics`]-backed [`http_body_request_validator::Observer`].
+
+use std::time::Instant;
+
+use http_body_request_validator::observer::BufferingOutcome;
+use http_request_validator::ValidationErrorKind;
+
+/// The counter of the processed requests.
+///
+/// Labeled with `route`, `outcome` (`accepted`, `rejected`, `buffering_failed` or `cancelled`)
+/// and `kind` (the [`http_request_validator::ErrorKind`] for the rejected requests, empty
+/// otherwise).
+pub const REQUESTS_TOTAL: &str = "http_request_validator_requests_total";
+
+/// The histogram of the buffered body sizes, in bytes.
+///
+/// Labeled with `route`.
+pub const BODY_SIZE_BYTES: &str = "http_request_validator_body_size_bytes";
+
+/// The histogram of the body buffering durations, in seconds.
+///
+/// Labeled with `route` and `outcome` (`ok` or `error`).
+pub const BUFFERING_DURATION_SECONDS: &str = "http_request_validator_buffering_duration_seconds";
+
+/// The histogram of the validation durations, in seconds.
+///
+/// Labeled with `route` and `outcome` (`ok` or `error`).
+pub const VALIDATION_DURATION_SECONDS: &str = "http_request_validator_validation_duration_seconds";
+
+/// The route label value for the requests without an [`axum::extract::MatchedPath`].
+pub const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// The observer that emits the metrics via the [`metrics`] crate.
+///
+/// The `route` label is taken from the [`axum::extract::MatchedPath`], so the layer has to be
+/// installed with [`axum::Router::route_layer`] for it to be available.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MetricsObserver;
+
+/// The per-request state of the [`MetricsObserver`].
+#[derive(Debug)]
+pub struct MetricsObserverState {
+    /// The `route` label value.
+    route: String,
+
+    /// When the current step has started.
+    step_started_at: Instant,
+}
+
+impl<ValidationError> http_body_request_validator::Observer<ValidationError> for MetricsObserver
+where
+    ValidationError: ValidationErrorKind,
+{
+    type State = MetricsObserverState;
+
+    fn pre_validation_failed(&self, parts: &axum::http::request::Parts, error: &ValidationError) {
+        count_request(route(parts), "rejected", error.kind().as_str());
+    }
+
+    fn buffering_started(&self, parts: &axum::http::request::Parts) -> Self::State {
+        MetricsObserverState {
+            route: route(parts),
+            step_started_at: Instant::now(),
+        }
+    }
+
+    fn buffering_finished(&self, state: &mut Self::State, outcome: BufferingOutcome) {
+        let elapsed = state.step_started_at.elapsed();
+        state.step_started_at = Instant::now();
+
+        match outcome {
+            BufferingOutcome::Buffered { body_size } => {
+                record_duration(BUFFERING_DURATION_SECONDS, &state.route, "ok", elapsed);
+                metrics::histogram!(BODY_SIZE_BYTES, "route" => state.route.clone())
+                    .record(u32::try_from(body_size).unwrap_or(u32::MAX));
+            }
+            BufferingOutcome::Failed => {
+                record_duration(BUFFERING_DURATION_SECONDS, &state.route, "error", elapsed);
+                count_request(state.route.clone(), "buffering_failed", "");
+            }
+        }
+    }
+
+    fn validation_finished(&self, state: Self::State, result: Result<(), &ValidationError>) {
+        let elapsed = state.step_started_at.elapsed();
+
+        match result {
+            Ok(()) => {
+                record_duration(VALIDATION_DURATION_SECONDS, &state.route, "ok", elapsed);
+                count_request(state.route, "accepted", "");
+            }
+            Err(error) => {
+                record_duration(VALIDATION_DURATION_SECONDS, &state.route, "error", elapsed);
+                count_request(state.route, "rejected", error.kind().as_str());
+            }
+        }
+    }
+
+    fn cancelled(&self, state: Self::State) {
+        count_request(state.route, "cancelled", "");
+    }
+}
+
+/// The `route` label value of the request.
+fn route(parts: &axum::http::request::Parts) -> String {
+    parts
+        .extensions
+        .get::<axum::extract::MatchedPath>()
+        .map_or(UNMATCHED_ROUTE, axum::extract::MatchedPath::as_str)
+        .to_owned()
+}
+
+/// Record the step duration histogram.
+fn record_duration(
+    name: &'static str,
+    route: &str,
+    outcome: &'static str,
+    elapsed: std::time::Duration,
+) {
+    metrics::histogram!(name, "route" => route.to_owned(), "outcome" => outcome).record(elapsed);
+}
+
+/// Increment the requests counter.
+fn count_request(route: String, outcome: &'static str, kind: &'static str) {
+    metrics::counter!(REQUESTS_TOTAL, "route" => route, "outcome" => outcome, "kind" => kind)
+        .increment(1);
+}
@@ -0,0 +1,170 @@
+//! [RFC 9457](https://www.rfc-editor.org/rfc/rfc9457) problem details error rendering.
+
+use std::{borrow::Cow, collections::BTreeMap};
+
+use axum::{
+    http,
+    response::{IntoResponse as _, Response},
+};
+
+use http_request_validator::{ErrorKind, ValidationErrorKind};
+
+use crate::{Error, ErrorHandler};
+
+/// The media type of the problem details JSON document.
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+/// The problem type to render a class of errors as.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProblemType {
+    /// The URI identifying the problem type.
+    pub type_uri: String,
+
+    /// The short human-readable summary of the problem type.
+    pub title: String,
+
+    /// The HTTP status code to respond with.
+    pub status: http::StatusCode,
+}
+
+impl ProblemType {
+    /// Create a new [`ProblemType`] with the `about:blank` type URI and the title taken from
+    /// the canonical reason of the status code.
+    pub fn from_status(status: http::StatusCode) -> Self {
+        Self {
+            type_uri: "about:blank".to_owned(),
+            title: status.canonical_reason().unwrap_or_default().to_owned(),
+            status,
+        }
+    }
+
+    /// Set the type URI.
+    pub fn with_type_uri(mut self, type_uri: impl Into<String>) -> Self {
+        self.type_uri = type_uri.into();
+        self
+    }
+
+    /// Set the title.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+}
+
+/// An error renderer that responds with an `application/problem+json` document.
+///
+/// The problem type for the validation errors is picked according to their
+/// [`ErrorKind`]: an explicitly configured one if set via [`Self::with_kind`], otherwise
+/// [`Self::validation`] for [`ErrorKind::Other`] and `about:blank` with the
+/// [`ErrorKind::status_code`] for the rest.
+///
+/// Error details are rendered via [`std::fmt::Display`] into the `detail` member, unless
+/// hidden with [`Self::hide_details`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProblemDetailsRenderer {
+    /// The problem type for the body buffering errors.
+    pub body_buffering: ProblemType,
+
+    /// The problem type for the unclassified validation errors.
+    pub validation: ProblemType,
+
+    /// The problem types for the validation errors of the given kinds.
+    pub validation_kinds: BTreeMap<ErrorKind, ProblemType>,
+
+    /// Whether to render the error details.
+    pub expose_details: bool,
+}
+
+impl Default for ProblemDetailsRenderer {
+    fn default() -> Self {
+        Self {
+            body_buffering: ProblemType::from_status(http::StatusCode::BAD_REQUEST),
+            validation: ProblemType::from_status(http::StatusCode::FORBIDDEN),
+            validation_kinds: BTreeMap::new(),
+            expose_details: true,
+        }
+    }
+}
+
+impl ProblemDetailsRenderer {
+    /// Set the problem type for the body buffering errors.
+    pub fn with_body_buffering(mut self, problem_type: ProblemType) -> Self {
+        self.body_buffering = problem_type;
+        self
+    }
+
+    /// Set the problem type for the unclassified validation errors.
+    pub fn with_validation(mut self, problem_type: ProblemType) -> Self {
+        self.validation = problem_type;
+        self
+    }
+
+    /// Set the problem type for the validation errors of the given kind.
+    pub fn with_kind(mut self, kind: ErrorKind, problem_type: ProblemType) -> Self {
+        self.validation_kinds.insert(kind, problem_type);
+        self
+    }
+
+    /// The problem type to render the validation error of the given kind as.
+    pub fn problem_type_for(&self, kind: ErrorKind) -> Cow<'_, ProblemType> {
+        if let Some(problem_type) = self.validation_kinds.get(&kind) {
+            return Cow::Borrowed(problem_type);
+        }
+        match kind {
+            ErrorKind::Other => Cow::Borrowed(&self.validation),
+            kind => Cow::Owned(ProblemType::from_status(kind.status_code())),
+        }
+    }
+
+    /// Do not render the error details, for instance to avoid leaking internals in production.
+    pub fn hide_details(mut self) -> Self {
+        self.expose_details = false;
+        self
+    }
+
+    /// Render the problem details response.
+    fn render(&self, problem_type: &ProblemType, detail: &dyn std::fmt::Display) -> Response {
+        let mut document = serde_json::json!({
+            "type": problem_type.type_uri,
+            "title": problem_type.title,
+            "status": problem_type.status.as_u16(),
+        });
+        if self.expose_details {
+            document["detail"] = serde_json::Value::String(detail.to_string());
+        }
+
+        (
+            problem_type.status,
+            [(
+                http::header::CONTENT_TYPE,
+                http::HeaderValue::from_static(PROBLEM_JSON_CONTENT_TYPE),
+            )],
+            document.to_string(),
+        )
+            .into_response()
+    }
+}
+
+impl<V> ErrorHandler<V> for ProblemDetailsRenderer
+where
+    V: std::fmt::Display + ValidationErrorKind + Send + Sync,
+    for<'a> V: 'a,
+{
+    type Response = Response;
+
+    async fn handle_error(&self, error: Error<V>) -> Self::Response {
+        match error {
+            Error::BodyBuffering(error) => {
+                #[cfg(feature = "decompression")]
+                if let Some(kind) = crate::decompression::error_kind(&error) {
+                    return self.render(&self.problem_type_for(kind), &error);
+                }
+                self.render(&self.body_buffering, &error)
+            }
+            Error::Validation(error) => {
+                let response = self.render(&self.problem_type_for(error.kind()), &error);
+                crate::layer::with_www_authenticate(response, &error)
+            }
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! An axum layer for HTTP response signing.
+
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    extract::Request,
+    http,
+    response::{IntoResponse, Response},
+};
+
+/// The layer that signs the responses of the inner service.
+///
+/// The response body is buffered to be signed.
+/// If the buffering or signing fails, responds with an empty `500 Internal Server Error`
+/// instead, so the unsigned response never goes out. The failure is reported to the observer,
+/// see [`Self::with_observer`], and logged with the `tracing` feature.
+///
+/// ## Examples
+///
+/// ```
+/// # #[derive(Clone)]
+/// # struct MySigner;
+/// #
+/// # impl<Data: bytes::Buf + Send + Sync> http_request_validator::ResponseSigner<Data> for MySigner {
+/// #    type Error = &'static str;
+/// #
+/// #    async fn sign<'a>(
+/// #        &'a self,
+/// #        _parts: &'a mut axum::http::response::Parts,
+/// #        buffered_body: &'a Data,
+/// #    ) -> Result<(), Self::Error> {
+/// #        unimplemented!();
+/// #    }
+/// # }
+/// #
+/// use axum::{routing::get, Router};
+///
+/// let app = Router::new()
+///     .route("/", get(|| async { "Hello, World!" }))
+///     .layer(axum_request_validator::ResponseSigningLayer::new(MySigner));
+/// # let _: Router<()> = app;
+/// ```
+#[derive(Debug, Clone)]
+pub struct ResponseSigningLayer<Signer, Observer = ()> {
+    /// The signer to use.
+    signer: Signer,
+
+    /// The observer of the buffering and signing.
+    observer: Observer,
+}
+
+impl<Signer> ResponseSigningLayer<Signer> {
+    /// Create a new [`ResponseSigningLayer`].
+    pub const fn new(signer: Signer) -> Self {
+        Self {
+            signer,
+            observer: (),
+        }
+    }
+}
+
+impl<Signer, Observer> ResponseSigningLayer<Signer, Observer> {
+    /// Set the [`http_body_request_validator::Observer`] to report the buffering and signing
+    /// progress to; the signing is reported as the validation.
+    pub fn with_observer<New>(self, observer: New) -> ResponseSigningLayer<Signer, New> {
+        let Self {
+            signer,
+            observer: _,
+        } = self;
+        ResponseSigningLayer { signer, observer }
+    }
+}
+
+impl<Signer, Observer, Inner> tower_layer::Layer<Inner> for ResponseSigningLayer<Signer, Observer>
+where
+    Signer: Clone,
+    Observer: Clone,
+{
+    type Service = ResponseSigningService<Signer, Inner, Observer>;
+
+    fn layer(&self, inner: Inner) -> Self::Service {
+        ResponseSigningService {
+            signer: self.signer.clone(),
+            observer: self.observer.clone(),
+            inner,
+        }
+    }
+}
+
+/// The service produced by the [`ResponseSigningLayer`].
+#[derive(Debug, Clone)]
+pub struct ResponseSigningService<Signer, Inner, Observer = ()> {
+    /// The signer to use.
+    signer: Signer,
+
+    /// The observer of the buffering and signing.
+    observer: Observer,
+
+    /// The inner service.
+    inner: Inner,
+}
+
+/// The response future of the [`ResponseSigningService`].
+///
+/// Boxed, as the futures returned by the [`http_request_validator::ResponseSigner`] can't be
+/// named.
+pub type SigningResponseFuture<Error> =
+    Pin<Box<dyn Future<Output = Result<Response, Error>> + Send + 'static>>;
+
+impl<Signer, Observer, Inner> tower_service::Service<Request>
+    for ResponseSigningService<Signer, Inner, Observer>
+where
+    Signer: http_request_validator::ResponseSigner<super::Data, Error: std::fmt::Display>
+        + Clone
+        + Send
+        + 'static,
+    Observer: http_body_request_validator::Observer<Signer::Error, http::response::Parts, State: Send>
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+    Inner: tower_service::Service<Request, Future: Send + 'static>,
+    Inner::Response: IntoResponse,
+{
+    type Response = Response;
+    type Error = Inner::Error;
+    type Future = SigningResponseFuture<Inner::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request) -> Self::Future {
+        let future = self.inner.call(req);
+        let signer = self.signer.clone();
+        let observer = self.observer.clone();
+        Box::pin(async move {
+            let res = future.await?.into_response();
+            Ok(sign_or_fail(signer, observer, res).await)
+        })
+    }
+}
+
+/// Sign the response, or respond with the `500 Internal Server Error` if that fails.
+///
+/// The error is only reported to the observer and logged, with the `tracing` feature, as it
+/// is not meant for the client.
+async fn sign_or_fail<Signer, Observer>(
+    signer: Signer,
+    observer: Observer,
+    res: Response,
+) -> Response
+where
+    Signer: http_request_validator::ResponseSigner<super::Data, Error: std::fmt::Display>,
+    Observer: http_body_request_validator::Observer<Signer::Error, http::response::Parts>,
+{
+    let error = match super::sign_response_with_observer(signer, observer, res).await {
+        Ok(res) => return res,
+        Err(error) => error,
+    };
+
+    #[cfg(feature = "tracing")]
+    match &error {
+        super::SigningError::BodyBuffering(error) => {
+            tracing::error!(%error, "unable to buffer the response");
+        }
+        super::SigningError::Signing(error) => {
+            tracing::error!(%error, "unable to sign the response");
+        }
+    }
+    let _ = error;
+
+    http::StatusCode::INTERNAL_SERVER_ERROR.into_response()
+}
@@ -43,3 +43,739 @@ fn layer_builds() {
         .route("/", axum::routing::get(|| async { "Hello, World!" }))
         .route_layer(super::layer::new(SampleValidator));
 }
+
+#[derive(Debug, Clone)]
+struct RejectingValidator;
+
+impl<Data: bytes::Buf + Sync> http_request_validator::Validator<Data> for RejectingValidator {
+    type Error = &'static str;
+
+    async fn validate<'a>(
+        &'a self,
+        _parts: &'a axum::http::request::Parts,
+        _buffered_body: &'a Data,
+    ) -> Result<(), Self::Error> {
+        Err("rejected")
+    }
+}
+
+#[tokio::test]
+async fn layer_passes_valid_request() {
+    use tower::ServiceExt as _;
+
+    let app: axum::Router<()> = axum::Router::new()
+        .route("/", axum::routing::post(|body: String| async move { body }))
+        .route_layer(super::layer::new(SampleValidator));
+
+    let res = app
+        .oneshot(
+            axum::http::Request::post("/")
+                .body(axum::body::Body::from("payload"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), axum::http::StatusCode::OK);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "payload");
+}
+
+#[tokio::test]
+async fn layer_rejects_invalid_request() {
+    use tower::ServiceExt as _;
+
+    let app: axum::Router<()> = axum::Router::new()
+        .route("/", axum::routing::post(|| async { "unreachable" }))
+        .route_layer(super::layer::new(RejectingValidator));
+
+    let res = app
+        .oneshot(
+            axum::http::Request::post("/")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), axum::http::StatusCode::FORBIDDEN);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "Invalid request: rejected");
+}
+
+#[tokio::test]
+async fn layer_report_only_lets_invalid_request_through() {
+    use tower::ServiceExt as _;
+
+    let app: axum::Router<()> = axum::Router::new()
+        .route(
+            "/",
+            axum::routing::post(
+                |failure: Option<axum::Extension<super::ValidationFailure<&'static str>>>,
+                 body: String| async move {
+                    let failure = failure.map(|axum::Extension(failure)| *failure.0);
+                    format!("{failure:?} {body}")
+                },
+            ),
+        )
+        .route_layer(super::layer::new(RejectingValidator).report_only());
+
+    let res = app
+        .oneshot(
+            axum::http::Request::post("/")
+                .body(axum::body::Body::from("payload"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), axum::http::StatusCode::OK);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "Some(\"rejected\") payload");
+}
+
+#[tokio::test]
+async fn layer_skips_non_matching_requests() {
+    use http_request_validator::predicate::Methods;
+    use tower::ServiceExt as _;
+
+    let app: axum::Router<()> = axum::Router::new()
+        .route(
+            "/",
+            axum::routing::get(|| async { "challenge" }).post(|| async { "unreachable" }),
+        )
+        .route_layer(
+            super::layer::new(RejectingValidator).when(Methods([axum::http::Method::POST])),
+        );
+
+    let res = app
+        .clone()
+        .oneshot(
+            axum::http::Request::get("/")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), axum::http::StatusCode::OK);
+
+    let res = app
+        .oneshot(
+            axum::http::Request::post("/")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), axum::http::StatusCode::FORBIDDEN);
+}
+
+#[derive(Debug)]
+struct MissingSignature;
+
+impl std::fmt::Display for MissingSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("missing signature")
+    }
+}
+
+impl http_request_validator::ValidationErrorKind for MissingSignature {
+    fn kind(&self) -> http_request_validator::ErrorKind {
+        http_request_validator::ErrorKind::MissingCredentials
+    }
+
+    fn www_authenticate(&self) -> Option<axum::http::HeaderValue> {
+        Some(axum::http::HeaderValue::from_static("Signature"))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SignatureRequiredValidator;
+
+impl<Data: bytes::Buf + Sync> http_request_validator::Validator<Data>
+    for SignatureRequiredValidator
+{
+    type Error = MissingSignature;
+
+    async fn validate<'a>(
+        &'a self,
+        _parts: &'a axum::http::request::Parts,
+        _buffered_body: &'a Data,
+    ) -> Result<(), Self::Error> {
+        Err(MissingSignature)
+    }
+}
+
+#[tokio::test]
+async fn layer_dispatches_to_matching_provider() {
+    use http_request_validator::{predicate::HeaderPresent, Dispatch, MatchedProvider};
+    use tower::ServiceExt as _;
+
+    let dispatch = Dispatch::<String>::new()
+        .provider(
+            "github",
+            HeaderPresent(axum::http::HeaderName::from_static("x-hub-signature-256")),
+            SampleValidator,
+        )
+        .provider(
+            "stripe",
+            HeaderPresent(axum::http::HeaderName::from_static("stripe-signature")),
+            RejectingValidator,
+        );
+    let app: axum::Router<()> = axum::Router::new()
+        .route(
+            "/",
+            axum::routing::post(|axum::Extension(MatchedProvider(name))| async move { name }),
+        )
+        .route_layer(super::layer::new(dispatch).with_error_kinds());
+
+    let res = app
+        .clone()
+        .oneshot(
+            axum::http::Request::post("/")
+                .header("x-hub-signature-256", "sha256=00")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), axum::http::StatusCode::OK);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "github");
+
+    let res = app
+        .oneshot(
+            axum::http::Request::post("/")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), axum::http::StatusCode::UNAUTHORIZED);
+}
+
+#[derive(Debug, Clone)]
+struct ResponseBodyLengthSigner;
+
+impl<Data: bytes::Buf + Sync> http_request_validator::ResponseSigner<Data>
+    for ResponseBodyLengthSigner
+{
+    type Error = &'static str;
+
+    async fn sign<'a>(
+        &'a self,
+        parts: &'a mut axum::http::response::Parts,
+        buffered_body: &'a Data,
+    ) -> Result<(), Self::Error> {
+        if !buffered_body.has_remaining() {
+            return Err("empty body");
+        }
+        parts
+            .headers
+            .insert("x-body-length", buffered_body.remaining().into());
+        Ok(())
+    }
+}
+
+/// The observer collecting the signing errors.
+#[derive(Debug, Clone, Default)]
+struct SigningFailures(std::sync::Arc<std::sync::Mutex<Vec<String>>>);
+
+impl<E: std::fmt::Display> http_body_request_validator::Observer<E, axum::http::response::Parts>
+    for SigningFailures
+{
+    type State = ();
+
+    fn buffering_started(&self, _head: &axum::http::response::Parts) -> Self::State {}
+
+    fn buffering_finished(
+        &self,
+        _state: &mut Self::State,
+        _outcome: http_body_request_validator::observer::BufferingOutcome,
+    ) {
+    }
+
+    fn validation_finished(&self, _state: Self::State, result: Result<(), &E>) {
+        if let Err(error) = result {
+            self.0.lock().unwrap().push(error.to_string());
+        }
+    }
+}
+
+#[tokio::test]
+async fn response_signing_layer_signs_responses() {
+    use tower::ServiceExt as _;
+
+    let failures = SigningFailures::default();
+    let app: axum::Router<()> = axum::Router::new()
+        .route("/", axum::routing::get(|| async { "payload" }))
+        .route("/empty", axum::routing::get(|| async {}))
+        .layer(
+            super::ResponseSigningLayer::new(ResponseBodyLengthSigner)
+                .with_observer(failures.clone()),
+        );
+
+    let res = app
+        .clone()
+        .oneshot(
+            axum::http::Request::get("/")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), axum::http::StatusCode::OK);
+    assert_eq!(res.headers()["x-body-length"], "7");
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, "payload");
+
+    let res = app
+        .oneshot(
+            axum::http::Request::get("/empty")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+    // The signer error is not leaked to the client.
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert!(body.is_empty());
+    // But it is reported to the observer.
+    assert_eq!(*failures.0.lock().unwrap(), ["empty body"]);
+}
+
+#[cfg(feature = "decompression")]
+#[derive(Debug, Clone)]
+struct PayloadValidator;
+
+#[cfg(feature = "decompression")]
+impl http_request_validator::Validator<bytes::Bytes> for PayloadValidator {
+    type Error = &'static str;
+
+    async fn validate<'a>(
+        &'a self,
+        parts: &'a axum::http::request::Parts,
+        buffered_body: &'a bytes::Bytes,
+    ) -> Result<(), Self::Error> {
+        if parts.headers.contains_key("content-encoding")
+            || parts.headers["content-length"] != "7"
+            || buffered_body != "payload"
+        {
+            return Err("not the decoded payload");
+        }
+        Ok(())
+    }
+
+    fn pre_validate(&self, parts: &mut axum::http::request::Parts) -> Result<(), Self::Error> {
+        if parts.headers["content-encoding"] != "gzip" {
+            return Err("not the encoded payload");
+        }
+        Ok(())
+    }
+}
+
+/// The observer counting the failed body bufferings.
+#[cfg(feature = "decompression")]
+#[derive(Debug, Clone, Default)]
+struct BufferingFailures(std::sync::Arc<std::sync::atomic::AtomicUsize>);
+
+#[cfg(feature = "decompression")]
+impl<E> http_body_request_validator::Observer<E> for BufferingFailures {
+    type State = ();
+
+    fn buffering_started(&self, _head: &axum::http::request::Parts) -> Self::State {}
+
+    fn buffering_finished(
+        &self,
+        _state: &mut Self::State,
+        outcome: http_body_request_validator::observer::BufferingOutcome,
+    ) {
+        if outcome == http_body_request_validator::observer::BufferingOutcome::Failed {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        }
+    }
+
+    fn validation_finished(&self, _state: Self::State, result: Result<(), &E>) {
+        assert!(result.is_ok(), "the validator ran over the undecoded body");
+    }
+}
+
+#[cfg(feature = "decompression")]
+#[tokio::test]
+async fn layer_decompresses_body() {
+    use std::io::Write as _;
+    use tower::ServiceExt as _;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(b"payload").unwrap();
+    let gzipped = encoder.finish().unwrap();
+
+    let router = || {
+        axum::Router::new().route(
+            "/",
+            axum::routing::post(
+                |failure: Option<axum::Extension<super::DecodingFailure>>,
+                 headers: axum::http::HeaderMap,
+                 body: String| async move {
+                    format!(
+                        "{:?} {:?} {body:?} {}",
+                        headers.get("content-encoding"),
+                        headers.get("content-length"),
+                        failure.is_some(),
+                    )
+                },
+            ),
+        )
+    };
+    let request = |body: &[u8]| {
+        axum::http::Request::post("/")
+            .header("content-encoding", "gzip")
+            .header("content-length", body.len())
+            .body(axum::body::Body::from(body.to_vec()))
+            .unwrap()
+    };
+
+    // The validator and the inner service both get the decoded body.
+    let app: axum::Router<()> = router().route_layer(
+        super::layer::new(PayloadValidator)
+            .with_error_kinds()
+            .decompress(1024),
+    );
+    let res = app.clone().oneshot(request(&gzipped)).await.unwrap();
+    assert_eq!(res.status(), axum::http::StatusCode::OK);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, r#"None Some("7") "payload" false"#);
+
+    // The decoding errors go through the error handler.
+    let res = app.clone().oneshot(request(b"not gzip")).await.unwrap();
+    assert_eq!(res.status(), axum::http::StatusCode::BAD_REQUEST);
+    let app: axum::Router<()> = router().route_layer(
+        super::layer::new(PayloadValidator)
+            .with_error_kinds()
+            .decompress(4),
+    );
+    let res = app.oneshot(request(&gzipped)).await.unwrap();
+    assert_eq!(res.status(), axum::http::StatusCode::PAYLOAD_TOO_LARGE);
+
+    // In the report-only mode, the body that can not be decoded is reported as a buffering
+    // failure and let through as is, without running the validator.
+    let failures = BufferingFailures::default();
+    let app: axum::Router<()> = router().route_layer(
+        super::layer::new(PayloadValidator)
+            .report_only()
+            .decompress(1024)
+            .with_observer(failures.clone()),
+    );
+    let res = app.clone().oneshot(request(b"not gzip")).await.unwrap();
+    assert_eq!(res.status(), axum::http::StatusCode::OK);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, r#"Some("gzip") Some("8") "not gzip" true"#);
+    assert_eq!(failures.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+
+    let res = app.oneshot(request(&gzipped)).await.unwrap();
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    assert_eq!(body, r#"None Some("7") "payload" false"#);
+    assert_eq!(failures.0.load(std::sync::atomic::Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn layer_classifies_errors() {
+    use tower::ServiceExt as _;
+
+    let app: axum::Router<()> = axum::Router::new()
+        .route("/", axum::routing::post(|| async { "unreachable" }))
+        .route_layer(super::layer::new(SignatureRequiredValidator).with_error_kinds());
+
+    let res = app
+        .oneshot(
+            axum::http::Request::post("/")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), axum::http::StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        res.headers()[axum::http::header::WWW_AUTHENTICATE],
+        "Signature"
+    );
+
+    // The classification is opt-in.
+    let app: axum::Router<()> = axum::Router::new()
+        .route("/", axum::routing::post(|| async { "unreachable" }))
+        .route_layer(super::layer::new(SignatureRequiredValidator));
+    let res = app
+        .oneshot(
+            axum::http::Request::post("/")
+                .body(axum::body::Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), axum::http::StatusCode::FORBIDDEN);
+    assert!(!res
+        .headers()
+        .contains_key(axum::http::header::WWW_AUTHENTICATE));
+}
+
+#[cfg(feature = "problem-details")]
+#[tokio::test]
+async fn problem_details_classifies_errors() {
+    use super::ErrorHandler as _;
+
+    let renderer = super::ProblemDetailsRenderer::default();
+
+    let res = renderer
+        .handle_error(super::Error::Validation(MissingSignature))
+        .await;
+
+    assert_eq!(res.status(), axum::http::StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        res.headers()[axum::http::header::WWW_AUTHENTICATE],
+        "Signature"
+    );
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let document: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(document["status"], 401);
+}
+
+#[cfg(feature = "problem-details")]
+#[tokio::test]
+async fn problem_details_renders_validation_error() {
+    use super::ErrorHandler as _;
+
+    let renderer = super::ProblemDetailsRenderer::default().with_validation(
+        super::ProblemType::from_status(axum::http::StatusCode::UNAUTHORIZED)
+            .with_type_uri("https://example.com/problems/bad-signature"),
+    );
+
+    let res = renderer
+        .handle_error(super::Error::<&str>::Validation("signature mismatch"))
+        .await;
+
+    assert_eq!(res.status(), axum::http::StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        res.headers()[axum::http::header::CONTENT_TYPE],
+        super::PROBLEM_JSON_CONTENT_TYPE
+    );
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let document: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        document,
+        serde_json::json!({
+            "type": "https://example.com/problems/bad-signature",
+            "title": "Unauthorized",
+            "status": 401,
+            "detail": "signature mismatch",
+        })
+    );
+}
+
+#[cfg(feature = "problem-details")]
+#[tokio::test]
+async fn problem_details_hides_details() {
+    use super::ErrorHandler as _;
+
+    let renderer = super::ProblemDetailsRenderer::default().hide_details();
+
+    let res = renderer
+        .handle_error(super::Error::<&str>::Validation("secret internals"))
+        .await;
+
+    assert_eq!(res.status(), axum::http::StatusCode::FORBIDDEN);
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let document: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(
+        document,
+        serde_json::json!({
+            "type": "about:blank",
+            "title": "Forbidden",
+            "status": 403,
+        })
+    );
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn metrics_observer_counts_rejections() {
+    use http_body_request_validator::{observer::BufferingOutcome, Observer};
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+
+    metrics::with_local_recorder(&recorder, || {
+        let observer = super::metrics::MetricsObserver;
+        let (parts, ()) = axum::http::Request::new(()).into_parts();
+
+        let mut state = Observer::<MissingSignature>::buffering_started(&observer, &parts);
+        Observer::<MissingSignature>::buffering_finished(
+            &observer,
+            &mut state,
+            BufferingOutcome::Buffered { body_size: 7 },
+        );
+        observer.validation_finished(state, Err(&MissingSignature));
+    });
+
+    let counters: Vec<_> = snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .filter(|(key, ..)| key.key().name() == super::metrics::REQUESTS_TOTAL)
+        .collect();
+    assert_eq!(counters.len(), 1);
+
+    let (key, _, _, value) = &counters[0];
+    let labels: Vec<_> = key
+        .key()
+        .labels()
+        .map(|label| (label.key(), label.value()))
+        .collect();
+    assert_eq!(
+        labels,
+        [
+            ("route", super::metrics::UNMATCHED_ROUTE),
+            ("outcome", "rejected"),
+            ("kind", "missing_credentials"),
+        ]
+    );
+    assert_eq!(value, &DebugValue::Counter(1));
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn metrics_layer_builds() {
+    let _app: axum::Router<()> = axum::Router::new()
+        .route("/", axum::routing::get(|| async { "Hello, World!" }))
+        .route_layer(
+            super::layer::new(SampleValidator).with_observer(super::metrics::MetricsObserver),
+        );
+}
+
+/// The subscriber recording the fields of the spans.
+#[cfg(feature = "tracing")]
+#[derive(Debug, Clone, Default)]
+struct SpanRecorder {
+    /// The names and the fields of the spans, in the order they were created.
+    spans: std::sync::Arc<std::sync::Mutex<Vec<(&'static str, SpanFields)>>>,
+}
+
+/// The recorded fields of a span.
+#[cfg(feature = "tracing")]
+type SpanFields = std::collections::HashMap<&'static str, String>;
+
+#[cfg(feature = "tracing")]
+impl SpanRecorder {
+    fn fields(&self, name: &str) -> SpanFields {
+        let spans = self.spans.lock().unwrap();
+        let (_, fields) = spans.iter().find(|(span, _)| *span == name).unwrap();
+        fields.clone()
+    }
+}
+
+#[cfg(feature = "tracing")]
+struct FieldVisitor<'a>(&'a mut SpanFields);
+
+#[cfg(feature = "tracing")]
+impl tracing::field::Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.0.insert(field.name(), value.to_owned());
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.0.insert(field.name(), format!("{value:?}"));
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl tracing::Subscriber for SpanRecorder {
+    fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attributes: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+        let mut fields = SpanFields::new();
+        attributes.record(&mut FieldVisitor(&mut fields));
+        let mut spans = self.spans.lock().unwrap();
+        spans.push((attributes.metadata().name(), fields));
+        tracing::span::Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &tracing::span::Id, values: &tracing::span::Record<'_>) {
+        let mut spans = self.spans.lock().unwrap();
+        let index = usize::try_from(span.into_u64()).unwrap() - 1;
+        values.record(&mut FieldVisitor(&mut spans[index].1));
+    }
+
+    fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+
+    fn event(&self, _event: &tracing::Event<'_>) {}
+
+    fn enter(&self, _span: &tracing::span::Id) {}
+
+    fn exit(&self, _span: &tracing::span::Id) {}
+}
+
+#[cfg(feature = "tracing")]
+#[tokio::test]
+async fn tracing_records_span_fields() {
+    use tower::ServiceExt as _;
+
+    let recorder = SpanRecorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+
+    let app: axum::Router<()> = axum::Router::new()
+        .route("/hook", axum::routing::post(|| async { "ok" }))
+        .route_layer(super::layer::new(RejectingValidator).report_only());
+    let res = app
+        .oneshot(
+            axum::http::Request::post("/hook?token=secret")
+                .body(axum::body::Body::from("payload"))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(res.status(), axum::http::StatusCode::OK);
+
+    let request = recorder.fields("http_request_validator.request");
+    assert_eq!(request["method"], "POST");
+    assert_eq!(request["path"], "/hook");
+    assert_eq!(request["outcome"], "reported");
+    assert!(request.contains_key("duration_us"));
+    assert!(!request.contains_key("status"));
+
+    let buffer = recorder.fields("http_request_validator.buffer");
+    assert_eq!(buffer["body_size"], "7");
+    assert_eq!(buffer["outcome"], "ok");
+    assert!(buffer.contains_key("duration_us"));
+
+    let validate = recorder.fields("http_request_validator.validate");
+    assert_eq!(validate["outcome"], "error");
+    assert!(validate.contains_key("duration_us"));
+}
@@ -0,0 +1,55 @@
+//! [`tracing`] instrumentation of the axum layer.
+//!
+//! Only the request method and path, the outcome, the response status and the duration are
+//! recorded; the query string, headers, body and error details are not, as they can carry
+//! secrets.
+
+use axum::{extract::Request, response::Response};
+use tracing::Instrument as _;
+
+/// Create the span for the request validation.
+pub(crate) fn span(req: &Request) -> tracing::Span {
+    tracing::info_span!(
+        "http_request_validator.request",
+        method = %req.method(),
+        path = req.uri().path(),
+        outcome = tracing::field::Empty,
+        status = tracing::field::Empty,
+        duration_us = tracing::field::Empty,
+    )
+}
+
+/// Run the request validation within the given span.
+///
+/// The requests let through are recorded as `accepted`, or as `reported` if they failed
+/// the validation in the report-only mode, as told by `reported`.
+pub(crate) async fn instrument(
+    span: tracing::Span,
+    validation: impl core::future::Future<Output = Result<Request, Response>>,
+    reported: impl FnOnce(&Request) -> bool,
+) -> Result<Request, Response> {
+    let started_at = std::time::Instant::now();
+
+    let result = validation.instrument(span.clone()).await;
+
+    let duration_us = u64::try_from(started_at.elapsed().as_micros()).unwrap_or(u64::MAX);
+    span.record("duration_us", duration_us);
+
+    match &result {
+        Ok(req) => {
+            if reported(req) {
+                span.record("outcome", "reported");
+                tracing::info!(parent: &span, "request failed the validation, let through");
+            } else {
+                span.record("outcome", "accepted");
+            }
+        }
+        Err(response) => {
+            span.record("outcome", "rejected");
+            span.record("status", response.status().as_u16());
+            tracing::info!(parent: &span, status = response.status().as_u16(), "request rejected");
+        }
+    }
+
+    result
+}
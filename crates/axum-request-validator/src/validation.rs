@@ -19,7 +19,7 @@ static_assertions::assert_type_eq_all!(Data, bytes::Bytes);
 
 /// The custom implementation of the [`http_body_request_validator::convert::BufferedToBody`] for
 /// axum [`Body`].
-enum CustomBufferedToBody {}
+pub(crate) enum CustomBufferedToBody {}
 
 impl http_body_request_validator::convert::BufferedToBody for CustomBufferedToBody {
     type Buffered = BuffererBuffered;
@@ -34,6 +34,18 @@ impl http_body_request_validator::convert::BufferedToBody for CustomBufferedToBo
     }
 }
 
+/// The [`http_body_request_validator::BufferingValidator`] producing the axum [`Body`] with the
+/// given bufferer.
+pub(crate) fn buffering_validator<Bufferer>(
+    bufferer: Bufferer,
+) -> http_body_request_validator::BufferingValidator<Bufferer, Body, CustomBufferedToBody>
+where
+    Bufferer: http_body_request_validator::Bufferer<Body, Buffered = BuffererBuffered>,
+{
+    http_body_request_validator::BufferingValidator::new(bufferer)
+        .with_buffered_to_out_body::<CustomBufferedToBody>()
+}
+
 /// Validate the [`axum`] request.
 pub async fn validate<Validator>(
     validator: Validator,
@@ -42,8 +54,83 @@ pub async fn validate<Validator>(
 where
     Validator: http_request_validator::Validator<Data>,
 {
-    http_body_request_validator::BufferingValidator::new(Bufferer::new())
-        .with_buffered_to_out_body::<CustomBufferedToBody>()
+    validate_with_observer(validator, (), req).await
+}
+
+/// Validate the [`axum`] request, reporting the progress to the given observer.
+pub async fn validate_with_observer<Validator, Observer>(
+    validator: Validator,
+    observer: Observer,
+    req: axum::http::Request<Body>,
+) -> Result<axum::http::Request<Body>, Error<Validator::Error>>
+where
+    Validator: http_request_validator::Validator<Data>,
+    Observer: http_body_request_validator::Observer<Validator::Error>,
+{
+    buffering_validator(Bufferer::new())
+        .with_observer(observer)
         .validate(validator, req)
         .await
 }
+
+/// Validate the [`axum`] request, but let it through even if the validation fails.
+///
+/// See [`http_body_request_validator::BufferingValidator::validate_report_only`].
+pub async fn validate_report_only<Validator>(
+    validator: Validator,
+    req: axum::http::Request<Body>,
+) -> Result<(axum::http::Request<Body>, Result<(), Validator::Error>), axum::Error>
+where
+    Validator: http_request_validator::Validator<Data>,
+{
+    validate_report_only_with_observer(validator, (), req).await
+}
+
+/// Validate the [`axum`] request, but let it through even if the validation fails, reporting
+/// the progress to the given observer.
+pub async fn validate_report_only_with_observer<Validator, Observer>(
+    validator: Validator,
+    observer: Observer,
+    req: axum::http::Request<Body>,
+) -> Result<(axum::http::Request<Body>, Result<(), Validator::Error>), axum::Error>
+where
+    Validator: http_request_validator::Validator<Data>,
+    Observer: http_body_request_validator::Observer<Validator::Error>,
+{
+    buffering_validator(Bufferer::new())
+        .with_observer(observer)
+        .validate_report_only(validator, req)
+        .await
+}
+
+/// The [`axum`]-specific signing error type.
+pub type SigningError<S> = http_body_request_validator::SigningError<axum::Error, S>;
+
+/// Sign the [`axum`] response.
+pub async fn sign_response<Signer>(
+    signer: Signer,
+    res: axum::response::Response,
+) -> Result<axum::response::Response, SigningError<Signer::Error>>
+where
+    Signer: http_request_validator::ResponseSigner<Data>,
+{
+    sign_response_with_observer(signer, (), res).await
+}
+
+/// Sign the [`axum`] response, reporting the progress to the given observer.
+///
+/// The signing is reported to the observer as the validation.
+pub async fn sign_response_with_observer<Signer, Observer>(
+    signer: Signer,
+    observer: Observer,
+    res: axum::response::Response,
+) -> Result<axum::response::Response, SigningError<Signer::Error>>
+where
+    Signer: http_request_validator::ResponseSigner<Data>,
+    Observer: http_body_request_validator::Observer<Signer::Error, axum::http::response::Parts>,
+{
+    buffering_validator(Bufferer::new())
+        .with_observer(observer)
+        .sign_response(signer, res)
+        .await
+}
@@ -11,6 +11,15 @@ pub enum Error<B, V> {
     Validation(V),
 }
 
+/// An error that can occur while signing the request or response.
+#[derive(Debug)]
+pub enum SigningError<B, S> {
+    /// The buffering of the body failed.
+    BodyBuffering(B),
+    /// The signing failed.
+    Signing(S),
+}
+
 /// The trivial [`crate::convert::BufferedToBody`] implementation for a given bufferer.
 pub type TrivialBufferedToOutBodyFor<Bufferer, InBody> =
     crate::convert::Trivial<crate::bufferer::BufferedFor<Bufferer, InBody>>;
@@ -20,10 +29,14 @@ pub struct BufferingValidator<
     Bufferer,
     InBody,
     BufferedToOutBody = TrivialBufferedToOutBodyFor<Bufferer, InBody>,
+    Observer = (),
 > {
     /// The bufferer.
     pub bufferer: Bufferer,
 
+    /// The observer, set with [`BufferingValidator::with_observer`].
+    observer: Observer,
+
     /// The phantom data types.
     pub phantom_data: core::marker::PhantomData<(fn() -> InBody, BufferedToOutBody)>,
 }
@@ -34,34 +47,58 @@ where
     InBody: http_body::Body,
     Bufferer: crate::Bufferer<InBody>,
 {
-    /// Create a new [`BufferingValidator`] with trivial `BufferedToOutBody`.
+    /// Create a new [`BufferingValidator`] with trivial `BufferedToOutBody` and no observer.
     pub const fn new(bufferer: Bufferer) -> Self {
         Self {
             bufferer,
+            observer: (),
             phantom_data: core::marker::PhantomData,
         }
     }
 }
 
-impl<Bufferer, InBody, BufferedToOutBody> BufferingValidator<Bufferer, InBody, BufferedToOutBody>
+impl<Bufferer, InBody, BufferedToOutBody, Observer>
+    BufferingValidator<Bufferer, InBody, BufferedToOutBody, Observer>
 where
     InBody: http_body::Body,
     Bufferer: crate::Bufferer<InBody>,
 {
     /// Change the `BufferedToOutBody` type.
-    pub fn with_buffered_to_out_body<New>(self) -> BufferingValidator<Bufferer, InBody, New> {
+    pub fn with_buffered_to_out_body<New>(
+        self,
+    ) -> BufferingValidator<Bufferer, InBody, New, Observer> {
         let Self {
             bufferer,
+            observer,
             phantom_data: _,
         } = self;
         BufferingValidator {
             bufferer,
+            observer,
             phantom_data: core::marker::PhantomData,
         }
     }
+
+    /// Set the [`crate::Observer`].
+    pub fn with_observer<New>(
+        self,
+        observer: New,
+    ) -> BufferingValidator<Bufferer, InBody, BufferedToOutBody, New> {
+        let Self {
+            bufferer,
+            observer: _,
+            phantom_data,
+        } = self;
+        BufferingValidator {
+            bufferer,
+            observer,
+            phantom_data,
+        }
+    }
 }
 
-impl<Bufferer, InBody, BufferedToOutBody> BufferingValidator<Bufferer, InBody, BufferedToOutBody>
+impl<Bufferer, InBody, BufferedToOutBody, Observer>
+    BufferingValidator<Bufferer, InBody, BufferedToOutBody, Observer>
 where
     InBody: http_body::Body,
     Bufferer: crate::Bufferer<InBody>,
@@ -73,6 +110,9 @@ where
     /// Takes the `InBody` out of the request, buffers it, validates the buffered body data using
     /// the specified validator, and then converts the buffered stuff with `BufferedToOutBody` to
     /// get the `BufferedToOutBody::Body` type.
+    ///
+    /// The request head is checked with [`http_request_validator::Validator::pre_validate`]
+    /// first, and if that fails, the request is rejected without reading the body.
     pub async fn validate<Validator>(
         &self,
         validator: Validator,
@@ -80,22 +120,253 @@ where
     ) -> Result<http::Request<BufferedToOutBody::Body>, Error<Bufferer::Error, Validator::Error>>
     where
         Validator: http_request_validator::Validator<crate::bufferer::DataFor<Bufferer, InBody>>,
+        Observer: crate::Observer<Validator::Error>,
+    {
+        self.validate_local(http_request_validator::AsLocal(validator), req)
+            .await
+    }
+
+    /// Validate the given request with a [`http_request_validator::LocalValidator`].
+    ///
+    /// The same as [`Self::validate`], but the returned future is not required to be [`Send`].
+    pub async fn validate_local<Validator>(
+        &self,
+        validator: Validator,
+        req: http::Request<InBody>,
+    ) -> Result<http::Request<BufferedToOutBody::Body>, Error<Bufferer::Error, Validator::Error>>
+    where
+        Validator:
+            http_request_validator::LocalValidator<crate::bufferer::DataFor<Bufferer, InBody>>,
+        Observer: crate::Observer<Validator::Error>,
+    {
+        let (mut parts, body) = req.into_parts();
+        if let Err(error) = Self::pre_validate(&validator, &mut parts) {
+            self.observer.pre_validation_failed(&parts, &error);
+            return Err(Error::Validation(error));
+        }
+
+        let (req, result) = self
+            .buffer_and_validate(validator, parts, body, Ok(()))
+            .await
+            .map_err(Error::BodyBuffering)?;
+        result.map_err(Error::Validation)?;
+        Ok(req)
+    }
+
+    /// Validate the given request, but let it through even if the validation fails.
+    ///
+    /// Useful for rolling out the new validation logic: the request is returned along with the
+    /// validation result, so the failures can be recorded without rejecting the request.
+    /// The body buffering errors are still returned as errors, as the body is lost then.
+    ///
+    /// The body is buffered even if the pre-validation fails, so the request can be let through,
+    /// and the pre-validation error is reported as the validation result.
+    pub async fn validate_report_only<Validator>(
+        &self,
+        validator: Validator,
+        req: http::Request<InBody>,
+    ) -> Result<
+        (
+            http::Request<BufferedToOutBody::Body>,
+            Result<(), Validator::Error>,
+        ),
+        Bufferer::Error,
+    >
+    where
+        Validator: http_request_validator::Validator<crate::bufferer::DataFor<Bufferer, InBody>>,
+        Observer: crate::Observer<Validator::Error>,
+    {
+        let validator = http_request_validator::AsLocal(validator);
+        let (mut parts, body) = req.into_parts();
+        let pre_validation = Self::pre_validate(&validator, &mut parts);
+        self.buffer_and_validate(validator, parts, body, pre_validation)
+            .await
+    }
+
+    /// Check the request head before the body is buffered.
+    fn pre_validate<Validator>(
+        validator: &Validator,
+        parts: &mut http::request::Parts,
+    ) -> Result<(), Validator::Error>
+    where
+        Validator:
+            http_request_validator::LocalValidator<crate::bufferer::DataFor<Bufferer, InBody>>,
+    {
+        let pre_validate = |parts: &mut http::request::Parts| validator.pre_validate(parts);
+        #[cfg(feature = "tracing")]
+        let pre_validate = |parts: &mut http::request::Parts| {
+            crate::trace::pre_validate::<Validator, _>(|| pre_validate(parts))
+        };
+        pre_validate(parts)
+    }
+
+    /// Buffer and validate the given request, returning the validation result along with it.
+    ///
+    /// The validation is skipped if the pre-validation has failed, and its error is returned
+    /// instead.
+    async fn buffer_and_validate<Validator>(
+        &self,
+        validator: Validator,
+        parts: http::request::Parts,
+        body: InBody,
+        pre_validation: Result<(), Validator::Error>,
+    ) -> Result<
+        (
+            http::Request<BufferedToOutBody::Body>,
+            Result<(), Validator::Error>,
+        ),
+        Bufferer::Error,
+    >
+    where
+        Validator:
+            http_request_validator::LocalValidator<crate::bufferer::DataFor<Bufferer, InBody>>,
+        Observer: crate::Observer<Validator::Error>,
+    {
+        let (parts, body, result) = self
+            .buffer_and_run(parts, body, |parts, buffered| async move {
+                let result = match pre_validation {
+                    Ok(()) => {
+                        let validation = validator.validate(&parts, buffered.as_buf());
+                        #[cfg(feature = "tracing")]
+                        let validation = crate::trace::validate::<Validator, _>(validation);
+                        validation.await
+                    }
+                    Err(error) => Err(error),
+                };
+                (parts, buffered, result)
+            })
+            .await?;
+        Ok((http::Request::from_parts(parts, body), result))
+    }
+
+    /// Sign the given request with a [`http_request_validator::Signer`].
+    ///
+    /// Takes the `InBody` out of the request, buffers it, signs the request using the buffered
+    /// body data, and then converts the buffered stuff with `BufferedToOutBody`.
+    /// The signing is reported to the observer as the validation.
+    pub async fn sign<Signer>(
+        &self,
+        signer: Signer,
+        req: http::Request<InBody>,
+    ) -> Result<http::Request<BufferedToOutBody::Body>, SigningError<Bufferer::Error, Signer::Error>>
+    where
+        Signer: http_request_validator::Signer<crate::bufferer::DataFor<Bufferer, InBody>>,
+        Observer: crate::Observer<Signer::Error>,
     {
         let (parts, body) = req.into_parts();
+        let (parts, body, result) = self
+            .buffer_and_run(parts, body, |mut parts, buffered| async move {
+                let result = signer.sign(&mut parts, buffered.as_buf()).await;
+                (parts, buffered, result)
+            })
+            .await
+            .map_err(SigningError::BodyBuffering)?;
+        result.map_err(SigningError::Signing)?;
+        Ok(http::Request::from_parts(parts, body))
+    }
 
-        let buffered = self
-            .bufferer
-            .buffer(body)
+    /// Validate the given response with a [`http_request_validator::ResponseValidator`].
+    ///
+    /// The same as [`Self::validate`], but for the responses, so the observer gets the response
+    /// head.
+    pub async fn validate_response<Validator>(
+        &self,
+        validator: Validator,
+        res: http::Response<InBody>,
+    ) -> Result<http::Response<BufferedToOutBody::Body>, Error<Bufferer::Error, Validator::Error>>
+    where
+        Validator:
+            http_request_validator::ResponseValidator<crate::bufferer::DataFor<Bufferer, InBody>>,
+        Observer: crate::Observer<Validator::Error, http::response::Parts>,
+    {
+        let (parts, body) = res.into_parts();
+        let (parts, body, result) = self
+            .buffer_and_run(parts, body, |parts, buffered| async move {
+                let result = validator.validate(&parts, buffered.as_buf()).await;
+                (parts, buffered, result)
+            })
             .await
             .map_err(Error::BodyBuffering)?;
+        result.map_err(Error::Validation)?;
+        Ok(http::Response::from_parts(parts, body))
+    }
 
-        validator
-            .validate(&parts, buffered.as_buf())
+    /// Sign the given response with a [`http_request_validator::ResponseSigner`].
+    ///
+    /// The same as [`Self::sign`], but for the responses, so the observer gets the response
+    /// head.
+    pub async fn sign_response<Signer>(
+        &self,
+        signer: Signer,
+        res: http::Response<InBody>,
+    ) -> Result<http::Response<BufferedToOutBody::Body>, SigningError<Bufferer::Error, Signer::Error>>
+    where
+        Signer: http_request_validator::ResponseSigner<crate::bufferer::DataFor<Bufferer, InBody>>,
+        Observer: crate::Observer<Signer::Error, http::response::Parts>,
+    {
+        let (parts, body) = res.into_parts();
+        let (parts, body, result) = self
+            .buffer_and_run(parts, body, |mut parts, buffered| async move {
+                let result = signer.sign(&mut parts, buffered.as_buf()).await;
+                (parts, buffered, result)
+            })
             .await
-            .map_err(Error::Validation)?;
+            .map_err(SigningError::BodyBuffering)?;
+        result.map_err(SigningError::Signing)?;
+        Ok(http::Response::from_parts(parts, body))
+    }
 
-        let req = http::Request::from_parts(parts, BufferedToOutBody::buffered_to_body(buffered));
+    /// Buffer the given body and run the step over it and the head, notifying the observer.
+    ///
+    /// The step takes the head and the buffered body and gives them back along with its result,
+    /// so its future can borrow them and still be [`Send`] when they are. The buffered body is
+    /// converted with `BufferedToOutBody` afterwards.
+    async fn buffer_and_run<Head, StepError, Step, StepFuture>(
+        &self,
+        head: Head,
+        body: InBody,
+        step: Step,
+    ) -> Result<(Head, BufferedToOutBody::Body, Result<(), StepError>), Bufferer::Error>
+    where
+        Observer: crate::Observer<StepError, Head>,
+        Step: FnOnce(Head, crate::bufferer::BufferedFor<Bufferer, InBody>) -> StepFuture,
+        StepFuture: core::future::Future<
+            Output = (
+                Head,
+                crate::bufferer::BufferedFor<Bufferer, InBody>,
+                Result<(), StepError>,
+            ),
+        >,
+    {
+        let mut observation = crate::observer::Observation::start(&self.observer, &head);
 
-        Ok(req)
+        let buffered = match self.buffer(body).await {
+            Ok(buffered) => {
+                let body_size = bytes::Buf::remaining(buffered.as_buf());
+                observation
+                    .buffering_finished(crate::observer::BufferingOutcome::Buffered { body_size });
+                buffered
+            }
+            Err(error) => {
+                observation.buffering_finished(crate::observer::BufferingOutcome::Failed);
+                return Err(error);
+            }
+        };
+
+        let (head, buffered, result) = step(head, buffered).await;
+        observation.validation_finished(result.as_ref().map(|_| ()));
+
+        Ok((head, BufferedToOutBody::buffered_to_body(buffered), result))
+    }
+
+    /// Buffer the given body.
+    async fn buffer(
+        &self,
+        body: InBody,
+    ) -> Result<crate::bufferer::BufferedFor<Bufferer, InBody>, Bufferer::Error> {
+        let buffering = self.bufferer.buffer(body);
+        #[cfg(feature = "tracing")]
+        let buffering = crate::trace::buffer(buffering);
+        buffering.await
     }
 }
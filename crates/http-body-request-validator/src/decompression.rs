@@ -0,0 +1,236 @@
+//! Decompression of the buffered bodies according to the `Content-Encoding`.
+//!
+//! Some senders sign the compressed bytes, and others sign the decompressed ones.
+//! The validators get the raw bytes by default; wrap the ones that want the decoded bytes into
+//! [`Decoded`]. Only the validators see the decoded bytes then: the request is passed on with
+//! the raw body, so decode it downstream of the validation if the handlers need it decoded.
+//! The integrations that decode the body while buffering it give the decoded bytes to all
+//! the validators and to the handlers; the choice between the raw and the decoded bytes is
+//! per integration then, not per validator.
+//!
+//! Supports `gzip`, `deflate`, `br` and `zstd`, including the stacked encodings.
+//! The decoded size is limited to protect against the decompression bombs.
+
+use std::io::Read as _;
+
+use bytes::Bytes;
+
+/// The default limit of the decoded body size, in bytes.
+pub const DEFAULT_MAX_DECODED_SIZE: usize = 10 * 1024 * 1024;
+
+/// An error that can occur while decoding the body.
+#[derive(Debug)]
+pub enum Error {
+    /// The content coding is not supported.
+    UnsupportedEncoding(http::HeaderValue),
+    /// The decoded body exceeds the limit.
+    TooLarge {
+        /// The limit of the decoded body size, in bytes.
+        max_decoded_size: usize,
+    },
+    /// The body can not be decoded.
+    Corrupt(std::io::Error),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnsupportedEncoding(encoding) => {
+                write!(f, "unsupported content encoding: {encoding:?}")
+            }
+            Self::TooLarge { max_decoded_size } => {
+                write!(f, "decoded body exceeds {max_decoded_size} bytes")
+            }
+            Self::Corrupt(error) => write!(f, "corrupt body: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Corrupt(error) => Some(error),
+            Self::UnsupportedEncoding(_) | Self::TooLarge { .. } => None,
+        }
+    }
+}
+
+impl http_request_validator::ValidationErrorKind for Error {
+    fn kind(&self) -> http_request_validator::ErrorKind {
+        match self {
+            Self::TooLarge { .. } => http_request_validator::ErrorKind::TooLarge,
+            Self::UnsupportedEncoding(_) => http_request_validator::ErrorKind::UnsupportedMediaType,
+            Self::Corrupt(_) => http_request_validator::ErrorKind::Malformed,
+        }
+    }
+}
+
+/// Whether the headers declare any content coding other than `identity`.
+pub fn is_encoded(headers: &http::HeaderMap) -> bool {
+    encodings(headers)
+        .iter()
+        .any(|encoding| !encoding.eq_ignore_ascii_case(b"identity"))
+}
+
+/// The content codings from the `Content-Encoding` headers, in the order they were applied.
+fn encodings(headers: &http::HeaderMap) -> std::vec::Vec<&[u8]> {
+    headers
+        .get_all(http::header::CONTENT_ENCODING)
+        .iter()
+        .flat_map(|value| value.as_bytes().split(|&c| c == b','))
+        .map(<[u8]>::trim_ascii)
+        .filter(|encoding| !encoding.is_empty())
+        .collect()
+}
+
+/// Decode the body according to the `Content-Encoding` headers.
+///
+/// Returns the body as is if it is not encoded.
+pub fn decode(
+    headers: &http::HeaderMap,
+    body: &Bytes,
+    max_decoded_size: usize,
+) -> Result<Bytes, Error> {
+    let mut decoded = body.clone();
+    for encoding in encodings(headers).into_iter().rev() {
+        decoded = decode_one(encoding, &decoded, max_decoded_size)?;
+    }
+    Ok(decoded)
+}
+
+/// Decode the data encoded with a single content coding.
+fn decode_one(encoding: &[u8], data: &Bytes, max_decoded_size: usize) -> Result<Bytes, Error> {
+    let reader: std::boxed::Box<dyn std::io::Read + '_> = match &encoding.to_ascii_lowercase()[..] {
+        b"identity" => return Ok(data.clone()),
+        b"gzip" | b"x-gzip" => std::boxed::Box::new(flate2::read::MultiGzDecoder::new(&data[..])),
+        b"deflate" => std::boxed::Box::new(flate2::read::ZlibDecoder::new(&data[..])),
+        b"br" => std::boxed::Box::new(brotli::Decompressor::new(&data[..], 4096)),
+        b"zstd" => std::boxed::Box::new(
+            zstd::stream::read::Decoder::with_buffer(&data[..]).map_err(Error::Corrupt)?,
+        ),
+        other => {
+            let other = http::HeaderValue::from_bytes(other)
+                .unwrap_or_else(|_| http::HeaderValue::from_static("<invalid>"));
+            return Err(Error::UnsupportedEncoding(other));
+        }
+    };
+
+    // Read one byte past the limit to tell the exactly fitting body from the exceeding one.
+    let limit = u64::try_from(max_decoded_size)
+        .unwrap_or(u64::MAX)
+        .saturating_add(1);
+    let mut decoded = std::vec::Vec::new();
+    reader
+        .take(limit)
+        .read_to_end(&mut decoded)
+        .map_err(Error::Corrupt)?;
+    if decoded.len() > max_decoded_size {
+        return Err(Error::TooLarge { max_decoded_size });
+    }
+    Ok(decoded.into())
+}
+
+/// Adjust the headers to describe the decoded body of the given length.
+///
+/// Removes the `Content-Encoding` and sets the `Content-Length`.
+pub fn adjust_headers(headers: &mut http::HeaderMap, decoded_len: usize) {
+    headers.remove(http::header::CONTENT_ENCODING);
+    headers.insert(http::header::CONTENT_LENGTH, decoded_len.into());
+}
+
+/// The validator that validates the decoded body.
+///
+/// Decodes the buffered body according to the `Content-Encoding` and runs the inner validator
+/// over the decoded bytes. The request head is passed as is.
+///
+/// The decoding runs synchronously, so this is a [`http_request_validator::SyncValidator`]
+/// over a [`http_request_validator::SyncValidator`]. Wrapped into
+/// [`http_request_validator::AsAsync`], it decodes on the async executor thread, blocking it
+/// for as long as the decoding takes; keep the `max_decoded_size` low there, or decode on
+/// the blocking thread pool in the integration, like the axum layer does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Decoded<V> {
+    /// The validator to run over the decoded body.
+    pub inner: V,
+
+    /// The limit of the decoded body size, in bytes.
+    pub max_decoded_size: usize,
+}
+
+impl<V> Decoded<V> {
+    /// Create a new [`Decoded`] validator with the [`DEFAULT_MAX_DECODED_SIZE`].
+    pub const fn new(inner: V) -> Self {
+        Self {
+            inner,
+            max_decoded_size: DEFAULT_MAX_DECODED_SIZE,
+        }
+    }
+
+    /// Set the limit of the decoded body size, in bytes.
+    pub const fn with_max_decoded_size(mut self, max_decoded_size: usize) -> Self {
+        self.max_decoded_size = max_decoded_size;
+        self
+    }
+}
+
+/// An error that can occur while validating the decoded body.
+#[derive(Debug)]
+pub enum DecodedError<E> {
+    /// The body can not be decoded.
+    Decompression(Error),
+    /// The validation of the decoded body failed.
+    Validation(E),
+}
+
+impl<E: core::fmt::Display> core::fmt::Display for DecodedError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Decompression(error) => error.fmt(f),
+            Self::Validation(error) => error.fmt(f),
+        }
+    }
+}
+
+impl<E> http_request_validator::ValidationErrorKind for DecodedError<E>
+where
+    E: http_request_validator::ValidationErrorKind,
+{
+    fn kind(&self) -> http_request_validator::ErrorKind {
+        match self {
+            Self::Decompression(error) => error.kind(),
+            Self::Validation(error) => error.kind(),
+        }
+    }
+
+    fn www_authenticate(&self) -> Option<http::HeaderValue> {
+        match self {
+            Self::Decompression(_) => None,
+            Self::Validation(error) => error.www_authenticate(),
+        }
+    }
+}
+
+impl<V> http_request_validator::SyncValidator<Bytes> for Decoded<V>
+where
+    V: http_request_validator::SyncValidator<Bytes>,
+{
+    type Error = DecodedError<V::Error>;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Bytes,
+    ) -> Result<(), Self::Error> {
+        let decoded = decode(&parts.headers, buffered_body, self.max_decoded_size)
+            .map_err(DecodedError::Decompression)?;
+        self.inner
+            .validate(parts, &decoded)
+            .map_err(DecodedError::Validation)
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        self.inner
+            .pre_validate(parts)
+            .map_err(DecodedError::Validation)
+    }
+}
@@ -5,18 +5,32 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(any(feature = "decompression", feature = "tracing"))]
+extern crate std;
+
 pub mod as_buf;
 pub mod bufferer;
 pub mod buffering_validator;
 pub mod convert;
+pub mod observer;
 
 #[cfg(feature = "buffered")]
 pub mod buffered;
 
+#[cfg(feature = "decompression")]
+pub mod decompression;
+
 #[cfg(feature = "http-body-util")]
 pub mod http_body_util;
 
+#[cfg(feature = "tracing")]
+mod trace;
+
 pub use self::as_buf::AsBuf;
 pub use self::bufferer::Bufferer;
+pub use self::observer::Observer;
 
 pub use self::buffering_validator::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,145 @@
+//! [`Observer`] trait for hooking into the buffering and validation.
+
+/// The outcome of the body buffering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BufferingOutcome {
+    /// The body was buffered.
+    Buffered {
+        /// The size of the buffered body, in bytes.
+        body_size: usize,
+    },
+
+    /// The body buffering failed.
+    Failed,
+}
+
+/// The hooks invoked by the [`crate::BufferingValidator`] as it processes a request.
+///
+/// Useful for collecting metrics. The hooks get the request head, or, with `Head` set to
+/// [`http::response::Parts`], the response head for the responses; the signing is reported as
+/// the validation.
+/// The hooks are called in order: [`Self::buffering_started`], [`Self::buffering_finished`]
+/// and, if the buffering succeeded, [`Self::validation_finished`]. If the request is rejected
+/// by the pre-validation, before the body is buffered, only [`Self::pre_validation_failed`]
+/// is called. If the request is dropped midway, [`Self::cancelled`] is called instead of
+/// the remaining hooks.
+pub trait Observer<ValidationError, Head = http::request::Parts> {
+    /// The per-request state carried between the hooks.
+    type State;
+
+    /// The pre-validation has rejected the request, the body is not going to be buffered.
+    ///
+    /// Does nothing by default.
+    fn pre_validation_failed(&self, head: &Head, error: &ValidationError) {
+        let _ = (head, error);
+    }
+
+    /// The body buffering is about to start.
+    fn buffering_started(&self, head: &Head) -> Self::State;
+
+    /// The body buffering has finished.
+    fn buffering_finished(&self, state: &mut Self::State, outcome: BufferingOutcome);
+
+    /// The validation has finished.
+    fn validation_finished(&self, state: Self::State, result: Result<(), &ValidationError>);
+
+    /// The request was dropped before the buffering or the validation has finished, for
+    /// instance because the client has gone away.
+    ///
+    /// Does nothing by default.
+    fn cancelled(&self, state: Self::State) {
+        let _ = state;
+    }
+}
+
+/// The no-op observer.
+impl<ValidationError, Head> Observer<ValidationError, Head> for () {
+    type State = ();
+
+    fn buffering_started(&self, _head: &Head) -> Self::State {}
+
+    fn buffering_finished(&self, _state: &mut Self::State, _outcome: BufferingOutcome) {}
+
+    fn validation_finished(&self, _state: Self::State, _result: Result<(), &ValidationError>) {}
+}
+
+impl<T, ValidationError, Head> Observer<ValidationError, Head> for &T
+where
+    T: Observer<ValidationError, Head> + ?Sized,
+{
+    type State = T::State;
+
+    fn pre_validation_failed(&self, head: &Head, error: &ValidationError) {
+        (**self).pre_validation_failed(head, error);
+    }
+
+    fn buffering_started(&self, head: &Head) -> Self::State {
+        (**self).buffering_started(head)
+    }
+
+    fn buffering_finished(&self, state: &mut Self::State, outcome: BufferingOutcome) {
+        (**self).buffering_finished(state, outcome);
+    }
+
+    fn validation_finished(&self, state: Self::State, result: Result<(), &ValidationError>) {
+        (**self).validation_finished(state, result);
+    }
+
+    fn cancelled(&self, state: Self::State) {
+        (**self).cancelled(state);
+    }
+}
+
+/// The observation of a request in progress, telling the observer it was cancelled if dropped
+/// before finishing.
+pub(crate) struct Observation<'a, Observer, ValidationError, Head>
+where
+    Observer: self::Observer<ValidationError, Head>,
+{
+    /// The observer.
+    observer: &'a Observer,
+
+    /// The observer state, taken once the observation is finished.
+    state: Option<Observer::State>,
+}
+
+impl<'a, Observer, ValidationError, Head> Observation<'a, Observer, ValidationError, Head>
+where
+    Observer: self::Observer<ValidationError, Head>,
+{
+    /// Start observing the request, as its body buffering is about to start.
+    pub(crate) fn start(observer: &'a Observer, head: &Head) -> Self {
+        Self {
+            observer,
+            state: Some(observer.buffering_started(head)),
+        }
+    }
+
+    /// The body buffering has finished; the observation is finished too if it has failed.
+    pub(crate) fn buffering_finished(&mut self, outcome: BufferingOutcome) {
+        if let Some(state) = &mut self.state {
+            self.observer.buffering_finished(state, outcome);
+        }
+        if outcome == BufferingOutcome::Failed {
+            self.state = None;
+        }
+    }
+
+    /// The validation has finished, and so has the observation.
+    pub(crate) fn validation_finished(mut self, result: Result<(), &ValidationError>) {
+        if let Some(state) = self.state.take() {
+            self.observer.validation_finished(state, result);
+        }
+    }
+}
+
+impl<Observer, ValidationError, Head> Drop for Observation<'_, Observer, ValidationError, Head>
+where
+    Observer: self::Observer<ValidationError, Head>,
+{
+    fn drop(&mut self) {
+        if let Some(state) = self.state.take() {
+            self.observer.cancelled(state);
+        }
+    }
+}
@@ -0,0 +1,404 @@
+//! The tests for the buffering validation logic.
+
+extern crate std;
+
+use std::{rc::Rc, string::String, vec::Vec};
+
+#[derive(Debug, Clone, Default)]
+struct LocalRecordingValidator {
+    seen: Rc<core::cell::RefCell<Vec<bytes::Bytes>>>,
+}
+
+impl http_request_validator::LocalValidator<bytes::Bytes> for LocalRecordingValidator {
+    type Error = String;
+
+    async fn validate<'a>(
+        &'a self,
+        _parts: &'a http::request::Parts,
+        buffered_body: &'a bytes::Bytes,
+    ) -> Result<(), Self::Error> {
+        self.seen.borrow_mut().push(buffered_body.clone());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn validate_local() {
+    let validator = LocalRecordingValidator::default();
+
+    let req = http::Request::new(http_body_util::Full::new(bytes::Bytes::from_static(
+        b"payload",
+    )));
+
+    let buffering_validator =
+        crate::BufferingValidator::new(crate::http_body_util::Bufferer::<bytes::Bytes>::new());
+    let _req = buffering_validator
+        .validate_local(&validator, req)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        &*validator.seen.borrow(),
+        &[bytes::Bytes::from_static(b"payload")]
+    );
+}
+
+#[derive(Debug, Clone, Copy)]
+struct NonEmptyValidator;
+
+impl<Data: bytes::Buf> http_request_validator::SyncValidator<Data> for NonEmptyValidator {
+    type Error = &'static str;
+
+    fn validate(
+        &self,
+        _parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        if !buffered_body.has_remaining() {
+            return Err("body is empty");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn validate_sync() {
+    let buffering_validator =
+        crate::BufferingValidator::new(crate::http_body_util::Bufferer::<bytes::Bytes>::new());
+    let validator = http_request_validator::AsAsync(NonEmptyValidator);
+
+    let req = http::Request::new(http_body_util::Full::new(bytes::Bytes::from_static(
+        b"payload",
+    )));
+    let _req = buffering_validator.validate(validator, req).await.unwrap();
+
+    let req = http::Request::new(http_body_util::Full::new(bytes::Bytes::new()));
+    let result = buffering_validator.validate(validator, req).await;
+    assert!(matches!(
+        result,
+        Err(crate::Error::Validation("body is empty"))
+    ));
+}
+
+/// Requires the `x-signature` header, before the body is buffered.
+#[derive(Debug, Clone, Copy)]
+struct SignaturePresentValidator;
+
+impl<Data: bytes::Buf> http_request_validator::SyncValidator<Data> for SignaturePresentValidator {
+    type Error = &'static str;
+
+    fn validate(
+        &self,
+        _parts: &http::request::Parts,
+        _buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        if !parts.headers.contains_key("x-signature") {
+            return Err("missing signature");
+        }
+        Ok(())
+    }
+}
+
+/// Records the names of the hooks called.
+#[derive(Debug, Default)]
+struct RecordingObserver {
+    hooks: core::cell::RefCell<Vec<&'static str>>,
+}
+
+impl<ValidationError, Head> crate::Observer<ValidationError, Head> for RecordingObserver {
+    type State = ();
+
+    fn pre_validation_failed(&self, _head: &Head, _error: &ValidationError) {
+        self.hooks.borrow_mut().push("pre_validation_failed");
+    }
+
+    fn buffering_started(&self, _head: &Head) -> Self::State {
+        self.hooks.borrow_mut().push("buffering_started");
+    }
+
+    fn buffering_finished(
+        &self,
+        _state: &mut Self::State,
+        _outcome: crate::observer::BufferingOutcome,
+    ) {
+        self.hooks.borrow_mut().push("buffering_finished");
+    }
+
+    fn validation_finished(&self, _state: Self::State, _result: Result<(), &ValidationError>) {
+        self.hooks.borrow_mut().push("validation_finished");
+    }
+
+    fn cancelled(&self, _state: Self::State) {
+        self.hooks.borrow_mut().push("cancelled");
+    }
+}
+
+/// The body that never yields anything.
+struct PendingBody;
+
+impl http_body::Body for PendingBody {
+    type Data = bytes::Bytes;
+    type Error = core::convert::Infallible;
+
+    fn poll_frame(
+        self: core::pin::Pin<&mut Self>,
+        _cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        core::task::Poll::Pending
+    }
+}
+
+#[test]
+fn observer_cancelled() {
+    let observer = RecordingObserver::default();
+    let buffering_validator =
+        crate::BufferingValidator::new(crate::http_body_util::Bufferer::<bytes::Bytes>::new())
+            .with_observer(&observer);
+    let validator = http_request_validator::AsAsync(NonEmptyValidator);
+
+    {
+        let validation = buffering_validator.validate(validator, http::Request::new(PendingBody));
+        let mut validation = core::pin::pin!(validation);
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        assert!(core::future::Future::poll(validation.as_mut(), &mut cx).is_pending());
+    }
+
+    assert_eq!(observer.hooks.take(), ["buffering_started", "cancelled"]);
+}
+
+#[tokio::test]
+async fn pre_validate() {
+    let observer = RecordingObserver::default();
+    let buffering_validator =
+        crate::BufferingValidator::new(crate::http_body_util::Bufferer::<bytes::Bytes>::new())
+            .with_observer(&observer);
+    let validator = http_request_validator::AsAsync(SignaturePresentValidator);
+    let req = || {
+        http::Request::new(http_body_util::Full::new(bytes::Bytes::from_static(
+            b"payload",
+        )))
+    };
+
+    // Rejected without buffering the body.
+    let result = buffering_validator.validate(validator, req()).await;
+    assert!(matches!(
+        result,
+        Err(crate::Error::Validation("missing signature"))
+    ));
+    assert_eq!(observer.hooks.take(), ["pre_validation_failed"]);
+
+    let mut signed = req();
+    signed
+        .headers_mut()
+        .insert("x-signature", "00".parse().unwrap());
+    let _req = buffering_validator
+        .validate(validator, signed)
+        .await
+        .unwrap();
+    assert_eq!(
+        observer.hooks.take(),
+        [
+            "buffering_started",
+            "buffering_finished",
+            "validation_finished"
+        ]
+    );
+
+    // The report-only mode still buffers the body to let the request through.
+    let (req, result) = buffering_validator
+        .validate_report_only(validator, req())
+        .await
+        .unwrap();
+    assert_eq!(result, Err("missing signature"));
+    assert_eq!(
+        http_body_util::BodyExt::collect(req.into_body())
+            .await
+            .unwrap()
+            .to_bytes(),
+        "payload"
+    );
+    assert_eq!(
+        observer.hooks.take(),
+        [
+            "buffering_started",
+            "buffering_finished",
+            "validation_finished"
+        ]
+    );
+}
+
+/// Puts the body length into the `x-body-length` response header, and checks it.
+#[derive(Debug, Clone, Copy)]
+struct ResponseBodyLength;
+
+impl<Data: bytes::Buf + Sync> http_request_validator::ResponseSigner<Data> for ResponseBodyLength {
+    type Error = core::convert::Infallible;
+
+    async fn sign<'a>(
+        &'a self,
+        parts: &'a mut http::response::Parts,
+        buffered_body: &'a Data,
+    ) -> Result<(), Self::Error> {
+        parts
+            .headers
+            .insert("x-body-length", buffered_body.remaining().into());
+        Ok(())
+    }
+}
+
+impl<Data: bytes::Buf + Sync> http_request_validator::ResponseValidator<Data>
+    for ResponseBodyLength
+{
+    type Error = &'static str;
+
+    async fn validate<'a>(
+        &'a self,
+        parts: &'a http::response::Parts,
+        buffered_body: &'a Data,
+    ) -> Result<(), Self::Error> {
+        let expected = http::HeaderValue::from(buffered_body.remaining());
+        if parts.headers.get("x-body-length") != Some(&expected) {
+            return Err("length mismatch");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn sign_and_validate_response() {
+    let observer = RecordingObserver::default();
+    let buffering_validator =
+        crate::BufferingValidator::new(crate::http_body_util::Bufferer::<bytes::Bytes>::new())
+            .with_observer(&observer);
+
+    let res = http::Response::new(http_body_util::Full::new(bytes::Bytes::from_static(
+        b"payload",
+    )));
+    let result = buffering_validator
+        .validate_response(ResponseBodyLength, res)
+        .await;
+    assert!(matches!(
+        result,
+        Err(crate::Error::Validation("length mismatch"))
+    ));
+
+    let res = http::Response::new(http_body_util::Full::new(bytes::Bytes::from_static(
+        b"payload",
+    )));
+    let res = buffering_validator
+        .sign_response(ResponseBodyLength, res)
+        .await
+        .unwrap();
+    assert_eq!(res.headers()["x-body-length"], "7");
+
+    // Both the validation and the signing are observed.
+    assert_eq!(
+        observer.hooks.take(),
+        [
+            "buffering_started",
+            "buffering_finished",
+            "validation_finished",
+            "buffering_started",
+            "buffering_finished",
+            "validation_finished"
+        ]
+    );
+
+    let result =
+        crate::BufferingValidator::new(crate::http_body_util::Bufferer::<bytes::Bytes>::new())
+            .validate_response(ResponseBodyLength, res)
+            .await;
+    assert!(result.is_ok());
+}
+
+#[cfg(feature = "decompression")]
+fn gzip(data: &[u8]) -> Vec<u8> {
+    use std::io::Write as _;
+
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+#[cfg(feature = "decompression")]
+#[test]
+fn decompression_decode() {
+    use crate::decompression::{decode, Error};
+
+    let headers = |encoding: &'static str| {
+        let mut headers = http::HeaderMap::new();
+        headers.insert(
+            http::header::CONTENT_ENCODING,
+            http::HeaderValue::from_static(encoding),
+        );
+        headers
+    };
+    let payload = b"{\"event\":\"ping\"}";
+
+    let gzipped = bytes::Bytes::from(gzip(payload));
+    assert_eq!(
+        decode(&headers("gzip"), &gzipped, 1024).unwrap(),
+        &payload[..]
+    );
+
+    let mut brotli = Vec::new();
+    brotli::BrotliCompress(&mut &gzipped[..], &mut brotli, &Default::default()).unwrap();
+    let stacked = bytes::Bytes::from(brotli);
+    assert_eq!(
+        decode(&headers("gzip, br"), &stacked, 1024).unwrap(),
+        &payload[..]
+    );
+
+    let zstd = bytes::Bytes::from(zstd::encode_all(&payload[..], 0).unwrap());
+    assert_eq!(decode(&headers("zstd"), &zstd, 1024).unwrap(), &payload[..]);
+
+    let identity = bytes::Bytes::from_static(payload);
+    assert_eq!(
+        decode(&http::HeaderMap::new(), &identity, 1).unwrap(),
+        &payload[..]
+    );
+
+    let bomb = bytes::Bytes::from(gzip(&[0; 64 * 1024]));
+    assert!(matches!(
+        decode(&headers("gzip"), &bomb, 1024),
+        Err(Error::TooLarge {
+            max_decoded_size: 1024
+        })
+    ));
+
+    let error = decode(&headers("compress"), &identity, 1024).unwrap_err();
+    assert!(matches!(error, Error::UnsupportedEncoding(_)));
+    assert_eq!(
+        http_request_validator::ValidationErrorKind::kind(&error),
+        http_request_validator::ErrorKind::UnsupportedMediaType
+    );
+    assert!(matches!(
+        decode(&headers("gzip"), &identity, 1024),
+        Err(Error::Corrupt(_))
+    ));
+}
+
+#[cfg(feature = "decompression")]
+#[tokio::test]
+async fn decompression_decoded_validator() {
+    let buffering_validator =
+        crate::BufferingValidator::new(crate::http_body_util::Bufferer::<bytes::Bytes>::new());
+    let validator =
+        http_request_validator::AsAsync(crate::decompression::Decoded::new(NonEmptyValidator));
+
+    let req = http::Request::builder()
+        .header(http::header::CONTENT_ENCODING, "gzip")
+        .body(http_body_util::Full::new(bytes::Bytes::from(gzip(b""))))
+        .unwrap();
+    let result = buffering_validator.validate(&validator, req).await;
+    assert!(matches!(
+        result,
+        Err(crate::Error::Validation(
+            crate::decompression::DecodedError::Validation("body is empty")
+        ))
+    ));
+}
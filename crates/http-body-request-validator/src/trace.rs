@@ -0,0 +1,107 @@
+//! [`tracing`] instrumentation of the buffering and validation.
+//!
+//! Only the body size, the validator type name, the outcome and the duration are recorded;
+//! neither the body nor the error details are, as they can carry secrets.
+
+use std::time::Instant;
+
+use tracing::Instrument as _;
+
+/// The outcome of a successful step.
+const OUTCOME_OK: &str = "ok";
+
+/// The outcome of a failed step.
+const OUTCOME_ERROR: &str = "error";
+
+/// The microseconds elapsed since the step has started, for the `duration_us` field.
+fn duration_us(started_at: Instant) -> u64 {
+    u64::try_from(started_at.elapsed().as_micros()).unwrap_or(u64::MAX)
+}
+
+/// Run the buffering within a span.
+pub(crate) async fn buffer<Buffered, Error>(
+    buffering: impl core::future::Future<Output = Result<Buffered, Error>>,
+) -> Result<Buffered, Error>
+where
+    Buffered: crate::AsBuf,
+{
+    let span = tracing::debug_span!(
+        "http_request_validator.buffer",
+        body_size = tracing::field::Empty,
+        outcome = tracing::field::Empty,
+        duration_us = tracing::field::Empty,
+    );
+
+    let started_at = Instant::now();
+    let result = buffering.instrument(span.clone()).await;
+    span.record("duration_us", duration_us(started_at));
+
+    match &result {
+        Ok(buffered) => {
+            span.record("body_size", bytes::Buf::remaining(buffered.as_buf()) as u64);
+            span.record("outcome", OUTCOME_OK);
+        }
+        Err(_) => {
+            span.record("outcome", OUTCOME_ERROR);
+            tracing::debug!(parent: &span, "request body buffering failed");
+        }
+    }
+
+    result
+}
+
+/// Run the pre-validation within a span.
+pub(crate) fn pre_validate<Validator, Error>(
+    pre_validation: impl FnOnce() -> Result<(), Error>,
+) -> Result<(), Error> {
+    let span = tracing::debug_span!(
+        "http_request_validator.pre_validate",
+        validator = core::any::type_name::<Validator>(),
+        outcome = tracing::field::Empty,
+        duration_us = tracing::field::Empty,
+    );
+
+    let started_at = Instant::now();
+    let result = span.in_scope(pre_validation);
+    span.record("duration_us", duration_us(started_at));
+
+    match &result {
+        Ok(()) => {
+            span.record("outcome", OUTCOME_OK);
+        }
+        Err(_) => {
+            span.record("outcome", OUTCOME_ERROR);
+            tracing::debug!(parent: &span, "request pre-validation failed");
+        }
+    }
+
+    result
+}
+
+/// Run the validation within a span.
+pub(crate) async fn validate<Validator, Error>(
+    validation: impl core::future::Future<Output = Result<(), Error>>,
+) -> Result<(), Error> {
+    let span = tracing::debug_span!(
+        "http_request_validator.validate",
+        validator = core::any::type_name::<Validator>(),
+        outcome = tracing::field::Empty,
+        duration_us = tracing::field::Empty,
+    );
+
+    let started_at = Instant::now();
+    let result = validation.instrument(span.clone()).await;
+    span.record("duration_us", duration_us(started_at));
+
+    match &result {
+        Ok(()) => {
+            span.record("outcome", OUTCOME_OK);
+        }
+        Err(_) => {
+            span.record("outcome", OUTCOME_ERROR);
+            tracing::debug!(parent: &span, "request validation failed");
+        }
+    }
+
+    result
+}
@@ -0,0 +1,218 @@
+//! A command line tool to verify and sign the captured HTTP requests.
+//!
+//! Reads a raw HTTP/1.1 request from a file or stdin, and either checks its signature printing
+//! a detailed diagnosis, or signs it printing the signed request.
+
+use std::{
+    io::{Read as _, Write as _},
+    process::ExitCode,
+};
+
+use clap::Parser as _;
+
+mod raw;
+
+/// Verify or sign the captured HTTP requests.
+#[derive(Debug, clap::Parser)]
+#[command(version, about)]
+struct Cli {
+    /// The command to run.
+    #[command(subcommand)]
+    command: Command,
+}
+
+/// The command to run.
+#[derive(Debug, clap::Subcommand)]
+enum Command {
+    /// Check the request signature and print the diagnosis.
+    ///
+    /// Exits with 1 if the signature is not valid.
+    Verify(Args),
+
+    /// Sign the request and print it.
+    Sign(Args),
+}
+
+/// The arguments common for all commands.
+#[derive(Debug, clap::Args)]
+struct Args {
+    /// The signature scheme.
+    #[arg(long, value_enum)]
+    scheme: SchemeName,
+
+    /// The file with the secret to sign the requests with; the trailing newline is ignored.
+    ///
+    /// If not given, the secret is taken from the `HTTP_REQUEST_VALIDATOR_SECRET` environment
+    /// variable. The secret can not be passed on the command line, as the other users of the
+    /// system can see it there.
+    #[arg(long)]
+    secret_file: Option<std::path::PathBuf>,
+
+    /// The current UNIX timestamp to use instead of the system time, for the timestamped
+    /// schemes.
+    #[arg(long)]
+    now: Option<u64>,
+
+    /// The maximum allowed timestamp skew in seconds, for the timestamped schemes.
+    #[arg(long, default_value_t = 300)]
+    tolerance: u64,
+
+    /// The file with the raw HTTP/1.1 request; reads stdin if not given.
+    input: Option<std::path::PathBuf>,
+}
+
+/// The supported signature schemes.
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum SchemeName {
+    /// GitHub `X-Hub-Signature-256`.
+    Github,
+    /// Stripe `Stripe-Signature`.
+    Stripe,
+}
+
+/// The configured signature scheme.
+#[derive(Debug)]
+enum Scheme {
+    /// See [`webhook_request_validator::GitHub`].
+    GitHub(webhook_request_validator::GitHub),
+    /// See [`webhook_request_validator::Stripe`].
+    Stripe(webhook_request_validator::Stripe),
+}
+
+impl Scheme {
+    /// Configure the scheme from the arguments and the secret.
+    fn new(args: &Args, secret: &[u8]) -> Self {
+        match args.scheme {
+            SchemeName::Github => Self::GitHub(webhook_request_validator::GitHub::new(secret)),
+            SchemeName::Stripe => {
+                let scheme =
+                    webhook_request_validator::Stripe::new(secret).with_tolerance(args.tolerance);
+                Self::Stripe(match args.now {
+                    Some(now) => scheme.with_now(now),
+                    None => scheme,
+                })
+            }
+        }
+    }
+
+    /// Check the request signature.
+    fn diagnose(
+        &self,
+        parts: &http::request::Parts,
+        body: &bytes::Bytes,
+    ) -> webhook_request_validator::Diagnosis {
+        match self {
+            Self::GitHub(scheme) => scheme.diagnose(parts, body),
+            Self::Stripe(scheme) => scheme.diagnose(parts, body),
+        }
+    }
+
+    /// Sign the request.
+    fn sign(&self, req: http::Request<bytes::Bytes>) -> http::Request<bytes::Bytes> {
+        let result = match self {
+            Self::GitHub(scheme) => http_request_validator::sign_blocking(scheme, req),
+            Self::Stripe(scheme) => http_request_validator::sign_blocking(scheme, req),
+        };
+        match result {
+            Ok(req) => req,
+            Err(infallible) => match infallible {},
+        }
+    }
+}
+
+/// The environment variable with the secret.
+const SECRET_ENV: &str = "HTTP_REQUEST_VALIDATOR_SECRET";
+
+/// Read the secret from the file, or from the environment.
+fn read_secret(
+    secret_file: Option<&std::path::Path>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let Some(path) = secret_file else {
+        return match std::env::var(SECRET_ENV) {
+            Ok(secret) => Ok(secret.into_bytes()),
+            Err(std::env::VarError::NotPresent) => {
+                Err(format!("no secret: pass --secret-file or set {SECRET_ENV}").into())
+            }
+            Err(error) => Err(format!("invalid {SECRET_ENV}: {error}").into()),
+        };
+    };
+
+    let mut secret = std::fs::read(path)?;
+    if secret.ends_with(b"\n") {
+        secret.pop();
+        if secret.ends_with(b"\r") {
+            secret.pop();
+        }
+    }
+    Ok(secret)
+}
+
+/// Read the whole input file, or stdin.
+fn read_input(input: Option<&std::path::Path>) -> std::io::Result<Vec<u8>> {
+    match input {
+        Some(path) => std::fs::read(path),
+        None => {
+            let mut buf = Vec::new();
+            std::io::stdin().read_to_end(&mut buf)?;
+            Ok(buf)
+        }
+    }
+}
+
+/// Print the diagnosis in the human-readable form.
+fn print_diagnosis(
+    out: &mut impl std::io::Write,
+    diagnosis: &webhook_request_validator::Diagnosis,
+    tolerance: u64,
+) -> std::io::Result<()> {
+    writeln!(out, "signature header:  {}", diagnosis.header)?;
+    writeln!(
+        out,
+        "canonical string:  {} bytes: \"{}\"",
+        diagnosis.canonical.len(),
+        diagnosis.canonical.escape_ascii()
+    )?;
+    writeln!(out, "expected:          {}", diagnosis.expected)?;
+    writeln!(
+        out,
+        "received:          {}",
+        diagnosis.received.as_deref().unwrap_or("<none>")
+    )?;
+    if let Some(skew) = diagnosis.timestamp_skew {
+        writeln!(out, "timestamp skew:    {skew:+}s (tolerance {tolerance}s)")?;
+    }
+    match diagnosis.problem {
+        None => writeln!(out, "result:            valid"),
+        Some(problem) => writeln!(out, "result:            invalid: {problem}"),
+    }
+}
+
+fn main() -> Result<ExitCode, Box<dyn std::error::Error>> {
+    let cli = Cli::parse();
+    let (Command::Verify(args) | Command::Sign(args)) = &cli.command;
+
+    let secret = read_secret(args.secret_file.as_deref())?;
+    let input = read_input(args.input.as_deref())?;
+    let req = raw::parse(&input)?;
+    let scheme = Scheme::new(args, &secret);
+
+    let mut stdout = std::io::stdout().lock();
+    match cli.command {
+        Command::Verify(ref args) => {
+            let (parts, body) = req.into_parts();
+            let diagnosis = scheme.diagnose(&parts, &body);
+            print_diagnosis(&mut stdout, &diagnosis, args.tolerance)?;
+            if diagnosis.problem.is_some() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Sign(_) => {
+            let req = scheme.sign(req);
+            stdout.write_all(&raw::serialize(&req))?;
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,144 @@
+//! Raw HTTP/1.1 request parsing and serialization.
+
+use bytes::Bytes;
+
+/// The maximum number of headers in the parsed request.
+const MAX_HEADERS: usize = 128;
+
+/// An error that can occur while parsing the raw request.
+#[derive(Debug)]
+pub enum ParseError {
+    /// The request head is not valid.
+    Head(httparse::Error),
+    /// The request head is not complete.
+    Incomplete,
+    /// The request head is parsed, but can not be represented as a request.
+    Request(http::Error),
+    /// The body is shorter than the `Content-Length`.
+    TruncatedBody {
+        /// The expected body length.
+        expected: usize,
+        /// The actual body length.
+        actual: usize,
+    },
+    /// The body is not valid `chunked` encoding.
+    InvalidChunkedBody,
+    /// The `Transfer-Encoding` is not `chunked`.
+    UnsupportedTransferEncoding,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Head(error) => write!(f, "invalid request head: {error}"),
+            Self::Incomplete => f.write_str("incomplete request head"),
+            Self::Request(error) => write!(f, "invalid request: {error}"),
+            Self::TruncatedBody { expected, actual } => write!(
+                f,
+                "truncated body: content-length is {expected}, but only {actual} bytes present"
+            ),
+            Self::InvalidChunkedBody => f.write_str("invalid chunked body"),
+            Self::UnsupportedTransferEncoding => {
+                f.write_str("unsupported transfer-encoding, only chunked is supported")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parse the raw HTTP/1.x request.
+///
+/// The body is everything after the head, cut to the `Content-Length` if present.
+/// The `chunked` body is decoded, so the signature is computed over the payload rather than
+/// the framing, and the `Transfer-Encoding` is replaced with the `Content-Length`.
+pub fn parse(input: &[u8]) -> Result<http::Request<Bytes>, ParseError> {
+    let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+    let mut head = httparse::Request::new(&mut headers);
+    let head_len = match head.parse(input).map_err(ParseError::Head)? {
+        httparse::Status::Complete(head_len) => head_len,
+        httparse::Status::Partial => return Err(ParseError::Incomplete),
+    };
+
+    let mut builder = http::Request::builder()
+        .method(head.method.unwrap_or_default())
+        .uri(head.path.unwrap_or_default())
+        .version(match head.version {
+            Some(0) => http::Version::HTTP_10,
+            _ => http::Version::HTTP_11,
+        });
+    for header in head.headers.iter() {
+        builder = builder.header(header.name, header.value);
+    }
+
+    let mut req = builder.body(()).map_err(ParseError::Request)?;
+    let headers = req.headers_mut();
+
+    let mut body = input.get(head_len..).unwrap_or_default();
+    if let Some(transfer_encoding) = headers.remove(http::header::TRANSFER_ENCODING) {
+        if !transfer_encoding
+            .as_bytes()
+            .eq_ignore_ascii_case(b"chunked")
+        {
+            return Err(ParseError::UnsupportedTransferEncoding);
+        }
+        let body = decode_chunked(body)?;
+        headers.insert(http::header::CONTENT_LENGTH, body.len().into());
+        return Ok(req.map(|()| body.into()));
+    }
+
+    let content_length = headers
+        .get(http::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok()?.parse::<usize>().ok());
+    if let Some(expected) = content_length {
+        body = body.get(..expected).ok_or(ParseError::TruncatedBody {
+            expected,
+            actual: body.len(),
+        })?;
+    }
+
+    Ok(req.map(|()| Bytes::copy_from_slice(body)))
+}
+
+/// Decode the `chunked` body, ignoring the chunk extensions and the trailers.
+fn decode_chunked(mut input: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let mut body = Vec::new();
+    loop {
+        let (size_len, size) = match httparse::parse_chunk_size(input) {
+            Ok(httparse::Status::Complete(chunk_size)) => chunk_size,
+            Ok(httparse::Status::Partial) | Err(httparse::InvalidChunkSize) => {
+                return Err(ParseError::InvalidChunkedBody)
+            }
+        };
+        if size == 0 {
+            return Ok(body);
+        }
+        let chunk = usize::try_from(size)
+            .ok()
+            .and_then(|size| input.get(size_len..)?.get(..size))
+            .ok_or(ParseError::InvalidChunkedBody)?;
+        body.extend_from_slice(chunk);
+        input = input[size_len + chunk.len()..]
+            .strip_prefix(b"\r\n")
+            .ok_or(ParseError::InvalidChunkedBody)?;
+    }
+}
+
+/// Serialize the request as raw HTTP/1.1.
+pub fn serialize(req: &http::Request<Bytes>) -> Vec<u8> {
+    let mut output = Vec::new();
+    let target = req
+        .uri()
+        .path_and_query()
+        .map_or("/", http::uri::PathAndQuery::as_str);
+    output.extend_from_slice(format!("{} {target} HTTP/1.1\r\n", req.method()).as_bytes());
+    for (name, value) in req.headers() {
+        output.extend_from_slice(name.as_str().as_bytes());
+        output.extend_from_slice(b": ");
+        output.extend_from_slice(value.as_bytes());
+        output.extend_from_slice(b"\r\n");
+    }
+    output.extend_from_slice(b"\r\n");
+    output.extend_from_slice(req.body());
+    output
+}
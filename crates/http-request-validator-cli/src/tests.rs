@@ -0,0 +1,62 @@
+//! The tests for the raw request handling.
+
+/// The captured request without a signature.
+const UNSIGNED: &[u8] = b"POST /webhook?x=1 HTTP/1.1\r\n\
+Host: example.com\r\n\
+Content-Type: application/json\r\n\
+Content-Length: 16\r\n\
+\r\n\
+{\"event\":\"ping\"}trailing garbage";
+
+#[test]
+fn raw_round_trip() {
+    let req = crate::raw::parse(UNSIGNED).unwrap();
+    assert_eq!(req.method(), http::Method::POST);
+    assert_eq!(req.uri(), "/webhook?x=1");
+    assert_eq!(req.headers()["content-type"], "application/json");
+    assert_eq!(req.body(), "{\"event\":\"ping\"}");
+
+    let reparsed = crate::raw::parse(&crate::raw::serialize(&req)).unwrap();
+    assert_eq!(reparsed.uri(), req.uri());
+    assert_eq!(reparsed.headers(), req.headers());
+    assert_eq!(reparsed.body(), req.body());
+}
+
+#[test]
+fn raw_truncated_body() {
+    let error =
+        crate::raw::parse(b"POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\nshort").unwrap_err();
+    assert!(matches!(
+        error,
+        crate::raw::ParseError::TruncatedBody {
+            expected: 10,
+            actual: 5
+        }
+    ));
+}
+
+#[test]
+fn raw_chunked_body() {
+    let req = crate::raw::parse(
+        b"POST / HTTP/1.1\r\n\
+Transfer-Encoding: chunked\r\n\
+\r\n\
+9;ext=1\r\n{\"event\":\r\n7\r\n\"ping\"}\r\n0\r\nX-Trailer: 1\r\n\r\n",
+    )
+    .unwrap();
+    assert_eq!(req.body(), "{\"event\":\"ping\"}");
+    assert_eq!(req.headers()["content-length"], "16");
+    assert!(!req.headers().contains_key("transfer-encoding"));
+
+    let error =
+        crate::raw::parse(b"POST / HTTP/1.1\r\nTransfer-Encoding: chunked\r\n\r\n9\r\nshort")
+            .unwrap_err();
+    assert!(matches!(error, crate::raw::ParseError::InvalidChunkedBody));
+
+    let error = crate::raw::parse(b"POST / HTTP/1.1\r\nTransfer-Encoding: gzip, chunked\r\n\r\n")
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        crate::raw::ParseError::UnsupportedTransferEncoding
+    ));
+}
@@ -0,0 +1,59 @@
+//! Assertions for the validation results.
+
+use http_body_request_validator::Error;
+
+/// Assert the validation has passed, and return the validated value.
+///
+/// # Panics
+///
+/// Panics if the result is an error.
+#[track_caller]
+pub fn assert_valid<T, E: core::fmt::Debug>(result: Result<T, E>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(error) => panic!("expected the request to be valid, got an error: {error:?}"),
+    }
+}
+
+/// Assert the validation has failed, and return the error.
+///
+/// # Panics
+///
+/// Panics if the result is not an error.
+#[track_caller]
+pub fn assert_invalid<T, E>(result: Result<T, E>) -> E {
+    match result {
+        Ok(_) => panic!("expected the request to be invalid, but it passed the validation"),
+        Err(error) => error,
+    }
+}
+
+/// Assert the result is an [`Error::Validation`], and return the validation error.
+///
+/// # Panics
+///
+/// Panics if the result is not an [`Error::Validation`].
+#[track_caller]
+pub fn assert_validation_error<T, B: core::fmt::Debug, V>(result: Result<T, Error<B, V>>) -> V {
+    match assert_invalid(result) {
+        Error::Validation(error) => error,
+        Error::BodyBuffering(error) => {
+            panic!("expected a validation error, got a body buffering error: {error:?}")
+        }
+    }
+}
+
+/// Assert the result is an [`Error::BodyBuffering`], and return the body buffering error.
+///
+/// # Panics
+///
+/// Panics if the result is not an [`Error::BodyBuffering`].
+#[track_caller]
+pub fn assert_body_buffering_error<T, B, V: core::fmt::Debug>(result: Result<T, Error<B, V>>) -> B {
+    match assert_invalid(result) {
+        Error::BodyBuffering(error) => error,
+        Error::Validation(error) => {
+            panic!("expected a body buffering error, got a validation error: {error:?}")
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Test utilities for the [`http_request_validator`] validators.
+//!
+//! Provides the [`TestRequest`] builder for crafting (and signing) the requests, the helpers to
+//! run the validators against an already buffered [`http::Request<bytes::Bytes>`], and the
+//! assertions for the [`http_body_request_validator::Error`].
+//!
+//! ## Examples
+//!
+//! ```
+//! use http_request_validator_test::{assert_valid, validate_sync, TestRequest};
+//!
+//! struct NonEmpty;
+//!
+//! impl<Data: bytes::Buf> http_request_validator::SyncValidator<Data> for NonEmpty {
+//!     type Error = &'static str;
+//!
+//!     fn validate(
+//!         &self,
+//!         _parts: &http::request::Parts,
+//!         buffered_body: &Data,
+//!     ) -> Result<(), Self::Error> {
+//!         if !buffered_body.has_remaining() {
+//!             return Err("empty body");
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! assert_valid(validate_sync(&NonEmpty, TestRequest::post("/").body("payload").build()));
+//! ```
+
+pub mod assert;
+pub mod request;
+pub mod validate;
+
+pub use self::assert::*;
+pub use self::request::TestRequest;
+pub use self::validate::*;
+
+#[cfg(test)]
+mod tests;
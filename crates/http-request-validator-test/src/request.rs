@@ -0,0 +1,135 @@
+//! [`TestRequest`] builder.
+
+use bytes::Bytes;
+
+/// The builder of the buffered requests for the tests.
+///
+/// Unlike [`http::request::Builder`], panics on invalid input right away, as that is what the
+/// tests want anyway, and allows signing the request once the body is set.
+#[derive(Debug)]
+pub struct TestRequest {
+    /// The request head.
+    parts: http::request::Parts,
+
+    /// The request body.
+    body: Bytes,
+}
+
+impl TestRequest {
+    /// Create a new request with the given method and URI.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the URI is invalid.
+    #[track_caller]
+    pub fn new(method: http::Method, uri: &str) -> Self {
+        let (parts, ()) = http::Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(())
+            .expect("invalid request URI")
+            .into_parts();
+        Self {
+            parts,
+            body: Bytes::new(),
+        }
+    }
+
+    /// Create a new `GET` request with the given URI.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the URI is invalid.
+    #[track_caller]
+    pub fn get(uri: &str) -> Self {
+        Self::new(http::Method::GET, uri)
+    }
+
+    /// Create a new `POST` request with the given URI.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the URI is invalid.
+    #[track_caller]
+    pub fn post(uri: &str) -> Self {
+        Self::new(http::Method::POST, uri)
+    }
+
+    /// Append a header.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the header name or value is invalid.
+    #[track_caller]
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        let name = http::HeaderName::try_from(name).expect("invalid header name");
+        let value = http::HeaderValue::try_from(value).expect("invalid header value");
+        self.parts.headers.append(name, value);
+        self
+    }
+
+    /// Insert an extension.
+    pub fn extension<T: Clone + Send + Sync + 'static>(mut self, extension: T) -> Self {
+        self.parts.extensions.insert(extension);
+        self
+    }
+
+    /// Set the body.
+    pub fn body(mut self, body: impl Into<Bytes>) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    /// Sign the request with the given function.
+    ///
+    /// The function gets the request head to put the signature into and the body to sign, so
+    /// call this after the body and the signed headers are set.
+    pub fn sign(mut self, sign: impl FnOnce(&mut http::request::Parts, &Bytes)) -> Self {
+        sign(&mut self.parts, &self.body);
+        self
+    }
+
+    /// Sign the request with the given [`http_request_validator::SyncSigner`].
+    ///
+    /// Call this after the body and the signed headers are set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signing fails.
+    #[track_caller]
+    pub fn sign_with<Signer>(mut self, signer: &Signer) -> Self
+    where
+        Signer: http_request_validator::SyncSigner<Bytes, Error: core::fmt::Debug> + ?Sized,
+    {
+        if let Err(error) = signer.sign(&mut self.parts, &self.body) {
+            panic!("signing failed: {error:?}");
+        }
+        self
+    }
+
+    /// Sign the request with the [`webhook_request_validator::GitHub`] scheme.
+    ///
+    /// Call this after the body is set.
+    pub fn sign_github(self, secret: impl Into<Vec<u8>>) -> Self {
+        self.sign_with(&webhook_request_validator::GitHub::new(secret))
+    }
+
+    /// Sign the request with the [`webhook_request_validator::Stripe`] scheme, with the given
+    /// UNIX timestamp.
+    ///
+    /// Call this after the body is set.
+    pub fn sign_stripe(self, secret: impl Into<Vec<u8>>, timestamp: u64) -> Self {
+        self.sign_with(&webhook_request_validator::Stripe::new(secret).with_now(timestamp))
+    }
+
+    /// Build the request.
+    pub fn build(self) -> http::Request<Bytes> {
+        http::Request::from_parts(self.parts, self.body)
+    }
+}
+
+impl From<TestRequest> for http::Request<Bytes> {
+    fn from(request: TestRequest) -> Self {
+        request.build()
+    }
+}
@@ -0,0 +1,135 @@
+//! The tests for the test utilities.
+
+use crate::{
+    assert_body_buffering_error, assert_invalid, assert_valid, assert_validation_error, TestRequest,
+};
+
+/// Requires the `x-signature` header to be the reversed body.
+struct ReversedBodySignature;
+
+impl http_request_validator::SyncValidator<bytes::Bytes> for ReversedBodySignature {
+    type Error = &'static str;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &bytes::Bytes,
+    ) -> Result<(), Self::Error> {
+        let signature = parts
+            .headers
+            .get("x-signature")
+            .ok_or("missing signature")?;
+        if !signature.as_bytes().iter().eq(buffered_body.iter().rev()) {
+            return Err("bad signature");
+        }
+        Ok(())
+    }
+}
+
+impl http_request_validator::SyncSigner<bytes::Bytes> for ReversedBodySignature {
+    type Error = core::convert::Infallible;
+
+    fn sign(
+        &self,
+        parts: &mut http::request::Parts,
+        buffered_body: &bytes::Bytes,
+    ) -> Result<(), Self::Error> {
+        sign(parts, buffered_body);
+        Ok(())
+    }
+}
+
+/// Sign the request with the reversed body.
+fn sign(parts: &mut http::request::Parts, body: &bytes::Bytes) {
+    let signature: Vec<u8> = body.iter().rev().copied().collect();
+    parts.headers.insert(
+        "x-signature",
+        http::HeaderValue::from_bytes(&signature).unwrap(),
+    );
+}
+
+#[test]
+fn sign_and_validate() {
+    let req = TestRequest::post("/").body("payload").sign(sign);
+    let req = assert_valid(crate::validate_sync(&ReversedBodySignature, req));
+    assert_eq!(req.body(), "payload");
+
+    let req = TestRequest::post("/")
+        .body("payload")
+        .sign_with(&ReversedBodySignature);
+    assert_valid(crate::validate_sync(&ReversedBodySignature, req));
+
+    let req = TestRequest::post("/").body("payload");
+    assert_eq!(
+        assert_invalid(crate::validate_sync(&ReversedBodySignature, req)),
+        "missing signature"
+    );
+}
+
+#[test]
+fn sign_webhook_schemes() {
+    let github = webhook_request_validator::GitHub::new("secret");
+    let req = TestRequest::post("/").body("{}").sign_github("secret");
+    assert_valid(crate::validate_sync(&github, req));
+    let req = TestRequest::post("/").body("{}").sign_github("other");
+    assert_eq!(
+        assert_invalid(crate::validate_sync(&github, req)),
+        webhook_request_validator::Error::SignatureMismatch
+    );
+
+    let stripe = webhook_request_validator::Stripe::new("secret").with_now(1_700_000_000);
+    let req = TestRequest::post("/")
+        .body("{}")
+        .sign_stripe("secret", 1_700_000_100);
+    assert_valid(crate::validate_sync(&stripe, req));
+    let req = TestRequest::post("/")
+        .body("{}")
+        .sign_stripe("secret", 1_600_000_000);
+    assert_eq!(
+        assert_invalid(crate::validate_sync(&stripe, req)),
+        webhook_request_validator::Error::TimestampOutOfTolerance
+    );
+}
+
+#[tokio::test]
+async fn validate_async() {
+    let validator = http_request_validator::AsAsync(ReversedBodySignature);
+
+    let req = TestRequest::post("/")
+        .body("payload")
+        .sign(sign)
+        .header("x-extra", "1")
+        .build();
+    assert_valid(crate::validate(&validator, req).await);
+
+    let req = TestRequest::post("/")
+        .header("x-signature", "wrong")
+        .body("payload")
+        .build();
+    assert_eq!(
+        assert_invalid(crate::validate(&validator, req).await),
+        "bad signature"
+    );
+}
+
+#[test]
+fn error_assertions() {
+    type Error = http_body_request_validator::Error<&'static str, &'static str>;
+
+    assert_eq!(
+        assert_validation_error(Err::<(), _>(Error::Validation("invalid"))),
+        "invalid"
+    );
+    assert_eq!(
+        assert_body_buffering_error(Err::<(), _>(Error::BodyBuffering("too large"))),
+        "too large"
+    );
+}
+
+#[test]
+#[should_panic(expected = "expected a validation error")]
+fn error_assertions_panic() {
+    type Error = http_body_request_validator::Error<&'static str, &'static str>;
+
+    assert_validation_error(Err::<(), _>(Error::BodyBuffering("too large")));
+}
@@ -0,0 +1,51 @@
+//! Helpers to run the validators against the already buffered requests.
+
+use bytes::Bytes;
+
+/// Run the [`http_request_validator::Validator`] against the request.
+///
+/// Calls [`http_request_validator::Validator::pre_validate`] and then validates the request, the
+/// same way the integrations do, but without the body buffering.
+/// Returns the request back if it passes the validation.
+pub async fn validate<Validator>(
+    validator: &Validator,
+    req: http::Request<Bytes>,
+) -> Result<http::Request<Bytes>, Validator::Error>
+where
+    Validator: http_request_validator::Validator<Bytes> + ?Sized,
+{
+    let (mut parts, body) = req.into_parts();
+    validator.pre_validate(&mut parts)?;
+    validator.validate(&parts, &body).await?;
+    Ok(http::Request::from_parts(parts, body))
+}
+
+/// Run the [`http_request_validator::LocalValidator`] against the request.
+///
+/// The same as [`validate`], but the returned future is not required to be [`Send`].
+pub async fn validate_local<Validator>(
+    validator: &Validator,
+    req: http::Request<Bytes>,
+) -> Result<http::Request<Bytes>, Validator::Error>
+where
+    Validator: http_request_validator::LocalValidator<Bytes> + ?Sized,
+{
+    let (mut parts, body) = req.into_parts();
+    validator.pre_validate(&mut parts)?;
+    validator.validate(&parts, &body).await?;
+    Ok(http::Request::from_parts(parts, body))
+}
+
+/// Run the [`http_request_validator::SyncValidator`] against the request.
+///
+/// The same as [`http_request_validator::validate_blocking`], but takes anything convertible
+/// into the request, like the [`crate::TestRequest`].
+pub fn validate_sync<Validator>(
+    validator: &Validator,
+    req: impl Into<http::Request<Bytes>>,
+) -> Result<http::Request<Bytes>, Validator::Error>
+where
+    Validator: http_request_validator::SyncValidator<Bytes> + ?Sized,
+{
+    http_request_validator::validate_blocking(validator, req.into())
+}
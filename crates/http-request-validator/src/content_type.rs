@@ -0,0 +1,309 @@
+//! [`ContentTypeValidator`] for enforcing the `Content-Type` of the requests.
+
+use alloc::{borrow::ToOwned as _, string::String, vec::Vec};
+
+use crate::sniff::Format;
+
+/// A parsed media type, like `application/json; charset=utf-8`.
+///
+/// The type, the subtype and the parameter names are case-insensitive, and are stored
+/// lowercased.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MediaType {
+    /// The lowercase `type/subtype`.
+    essence: String,
+
+    /// The parameters, with the lowercase names and the unquoted values.
+    parameters: Vec<(String, String)>,
+}
+
+impl MediaType {
+    /// Parse the media type.
+    ///
+    /// The `*` subtype, or `*/*`, make a media range that matches any subtype, or any type,
+    /// and the `*+suffix` subtype, like in `application/*+json`, matches the structured syntax
+    /// suffix.
+    pub fn parse(value: &str) -> Option<Self> {
+        let (essence, mut rest) = value.split_once(';').unwrap_or((value, ""));
+        let essence = essence.trim().to_ascii_lowercase();
+        let (kind, subtype) = essence.split_once('/')?;
+        if !is_token(kind) || !is_token(subtype) || (kind == "*" && subtype != "*") {
+            return None;
+        }
+
+        let mut parameters = Vec::new();
+        loop {
+            rest = rest.trim_start_matches([' ', '\t', ';']);
+            if rest.is_empty() {
+                break;
+            }
+            let (name, after_name) = rest.split_once('=')?;
+            let name = name.trim_end();
+            if !is_token(name) {
+                return None;
+            }
+            let (value, after_value) = parse_value(after_name)?;
+            parameters.push((name.to_ascii_lowercase(), value));
+            rest = after_value.trim_start_matches([' ', '\t']);
+            if !rest.is_empty() && !rest.starts_with(';') {
+                return None;
+            }
+        }
+
+        Some(Self {
+            essence,
+            parameters,
+        })
+    }
+
+    /// The lowercase `type/subtype`.
+    pub fn essence(&self) -> &str {
+        &self.essence
+    }
+
+    /// The value of the parameter with the given case-insensitive name.
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.parameters
+            .iter()
+            .find(|(candidate, _)| candidate.eq_ignore_ascii_case(name))
+            .map(|(_, value)| &**value)
+    }
+
+    /// Whether this media type is within the range.
+    ///
+    /// The type and subtype have to match the range, and every parameter of the range has to
+    /// be present with the same value, compared case-insensitively.
+    pub fn matches(&self, range: &Self) -> bool {
+        let essence_matches = match range.essence.split_once('/') {
+            Some(("*", "*")) => true,
+            Some((kind, subtype)) if subtype.starts_with('*') => self
+                .essence
+                .split_once('/')
+                .is_some_and(|(candidate, rest)| {
+                    candidate == kind && rest.ends_with(&subtype[1..])
+                }),
+            _ => self.essence == range.essence,
+        };
+        essence_matches
+            && range.parameters.iter().all(|(name, value)| {
+                self.parameter(name)
+                    .is_some_and(|candidate| candidate.eq_ignore_ascii_case(value))
+            })
+    }
+}
+
+/// Whether the string is a non-empty token, as defined for the media types.
+fn is_token(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte))
+}
+
+/// Parse the parameter value, a token or a quoted string, returning it and the rest.
+pub(crate) fn parse_value(input: &str) -> Option<(String, &str)> {
+    let input = input.trim_start();
+    let Some(quoted) = input.strip_prefix('"') else {
+        let end = input.find([';', ' ', '\t']).unwrap_or(input.len());
+        let (value, rest) = input.split_at(end);
+        return is_token(value).then(|| (value.to_owned(), rest));
+    };
+
+    let mut value = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((pos, char)) = chars.next() {
+        match char {
+            '"' => return Some((value, &quoted[pos + 1..])),
+            '\\' => value.push(chars.next()?.1),
+            _ => value.push(char),
+        }
+    }
+    None
+}
+
+/// The validator that enforces the allowed media types and the charset of the requests.
+///
+/// Optionally sniffs the buffered body to confirm it looks like the declared JSON, urlencoded
+/// form or XML, see [`crate::sniff`].
+///
+/// The requests with an empty body and no `Content-Type` are let through.
+///
+/// This is a [`crate::SyncValidator`].
+///
+/// ## Examples
+///
+/// ```
+/// use http_request_validator::{content_type::ContentTypeValidator, validate_blocking};
+///
+/// let validator = ContentTypeValidator::new()
+///     .allow("application/json")
+///     .allow("application/*+json")
+///     .with_required_charset("utf-8")
+///     .with_sniffing(true);
+///
+/// let req = http::Request::post("/")
+///     .header("content-type", "application/json; charset=UTF-8")
+///     .body(bytes::Bytes::from_static(br#"{"event":"ping"}"#))
+///     .unwrap();
+/// assert!(validate_blocking(&validator, req).is_ok());
+///
+/// let req = http::Request::post("/")
+///     .header("content-type", "application/json; charset=utf-8")
+///     .body(bytes::Bytes::from_static(b"event=ping"))
+///     .unwrap();
+/// assert!(validate_blocking(&validator, req).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContentTypeValidator {
+    /// The allowed media types and ranges; any media type is allowed if empty.
+    allowed: Vec<MediaType>,
+
+    /// The required charset, if any.
+    charset: Option<String>,
+
+    /// Whether to sniff the body.
+    sniff: bool,
+}
+
+impl ContentTypeValidator {
+    /// Create a new [`ContentTypeValidator`] that allows any media type.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow the given media type or range.
+    ///
+    /// The parameters given here have to be present in the requests, like in
+    /// `application/json; version=2`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the media type can not be parsed.
+    #[track_caller]
+    pub fn allow(mut self, media_type: &str) -> Self {
+        let Some(media_type) = MediaType::parse(media_type) else {
+            panic!("invalid media type: {media_type:?}");
+        };
+        self.allowed.push(media_type);
+        self
+    }
+
+    /// Require the given charset, compared case-insensitively.
+    ///
+    /// The requests that do not declare a charset are rejected.
+    pub fn with_required_charset(mut self, charset: &str) -> Self {
+        self.charset = Some(charset.to_owned());
+        self
+    }
+
+    /// Set whether to sniff the body to confirm it looks like the declared media type.
+    pub const fn with_sniffing(mut self, sniff: bool) -> Self {
+        self.sniff = sniff;
+        self
+    }
+
+    /// Check the `Content-Type` header, and the body if sniffing.
+    pub fn check(&self, headers: &http::HeaderMap, body: &[u8]) -> Result<(), Error> {
+        let mut values = headers.get_all(http::header::CONTENT_TYPE).iter();
+        let Some(value) = values.next() else {
+            if body.is_empty() {
+                return Ok(());
+            }
+            return Err(Error::MissingContentType);
+        };
+        if values.next().is_some() {
+            return Err(Error::InvalidContentType);
+        }
+        let media_type = value
+            .to_str()
+            .ok()
+            .and_then(MediaType::parse)
+            .filter(|media_type| !media_type.essence.contains('*'))
+            .ok_or(Error::InvalidContentType)?;
+
+        if !self.allowed.is_empty() && !self.allowed.iter().any(|range| media_type.matches(range)) {
+            return Err(Error::UnsupportedMediaType(media_type));
+        }
+
+        if let Some(expected) = &self.charset {
+            match media_type.parameter("charset") {
+                None => return Err(Error::MissingCharset),
+                Some(charset) if !charset.eq_ignore_ascii_case(expected) => {
+                    return Err(Error::CharsetMismatch(charset.to_owned()));
+                }
+                Some(_) => {}
+            }
+        }
+
+        if self.sniff {
+            if let Some(format) = Format::of(media_type.essence()) {
+                if !format.sniff(body) {
+                    return Err(Error::BodyMismatch(format));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An error that can occur while validating the `Content-Type`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The request has a body, but no `Content-Type`.
+    MissingContentType,
+    /// The `Content-Type` can not be parsed, or there are several of them.
+    InvalidContentType,
+    /// The media type is not allowed.
+    UnsupportedMediaType(MediaType),
+    /// The charset is required, but not declared.
+    MissingCharset,
+    /// The declared charset is not the required one.
+    CharsetMismatch(String),
+    /// The body does not look like the declared format.
+    BodyMismatch(Format),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingContentType => f.write_str("missing content type"),
+            Self::InvalidContentType => f.write_str("invalid content type"),
+            Self::UnsupportedMediaType(media_type) => {
+                write!(f, "unsupported media type: {}", media_type.essence())
+            }
+            Self::MissingCharset => f.write_str("missing charset"),
+            Self::CharsetMismatch(charset) => write!(f, "unexpected charset: {charset}"),
+            Self::BodyMismatch(format) => write!(f, "body is not valid {}", format.as_str()),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+impl crate::ValidationErrorKind for Error {
+    fn kind(&self) -> crate::ErrorKind {
+        match self {
+            Self::UnsupportedMediaType(_) => crate::ErrorKind::UnsupportedMediaType,
+            Self::MissingContentType
+            | Self::InvalidContentType
+            | Self::MissingCharset
+            | Self::CharsetMismatch(_)
+            | Self::BodyMismatch(_) => crate::ErrorKind::Malformed,
+        }
+    }
+}
+
+impl<Data> crate::SyncValidator<Data> for ContentTypeValidator
+where
+    Data: bytes::Buf + AsRef<[u8]>,
+{
+    type Error = Error;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        self.check(&parts.headers, buffered_body.as_ref())
+    }
+}
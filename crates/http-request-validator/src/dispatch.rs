@@ -0,0 +1,580 @@
+//! [`Dispatch`] validator for routing the requests to one of several validators.
+//!
+//! Useful for the endpoints that receive the requests from several sources that sign them
+//! differently, like a single webhook endpoint for multiple providers.
+
+use crate::Predicate;
+
+/// The validator that picks one of the providers by the request head and validates the request
+/// with the provider's validator.
+///
+/// The providers are tried in the order they were added, and the first one with the matching
+/// predicate is used. The requests matching no provider are rejected with
+/// [`Error::NoProviderMatched`].
+///
+/// The name of the matched provider is recorded as a [`MatchedProvider`] in the request
+/// extensions in [`crate::Validator::pre_validate`], once the provider's pre-validation is done.
+///
+/// Implements [`crate::Validator`], [`crate::LocalValidator`] and [`crate::SyncValidator`] over
+/// the providers' validators of the same kind.
+///
+/// ## Examples
+///
+/// ```
+/// use http_request_validator::{predicate::HeaderPresent, Dispatch, SyncValidator};
+///
+/// struct SignatureValidator(&'static str);
+///
+/// impl<Data: bytes::Buf> SyncValidator<Data> for SignatureValidator {
+///     type Error = &'static str;
+///
+///     fn validate(
+///         &self,
+///         parts: &http::request::Parts,
+///         _buffered_body: &Data,
+///     ) -> Result<(), Self::Error> {
+///         if parts.headers.get(self.0).is_none_or(|value| value.is_empty()) {
+///             return Err("empty signature");
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let dispatch = Dispatch::<&'static str>::new()
+///     .provider(
+///         "github",
+///         HeaderPresent(http::HeaderName::from_static("x-hub-signature-256")),
+///         http_request_validator::AsAsync(SignatureValidator("x-hub-signature-256")),
+///     )
+///     .provider(
+///         "stripe",
+///         HeaderPresent(http::HeaderName::from_static("stripe-signature")),
+///         http_request_validator::AsAsync(SignatureValidator("stripe-signature")),
+///     );
+/// # let _ = dispatch;
+/// ```
+pub struct Dispatch<E, Providers = ()> {
+    /// The providers, in the nested pairs.
+    providers: Providers,
+
+    /// The error type marker.
+    phantom_data: core::marker::PhantomData<fn() -> E>,
+}
+
+impl<E> Dispatch<E> {
+    /// Create a new [`Dispatch`] without providers.
+    ///
+    /// The `E` is the error type the providers' validation errors are converted into.
+    pub const fn new() -> Self {
+        Self {
+            providers: (),
+            phantom_data: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<E> Default for Dispatch<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E, Providers> core::fmt::Debug for Dispatch<E, Providers>
+where
+    Providers: core::fmt::Debug,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Dispatch")
+            .field("providers", &self.providers)
+            .finish()
+    }
+}
+
+impl<E, Providers: Clone> Clone for Dispatch<E, Providers> {
+    fn clone(&self) -> Self {
+        Self {
+            providers: self.providers.clone(),
+            phantom_data: core::marker::PhantomData,
+        }
+    }
+}
+
+impl<E, Providers> Dispatch<E, Providers> {
+    /// Add a provider with the lower priority than the already added ones.
+    pub fn provider<P, V>(
+        self,
+        name: &'static str,
+        predicate: P,
+        validator: V,
+    ) -> Dispatch<E, (Providers, Provider<P, V>)> {
+        Dispatch {
+            providers: (
+                self.providers,
+                Provider {
+                    name,
+                    predicate,
+                    validator,
+                },
+            ),
+            phantom_data: core::marker::PhantomData,
+        }
+    }
+
+    /// The name of the provider matching the request head, if any.
+    pub fn select(&self, parts: &http::request::Parts) -> Option<&'static str>
+    where
+        Providers: Select,
+    {
+        self.providers.select(parts)
+    }
+}
+
+/// A single [`Dispatch`] provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Provider<P, V> {
+    /// The name of the provider, recorded in the [`MatchedProvider`].
+    pub name: &'static str,
+
+    /// The predicate selecting the requests of this provider.
+    pub predicate: P,
+
+    /// The validator for the requests of this provider.
+    pub validator: V,
+}
+
+/// The name of the [`Dispatch`] provider that matched the request.
+///
+/// Inserted into the request extensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MatchedProvider(pub &'static str);
+
+/// An error that can occur while validating the request with a [`Dispatch`].
+#[derive(Debug)]
+pub enum Error<E> {
+    /// No provider matched the request.
+    NoProviderMatched,
+
+    /// The validator of the matched provider failed.
+    Provider {
+        /// The name of the matched provider.
+        name: &'static str,
+
+        /// The validation error.
+        error: E,
+    },
+}
+
+impl<E: core::fmt::Display> core::fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NoProviderMatched => f.write_str("no provider matched the request"),
+            Self::Provider { name, error } => write!(f, "{name}: {error}"),
+        }
+    }
+}
+
+impl<E: crate::ValidationErrorKind> crate::ValidationErrorKind for Error<E> {
+    fn kind(&self) -> crate::ErrorKind {
+        match self {
+            Self::NoProviderMatched => crate::ErrorKind::MissingCredentials,
+            Self::Provider { error, .. } => error.kind(),
+        }
+    }
+
+    fn www_authenticate(&self) -> Option<http::HeaderValue> {
+        match self {
+            Self::NoProviderMatched => None,
+            Self::Provider { error, .. } => error.www_authenticate(),
+        }
+    }
+}
+
+/// The provider selection over the nested provider pairs.
+pub trait Select {
+    /// The name of the first provider matching the request head, if any.
+    fn select(&self, parts: &http::request::Parts) -> Option<&'static str>;
+}
+
+impl Select for () {
+    fn select(&self, _parts: &http::request::Parts) -> Option<&'static str> {
+        None
+    }
+}
+
+impl<Providers: Select, P: Predicate, V> Select for (Providers, Provider<P, V>) {
+    fn select(&self, parts: &http::request::Parts) -> Option<&'static str> {
+        self.0
+            .select(parts)
+            .or_else(|| self.1.predicate.matches(parts).then_some(self.1.name))
+    }
+}
+
+/// The validation over the nested provider pairs.
+pub trait Providers<Data: bytes::Buf, E>: Select {
+    /// Start the validation with the first provider matching the request head, if any.
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> Option<(
+        &'static str,
+        impl core::future::Future<Output = Result<(), E>> + Send + 'a,
+    )>
+    where
+        E: 'a;
+
+    /// Check the request head with the first provider matching it, if any.
+    fn pre_validate(
+        &self,
+        parts: &mut http::request::Parts,
+    ) -> Option<(&'static str, Result<(), E>)>;
+}
+
+impl<Data: bytes::Buf, E> Providers<Data, E> for () {
+    fn validate<'a>(
+        &'a self,
+        _parts: &'a http::request::Parts,
+        _buffered_body: &'a Data,
+    ) -> Option<(
+        &'static str,
+        impl core::future::Future<Output = Result<(), E>> + Send + 'a,
+    )>
+    where
+        E: 'a,
+    {
+        None::<(&'static str, core::future::Pending<_>)>
+    }
+
+    fn pre_validate(
+        &self,
+        _parts: &mut http::request::Parts,
+    ) -> Option<(&'static str, Result<(), E>)> {
+        None
+    }
+}
+
+/// One of the two futures.
+enum Either<A, B> {
+    /// The future of the higher priority provider.
+    Previous(A),
+    /// The future of this provider.
+    This(B),
+}
+
+impl<Data, E, Previous, P, V> Providers<Data, E> for (Previous, Provider<P, V>)
+where
+    Data: bytes::Buf,
+    Previous: Providers<Data, E>,
+    P: Predicate,
+    V: crate::Validator<Data, Error: Into<E>>,
+{
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> Option<(
+        &'static str,
+        impl core::future::Future<Output = Result<(), E>> + Send + 'a,
+    )>
+    where
+        E: 'a,
+    {
+        let (name, validation) = match self.0.validate(parts, buffered_body) {
+            Some((name, validation)) => (name, Either::Previous(validation)),
+            None if self.1.predicate.matches(parts) => (
+                self.1.name,
+                Either::This(crate::Validator::validate(
+                    &self.1.validator,
+                    parts,
+                    buffered_body,
+                )),
+            ),
+            None => return None,
+        };
+        Some((name, async move {
+            match validation {
+                Either::Previous(validation) => validation.await,
+                Either::This(validation) => validation.await.map_err(Into::into),
+            }
+        }))
+    }
+
+    fn pre_validate(
+        &self,
+        parts: &mut http::request::Parts,
+    ) -> Option<(&'static str, Result<(), E>)> {
+        if let Some(selected) = self.0.pre_validate(parts) {
+            return Some(selected);
+        }
+        if !self.1.predicate.matches(parts) {
+            return None;
+        }
+        let result = crate::Validator::pre_validate(&self.1.validator, parts).map_err(Into::into);
+        Some((self.1.name, result))
+    }
+}
+
+/// The validation over the nested provider pairs, for the [`crate::LocalValidator`]s.
+pub trait LocalProviders<Data: bytes::Buf, E>: Select {
+    /// Start the validation with the first provider matching the request head, if any.
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> Option<(
+        &'static str,
+        impl core::future::Future<Output = Result<(), E>> + 'a,
+    )>
+    where
+        E: 'a;
+
+    /// Check the request head with the first provider matching it, if any.
+    fn pre_validate(
+        &self,
+        parts: &mut http::request::Parts,
+    ) -> Option<(&'static str, Result<(), E>)>;
+}
+
+impl<Data: bytes::Buf, E> LocalProviders<Data, E> for () {
+    fn validate<'a>(
+        &'a self,
+        _parts: &'a http::request::Parts,
+        _buffered_body: &'a Data,
+    ) -> Option<(
+        &'static str,
+        impl core::future::Future<Output = Result<(), E>> + 'a,
+    )>
+    where
+        E: 'a,
+    {
+        None::<(&'static str, core::future::Pending<_>)>
+    }
+
+    fn pre_validate(
+        &self,
+        _parts: &mut http::request::Parts,
+    ) -> Option<(&'static str, Result<(), E>)> {
+        None
+    }
+}
+
+impl<Data, E, Previous, P, V> LocalProviders<Data, E> for (Previous, Provider<P, V>)
+where
+    Data: bytes::Buf,
+    Previous: LocalProviders<Data, E>,
+    P: Predicate,
+    V: crate::LocalValidator<Data, Error: Into<E>>,
+{
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> Option<(
+        &'static str,
+        impl core::future::Future<Output = Result<(), E>> + 'a,
+    )>
+    where
+        E: 'a,
+    {
+        let (name, validation) = match self.0.validate(parts, buffered_body) {
+            Some((name, validation)) => (name, Either::Previous(validation)),
+            None if self.1.predicate.matches(parts) => (
+                self.1.name,
+                Either::This(crate::LocalValidator::validate(
+                    &self.1.validator,
+                    parts,
+                    buffered_body,
+                )),
+            ),
+            None => return None,
+        };
+        Some((name, async move {
+            match validation {
+                Either::Previous(validation) => validation.await,
+                Either::This(validation) => validation.await.map_err(Into::into),
+            }
+        }))
+    }
+
+    fn pre_validate(
+        &self,
+        parts: &mut http::request::Parts,
+    ) -> Option<(&'static str, Result<(), E>)> {
+        if let Some(selected) = self.0.pre_validate(parts) {
+            return Some(selected);
+        }
+        if !self.1.predicate.matches(parts) {
+            return None;
+        }
+        let result =
+            crate::LocalValidator::pre_validate(&self.1.validator, parts).map_err(Into::into);
+        Some((self.1.name, result))
+    }
+}
+
+/// The validation over the nested provider pairs, for the [`crate::SyncValidator`]s.
+pub trait SyncProviders<Data: bytes::Buf, E>: Select {
+    /// Validate the request with the first provider matching the request head, if any.
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Option<(&'static str, Result<(), E>)>;
+
+    /// Check the request head with the first provider matching it, if any.
+    fn pre_validate(
+        &self,
+        parts: &mut http::request::Parts,
+    ) -> Option<(&'static str, Result<(), E>)>;
+}
+
+impl<Data: bytes::Buf, E> SyncProviders<Data, E> for () {
+    fn validate(
+        &self,
+        _parts: &http::request::Parts,
+        _buffered_body: &Data,
+    ) -> Option<(&'static str, Result<(), E>)> {
+        None
+    }
+
+    fn pre_validate(
+        &self,
+        _parts: &mut http::request::Parts,
+    ) -> Option<(&'static str, Result<(), E>)> {
+        None
+    }
+}
+
+impl<Data, E, Previous, P, V> SyncProviders<Data, E> for (Previous, Provider<P, V>)
+where
+    Data: bytes::Buf,
+    Previous: SyncProviders<Data, E>,
+    P: Predicate,
+    V: crate::SyncValidator<Data, Error: Into<E>>,
+{
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Option<(&'static str, Result<(), E>)> {
+        if let Some(selected) = self.0.validate(parts, buffered_body) {
+            return Some(selected);
+        }
+        if !self.1.predicate.matches(parts) {
+            return None;
+        }
+        let result = crate::SyncValidator::validate(&self.1.validator, parts, buffered_body)
+            .map_err(Into::into);
+        Some((self.1.name, result))
+    }
+
+    fn pre_validate(
+        &self,
+        parts: &mut http::request::Parts,
+    ) -> Option<(&'static str, Result<(), E>)> {
+        if let Some(selected) = self.0.pre_validate(parts) {
+            return Some(selected);
+        }
+        if !self.1.predicate.matches(parts) {
+            return None;
+        }
+        let result =
+            crate::SyncValidator::pre_validate(&self.1.validator, parts).map_err(Into::into);
+        Some((self.1.name, result))
+    }
+}
+
+/// Record the provider selected by the pre-validation, and attribute the error to it.
+fn selected<E>(
+    parts: &mut http::request::Parts,
+    selected: Option<(&'static str, Result<(), E>)>,
+) -> Result<(), Error<E>> {
+    let Some((name, result)) = selected else {
+        return Err(Error::NoProviderMatched);
+    };
+    parts.extensions.insert(MatchedProvider(name));
+    result.map_err(|error| Error::Provider { name, error })
+}
+
+impl<Data, E, Providers> crate::Validator<Data> for Dispatch<E, Providers>
+where
+    Data: bytes::Buf,
+    Providers: self::Providers<Data, E>,
+    E: Send,
+{
+    type Error = Error<E>;
+
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + Send + 'a {
+        let validation = self.providers.validate(parts, buffered_body);
+        async move {
+            let Some((name, validation)) = validation else {
+                return Err(Error::NoProviderMatched);
+            };
+            validation
+                .await
+                .map_err(|error| Error::Provider { name, error })
+        }
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        let selection = self.providers.pre_validate(parts);
+        selected(parts, selection)
+    }
+}
+
+impl<Data, E, Providers> crate::LocalValidator<Data> for Dispatch<E, Providers>
+where
+    Data: bytes::Buf,
+    Providers: LocalProviders<Data, E>,
+{
+    type Error = Error<E>;
+
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + 'a {
+        let validation = self.providers.validate(parts, buffered_body);
+        async move {
+            let Some((name, validation)) = validation else {
+                return Err(Error::NoProviderMatched);
+            };
+            validation
+                .await
+                .map_err(|error| Error::Provider { name, error })
+        }
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        let selection = LocalProviders::pre_validate(&self.providers, parts);
+        selected(parts, selection)
+    }
+}
+
+impl<Data, E, Providers> crate::SyncValidator<Data> for Dispatch<E, Providers>
+where
+    Data: bytes::Buf,
+    Providers: SyncProviders<Data, E>,
+{
+    type Error = Error<E>;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        let Some((name, result)) = self.providers.validate(parts, buffered_body) else {
+            return Err(Error::NoProviderMatched);
+        };
+        result.map_err(|error| Error::Provider { name, error })
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        let selection = SyncProviders::pre_validate(&self.providers, parts);
+        selected(parts, selection)
+    }
+}
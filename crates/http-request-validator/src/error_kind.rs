@@ -0,0 +1,136 @@
+//! [`ValidationErrorKind`] trait and [`ErrorKind`] classification.
+
+/// The classification of a validation error.
+///
+/// Used by the integrations to pick the response status code and headers without knowing
+/// the concrete error type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The credentials, like the signature header, are not present.
+    MissingCredentials,
+
+    /// The credentials are present, but the signature does not match.
+    BadSignature,
+
+    /// The credentials are valid, but expired, or the timestamp is out of the allowed window.
+    Expired,
+
+    /// The request has already been seen.
+    Replay,
+
+    /// The request is authenticated, but not allowed.
+    Forbidden,
+
+    /// The request, or the credentials in it, can't be parsed.
+    Malformed,
+
+    /// The request is too large.
+    TooLarge,
+
+    /// The requested resource does not exist.
+    NotFound,
+
+    /// The resource does not support the request method.
+    MethodNotAllowed,
+
+    /// The media type, or the content coding, of the request body is not supported.
+    UnsupportedMediaType,
+
+    /// The request can't be checked right now for the reasons on the server side, like
+    /// the keys to check it with not being available.
+    Unavailable,
+
+    /// The error is not classified.
+    Other,
+}
+
+impl ErrorKind {
+    /// The `snake_case` name of this kind, suitable for metric labels and logs.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::MissingCredentials => "missing_credentials",
+            Self::BadSignature => "bad_signature",
+            Self::Expired => "expired",
+            Self::Replay => "replay",
+            Self::Forbidden => "forbidden",
+            Self::Malformed => "malformed",
+            Self::TooLarge => "too_large",
+            Self::NotFound => "not_found",
+            Self::MethodNotAllowed => "method_not_allowed",
+            Self::UnsupportedMediaType => "unsupported_media_type",
+            Self::Unavailable => "unavailable",
+            Self::Other => "other",
+        }
+    }
+
+    /// The HTTP status code to respond with for this kind of error.
+    pub const fn status_code(self) -> http::StatusCode {
+        match self {
+            Self::MissingCredentials | Self::BadSignature | Self::Expired => {
+                http::StatusCode::UNAUTHORIZED
+            }
+            Self::Replay | Self::Forbidden | Self::Other => http::StatusCode::FORBIDDEN,
+            Self::Malformed => http::StatusCode::BAD_REQUEST,
+            Self::TooLarge => http::StatusCode::PAYLOAD_TOO_LARGE,
+            Self::NotFound => http::StatusCode::NOT_FOUND,
+            Self::MethodNotAllowed => http::StatusCode::METHOD_NOT_ALLOWED,
+            Self::UnsupportedMediaType => http::StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::Unavailable => http::StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
+
+/// The ability of a validation error to tell its [`ErrorKind`].
+///
+/// Implement this for the validator error types so that the error handlers can map them to
+/// the appropriate responses.
+pub trait ValidationErrorKind {
+    /// The kind of this error.
+    fn kind(&self) -> ErrorKind;
+
+    /// The `WWW-Authenticate` challenge to respond with, if any.
+    fn www_authenticate(&self) -> Option<http::HeaderValue> {
+        None
+    }
+}
+
+impl<T: ValidationErrorKind + ?Sized> ValidationErrorKind for &T {
+    fn kind(&self) -> ErrorKind {
+        (**self).kind()
+    }
+
+    fn www_authenticate(&self) -> Option<http::HeaderValue> {
+        (**self).www_authenticate()
+    }
+}
+
+impl ValidationErrorKind for str {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+impl ValidationErrorKind for core::convert::Infallible {
+    fn kind(&self) -> ErrorKind {
+        match *self {}
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ValidationErrorKind for alloc::string::String {
+    fn kind(&self) -> ErrorKind {
+        ErrorKind::Other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: ValidationErrorKind + ?Sized> ValidationErrorKind for alloc::boxed::Box<T> {
+    fn kind(&self) -> ErrorKind {
+        (**self).kind()
+    }
+
+    fn www_authenticate(&self) -> Option<http::HeaderValue> {
+        (**self).www_authenticate()
+    }
+}
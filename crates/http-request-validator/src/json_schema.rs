@@ -0,0 +1,163 @@
+//! [`JsonSchemaValidator`] for validating the JSON payloads against a JSON Schema.
+
+use alloc::{
+    string::{String, ToString as _},
+    vec::Vec,
+};
+
+/// The validator that parses the body as JSON and validates it against a JSON Schema.
+///
+/// The schema is compiled once, when the validator is created; only the schemas that do not
+/// reference external resources are supported.
+///
+/// This is a [`crate::SyncValidator`].
+///
+/// ## Examples
+///
+/// ```
+/// use http_request_validator::{json_schema::JsonSchemaValidator, validate_blocking};
+///
+/// let validator = JsonSchemaValidator::new(&serde_json::json!({
+///     "type": "object",
+///     "required": ["event"],
+///     "properties": { "event": { "type": "string" } },
+/// }))
+/// .unwrap();
+///
+/// let req = http::Request::new(bytes::Bytes::from_static(br#"{"event":"ping"}"#));
+/// assert!(validate_blocking(&validator, req).is_ok());
+///
+/// let req = http::Request::new(bytes::Bytes::from_static(br#"{"event":1}"#));
+/// let error = validate_blocking(&validator, req).unwrap_err();
+/// assert_eq!(error.violations()[0].instance_path, "/event");
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonSchemaValidator {
+    /// The compiled schema.
+    schema: jsonschema::Validator,
+}
+
+impl JsonSchemaValidator {
+    /// Compile the schema into a new [`JsonSchemaValidator`].
+    pub fn new(schema: &serde_json::Value) -> Result<Self, InvalidSchema> {
+        let schema = jsonschema::validator_for(schema).map_err(|error| InvalidSchema {
+            message: error.to_string(),
+        })?;
+        Ok(Self { schema })
+    }
+
+    /// Validate the parsed payload.
+    pub fn validate_value(&self, payload: &serde_json::Value) -> Result<(), Error> {
+        let violations = violations(&self.schema, payload);
+        if !violations.is_empty() {
+            return Err(Error::Schema(violations));
+        }
+        Ok(())
+    }
+}
+
+/// Collect the mismatches between the value and the compiled schema.
+pub(crate) fn violations(
+    schema: &jsonschema::Validator,
+    value: &serde_json::Value,
+) -> Vec<Violation> {
+    schema
+        .iter_errors(value)
+        .map(|error| Violation {
+            instance_path: error.instance_path().as_str().into(),
+            schema_path: error.schema_path().as_str().into(),
+            message: error.to_string(),
+        })
+        .collect()
+}
+
+/// The schema can not be compiled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidSchema {
+    /// The description of the problem.
+    pub message: String,
+}
+
+impl core::fmt::Display for InvalidSchema {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid JSON Schema: {}", self.message)
+    }
+}
+
+impl core::error::Error for InvalidSchema {}
+
+/// A single mismatch between the payload and the schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// The JSON pointer to the offending part of the payload.
+    pub instance_path: String,
+
+    /// The JSON pointer to the violated keyword in the schema.
+    pub schema_path: String,
+
+    /// The human-readable description of the mismatch.
+    pub message: String,
+}
+
+/// An error that can occur while validating the payload.
+#[derive(Debug)]
+pub enum Error {
+    /// The body is not valid JSON.
+    InvalidJson(serde_json::Error),
+    /// The payload does not match the schema.
+    Schema(Vec<Violation>),
+}
+
+impl Error {
+    /// The schema violations, if any.
+    pub fn violations(&self) -> &[Violation] {
+        match self {
+            Self::InvalidJson(_) => &[],
+            Self::Schema(violations) => violations,
+        }
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidJson(error) => write!(f, "invalid JSON: {error}"),
+            Self::Schema(violations) => {
+                f.write_str("payload does not match the schema")?;
+                for (index, violation) in violations.iter().enumerate() {
+                    let separator = if index == 0 { ": " } else { "; " };
+                    write!(
+                        f,
+                        "{separator}{}: {}",
+                        violation.instance_path, violation.message
+                    )?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+impl crate::ValidationErrorKind for Error {
+    fn kind(&self) -> crate::ErrorKind {
+        crate::ErrorKind::Malformed
+    }
+}
+
+impl<Data> crate::SyncValidator<Data> for JsonSchemaValidator
+where
+    Data: bytes::Buf + AsRef<[u8]>,
+{
+    type Error = Error;
+
+    fn validate(
+        &self,
+        _parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        let payload = serde_json::from_slice(buffered_body.as_ref()).map_err(Error::InvalidJson)?;
+        self.validate_value(&payload)
+    }
+}
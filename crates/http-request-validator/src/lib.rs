@@ -2,6 +2,33 @@
 
 #![no_std]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "alloc")]
+pub mod content_type;
+pub mod dispatch;
+pub mod error_kind;
+#[cfg(feature = "json-schema")]
+pub mod json_schema;
+#[cfg(feature = "alloc")]
+pub mod multipart;
+#[cfg(feature = "openapi")]
+pub mod openapi;
+pub mod predicate;
+pub mod response;
+pub mod signer;
+#[cfg(feature = "alloc")]
+pub mod sniff;
+#[cfg(feature = "alloc")]
+pub mod tls;
+
+pub use self::dispatch::{Dispatch, MatchedProvider};
+pub use self::error_kind::{ErrorKind, ValidationErrorKind};
+pub use self::predicate::{Predicate, When};
+pub use self::response::{ResponseSigner, ResponseValidator};
+pub use self::signer::{sign_blocking, Signer, SyncSigner};
+
 /// The [`http::Request`] validator.
 ///
 /// Runs over the buffered request body, so can be used to implement the request signature
@@ -19,6 +46,18 @@ pub trait Validator<Data: bytes::Buf> {
         parts: &'a http::request::Parts,
         buffered_body: &'a Data,
     ) -> impl core::future::Future<Output = Result<(), Self::Error>> + Send + 'a;
+
+    /// Check the request head before the body is buffered.
+    ///
+    /// The integrations reject the request without reading the body if this fails. This is also
+    /// the only phase that can record something in the [`http::Extensions`]: the results for the
+    /// downstream handlers, or the state for the validation under a type private to the validator.
+    /// Not every caller runs this phase, so the validation still has to check everything itself.
+    /// Passes by default.
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        let _ = parts;
+        Ok(())
+    }
 }
 
 impl<T: ?Sized, Data> Validator<Data> for T
@@ -34,6 +73,203 @@ where
         parts: &'a http::request::Parts,
         buffered_body: &'a Data,
     ) -> impl core::future::Future<Output = Result<(), Self::Error>> + 'a {
-        self.deref().validate(parts, buffered_body)
+        Validator::validate(&**self, parts, buffered_body)
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        Validator::pre_validate(&**self, parts)
+    }
+}
+
+/// The [`http::Request`] validator that is not required to be thread-safe.
+///
+/// The same as the [`Validator`], but the returned future does not have to be [`Send`], so
+/// the validator can hold `Rc` state or rely on a single-threaded runtime.
+///
+/// Wrap a [`Validator`] into [`AsLocal`] to use it where a [`LocalValidator`] is expected.
+pub trait LocalValidator<Data: bytes::Buf> {
+    /// An error that can occur during validation.
+    type Error;
+
+    /// Validate the request header and buffered body.
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + 'a;
+
+    /// Check the request head before the body is buffered, see [`Validator::pre_validate`].
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        let _ = parts;
+        Ok(())
     }
 }
+
+impl<T: ?Sized, Data> LocalValidator<Data> for T
+where
+    T: core::ops::Deref,
+    <T as core::ops::Deref>::Target: LocalValidator<Data>,
+    Data: bytes::Buf,
+{
+    type Error = <<T as core::ops::Deref>::Target as LocalValidator<Data>>::Error;
+
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + 'a {
+        LocalValidator::validate(&**self, parts, buffered_body)
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        LocalValidator::pre_validate(&**self, parts)
+    }
+}
+
+/// The adapter to use a [`Validator`] as a [`LocalValidator`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AsLocal<V>(pub V);
+
+impl<V, Data> LocalValidator<Data> for AsLocal<V>
+where
+    V: Validator<Data>,
+    Data: bytes::Buf,
+{
+    type Error = V::Error;
+
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + 'a {
+        Validator::validate(&self.0, parts, buffered_body)
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        Validator::pre_validate(&self.0, parts)
+    }
+}
+
+/// The [`http::Request`] validator that runs synchronously.
+///
+/// Implement this trait for the validation logic that does not need to await anything, like
+/// the signature checks, and it can be used both from the blocking code via
+/// [`validate_blocking`] and from the async code via [`AsAsync`].
+///
+/// Most of the validators shipped with this and the sibling crates are synchronous; the async
+/// integrations, like the axum layer, take a [`Validator`], so wrap them into [`AsAsync`] there.
+pub trait SyncValidator<Data: bytes::Buf> {
+    /// An error that can occur during validation.
+    type Error;
+
+    /// Validate the request header and buffered body.
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error>;
+
+    /// Check the request head before the body is buffered, see [`Validator::pre_validate`].
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        let _ = parts;
+        Ok(())
+    }
+}
+
+impl<T: ?Sized, Data> SyncValidator<Data> for T
+where
+    T: core::ops::Deref,
+    <T as core::ops::Deref>::Target: SyncValidator<Data>,
+    Data: bytes::Buf,
+{
+    type Error = <<T as core::ops::Deref>::Target as SyncValidator<Data>>::Error;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        SyncValidator::validate(&**self, parts, buffered_body)
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        SyncValidator::pre_validate(&**self, parts)
+    }
+}
+
+/// The adapter to use a [`SyncValidator`] as a [`Validator`], or a [`SyncSigner`] as a
+/// [`Signer`].
+///
+/// This is how the synchronous validators and signers are passed to the async integrations;
+/// the check runs inline, as it does not await anything.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct AsAsync<V>(pub V);
+
+impl<V, Data> Validator<Data> for AsAsync<V>
+where
+    V: SyncValidator<Data, Error: Send>,
+    Data: bytes::Buf,
+{
+    type Error = V::Error;
+
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + Send + 'a {
+        core::future::ready(SyncValidator::validate(&self.0, parts, buffered_body))
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        SyncValidator::pre_validate(&self.0, parts)
+    }
+}
+
+/// Validate the already buffered request without an async runtime.
+///
+/// Returns the request back if it passes the validation.
+///
+/// ## Examples
+///
+/// ```
+/// use http_request_validator::{validate_blocking, SyncValidator};
+///
+/// struct EmptyValidator;
+///
+/// impl<Data: bytes::Buf> SyncValidator<Data> for EmptyValidator {
+///     type Error = &'static str;
+///
+///     fn validate(
+///         &self,
+///         _parts: &http::request::Parts,
+///         buffered_body: &Data,
+///     ) -> Result<(), Self::Error> {
+///         if buffered_body.has_remaining() {
+///             return Err("body not empty");
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let req = http::Request::new(bytes::Bytes::new());
+/// assert!(validate_blocking(&EmptyValidator, req).is_ok());
+///
+/// let req = http::Request::new(bytes::Bytes::from_static(b"payload"));
+/// assert_eq!(validate_blocking(&EmptyValidator, req).unwrap_err(), "body not empty");
+/// ```
+pub fn validate_blocking<Validator, Data>(
+    validator: &Validator,
+    req: http::Request<Data>,
+) -> Result<http::Request<Data>, Validator::Error>
+where
+    Validator: SyncValidator<Data> + ?Sized,
+    Data: bytes::Buf,
+{
+    let (mut parts, body) = req.into_parts();
+    validator.pre_validate(&mut parts)?;
+    validator.validate(&parts, &body)?;
+    Ok(http::Request::from_parts(parts, body))
+}
+
+#[cfg(test)]
+mod tests;
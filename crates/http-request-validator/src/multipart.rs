@@ -0,0 +1,510 @@
+//! [`Multipart`] parser and [`MultipartValidator`] for validating the `multipart/*` bodies
+//! part by part.
+//!
+//! The parser works over the buffered body and hands out the parts as slices of it, so the
+//! bodies are not copied. To get an owned handle on a part of a [`bytes::Bytes`] body, use
+//! [`bytes::Bytes::slice_ref`].
+
+use alloc::{string::String, vec::Vec};
+
+use crate::content_type::{parse_value, MediaType};
+
+/// The limits the [`Multipart`] parser enforces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Limits {
+    /// The maximum number of parts.
+    pub max_parts: usize,
+
+    /// The maximum size of a part body, in bytes.
+    pub max_part_size: usize,
+
+    /// The maximum size of the headers of a part, in bytes.
+    pub max_headers_size: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            max_parts: 100,
+            max_part_size: 10 * 1024 * 1024,
+            max_headers_size: 8 * 1024,
+        }
+    }
+}
+
+/// The parser of a `multipart/*` body.
+///
+/// Iterates over the parts in order, stopping at the first error.
+///
+/// ## Examples
+///
+/// ```
+/// use http_request_validator::multipart::Multipart;
+///
+/// let req = http::Request::post("/")
+///     .header("content-type", "multipart/form-data; boundary=XyZ")
+///     .body(bytes::Bytes::from_static(
+///         b"--XyZ\r\n\
+///           Content-Disposition: form-data; name=\"manifest\"\r\n\
+///           \r\n\
+///           {\"files\":1}\r\n\
+///           --XyZ\r\n\
+///           Content-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\n\
+///           Content-Type: text/plain\r\n\
+///           \r\n\
+///           hello\r\n\
+///           --XyZ--\r\n",
+///     ))
+///     .unwrap();
+/// let (parts, body) = req.into_parts();
+///
+/// let multipart = Multipart::from_request(&parts, &body).unwrap();
+/// let parts: Vec<_> = multipart.collect::<Result<_, _>>().unwrap();
+/// assert_eq!(parts[0].name().as_deref(), Some("manifest"));
+/// assert_eq!(parts[1].filename().as_deref(), Some("a.txt"));
+/// assert_eq!(parts[1].content_type(), Some(&b"text/plain"[..]));
+/// assert_eq!(parts[1].body(), b"hello");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Multipart<'a> {
+    /// The body left to parse, right after a boundary delimiter.
+    rest: &'a [u8],
+
+    /// The delimiter preceding the boundaries after the first one, `CRLF--boundary`.
+    delimiter: Vec<u8>,
+
+    /// The limits to enforce.
+    limits: Limits,
+
+    /// The number of parts parsed so far.
+    count: usize,
+
+    /// Whether the closing delimiter has been reached, or an error has been returned.
+    done: bool,
+}
+
+impl<'a> Multipart<'a> {
+    /// Create a new parser of the body with the given boundary.
+    pub fn new(body: &'a [u8], boundary: &str) -> Result<Self, Error> {
+        if !(1..=70).contains(&boundary.len()) {
+            return Err(Error::InvalidBoundary);
+        }
+
+        let mut delimiter = Vec::with_capacity(boundary.len() + 4);
+        delimiter.extend_from_slice(b"\r\n--");
+        delimiter.extend_from_slice(boundary.as_bytes());
+
+        // The first delimiter may come without the leading CRLF, at the very start.
+        let rest = if body.starts_with(&delimiter[2..]) {
+            &body[delimiter.len() - 2..]
+        } else {
+            let start = find(body, &delimiter).ok_or(Error::MalformedBody)?;
+            &body[start + delimiter.len()..]
+        };
+
+        Ok(Self {
+            rest,
+            delimiter,
+            limits: Limits::default(),
+            count: 0,
+            done: false,
+        })
+    }
+
+    /// Create a new parser of the request body, taking the boundary from the `Content-Type`.
+    pub fn from_request(parts: &http::request::Parts, body: &'a [u8]) -> Result<Self, Error> {
+        let media_type = parts
+            .headers
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(MediaType::parse)
+            .filter(|media_type| media_type.essence().starts_with("multipart/"))
+            .ok_or(Error::NotMultipart)?;
+        let boundary = media_type
+            .parameter("boundary")
+            .ok_or(Error::InvalidBoundary)?;
+        Self::new(body, boundary)
+    }
+
+    /// Set the limits to enforce.
+    pub const fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Parse the next part.
+    fn parse_next(&mut self) -> Result<Option<Part<'a>>, Error> {
+        if self.rest.starts_with(b"--") {
+            return Ok(None);
+        }
+
+        // Skip the transport padding after the delimiter.
+        let padding = self
+            .rest
+            .iter()
+            .take_while(|&&byte| byte == b' ' || byte == b'\t')
+            .count();
+        let rest = self.rest[padding..]
+            .strip_prefix(b"\r\n")
+            .ok_or(Error::MalformedBody)?;
+
+        if self.count == self.limits.max_parts {
+            return Err(Error::TooManyParts {
+                max_parts: self.limits.max_parts,
+            });
+        }
+
+        let (headers, rest) = if let Some(rest) = rest.strip_prefix(b"\r\n") {
+            (&rest[..0], rest)
+        } else {
+            let window = &rest[..rest.len().min(self.limits.max_headers_size + 4)];
+            let Some(end) = find(window, b"\r\n\r\n") else {
+                if window.len() < rest.len() {
+                    return Err(Error::HeadersTooLarge {
+                        max_headers_size: self.limits.max_headers_size,
+                    });
+                }
+                return Err(Error::MalformedBody);
+            };
+            (&rest[..end], &rest[end + 4..])
+        };
+        if headers.len() > self.limits.max_headers_size {
+            return Err(Error::HeadersTooLarge {
+                max_headers_size: self.limits.max_headers_size,
+            });
+        }
+        if !header_lines(headers).all(|line| parse_header(line).is_some()) {
+            return Err(Error::MalformedHeaders);
+        }
+
+        // Look for the delimiter only as far as the largest part allowed could reach.
+        let window = &rest[..rest.len().min(
+            self.limits
+                .max_part_size
+                .saturating_add(self.delimiter.len()),
+        )];
+        let Some(end) = find(window, &self.delimiter) else {
+            if window.len() < rest.len() {
+                return Err(Error::PartTooLarge {
+                    max_part_size: self.limits.max_part_size,
+                });
+            }
+            return Err(Error::MalformedBody);
+        };
+
+        self.rest = &rest[end + self.delimiter.len()..];
+        self.count += 1;
+        Ok(Some(Part {
+            headers,
+            body: &rest[..end],
+        }))
+    }
+}
+
+impl<'a> Iterator for Multipart<'a> {
+    type Item = Result<Part<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = self.parse_next().transpose();
+        if !matches!(result, Some(Ok(_))) {
+            self.done = true;
+        }
+        result
+    }
+}
+
+/// A part of the multipart body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Part<'a> {
+    /// The raw headers, without the trailing empty line.
+    headers: &'a [u8],
+
+    /// The body.
+    body: &'a [u8],
+}
+
+impl<'a> Part<'a> {
+    /// The headers, as the names and the trimmed values, in order.
+    pub fn headers(&self) -> impl Iterator<Item = (&'a str, &'a [u8])> {
+        header_lines(self.headers).filter_map(parse_header)
+    }
+
+    /// The value of the first header with the given case-insensitive name.
+    pub fn header(&self, name: &str) -> Option<&'a [u8]> {
+        self.headers()
+            .find(|(candidate, _)| candidate.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
+    /// The `name` parameter of the `Content-Disposition`.
+    pub fn name(&self) -> Option<String> {
+        self.disposition_parameter("name")
+    }
+
+    /// The `filename` parameter of the `Content-Disposition`.
+    pub fn filename(&self) -> Option<String> {
+        self.disposition_parameter("filename")
+    }
+
+    /// The `Content-Type`.
+    pub fn content_type(&self) -> Option<&'a [u8]> {
+        self.header("content-type")
+    }
+
+    /// The body.
+    pub fn body(&self) -> &'a [u8] {
+        self.body
+    }
+
+    /// The value of the `Content-Disposition` parameter with the given name.
+    ///
+    /// The quoted values are returned unquoted and unescaped, like the media type parameters.
+    fn disposition_parameter(&self, name: &str) -> Option<String> {
+        let value = core::str::from_utf8(self.header("content-disposition")?).ok()?;
+        let mut rest = value.split_once(';')?.1;
+        loop {
+            rest = rest.trim_start_matches([' ', '\t', ';']);
+            let (candidate, after_name) = rest.split_once('=')?;
+            let (value, after_value) = parse_value(after_name)?;
+            if candidate.trim().eq_ignore_ascii_case(name) {
+                return Some(value);
+            }
+            rest = after_value;
+        }
+    }
+}
+
+/// The header lines of the raw headers.
+fn header_lines(headers: &[u8]) -> impl Iterator<Item = &[u8]> {
+    headers
+        .split(|&byte| byte == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+        .filter(|line| !line.is_empty())
+}
+
+/// Split the header line into the name and the trimmed value.
+fn parse_header(line: &[u8]) -> Option<(&str, &[u8])> {
+    let colon = line.iter().position(|&byte| byte == b':')?;
+    let name = core::str::from_utf8(&line[..colon]).ok()?;
+    if name.is_empty() || !name.bytes().all(|byte| byte.is_ascii_graphic()) {
+        return None;
+    }
+    Some((name, line[colon + 1..].trim_ascii()))
+}
+
+/// The position of the needle in the haystack.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// An error that can occur while parsing the multipart body.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The `Content-Type` is missing, or is not a `multipart/*` one.
+    NotMultipart,
+    /// The boundary is missing or invalid.
+    InvalidBoundary,
+    /// The body is not framed with the boundary delimiters properly.
+    MalformedBody,
+    /// The headers of a part can not be parsed.
+    MalformedHeaders,
+    /// There are too many parts.
+    TooManyParts {
+        /// The maximum number of parts.
+        max_parts: usize,
+    },
+    /// A part body is too large.
+    PartTooLarge {
+        /// The maximum size of a part body, in bytes.
+        max_part_size: usize,
+    },
+    /// The headers of a part are too large.
+    HeadersTooLarge {
+        /// The maximum size of the headers of a part, in bytes.
+        max_headers_size: usize,
+    },
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NotMultipart => f.write_str("not a multipart body"),
+            Self::InvalidBoundary => f.write_str("missing or invalid multipart boundary"),
+            Self::MalformedBody => f.write_str("malformed multipart body"),
+            Self::MalformedHeaders => f.write_str("malformed multipart part headers"),
+            Self::TooManyParts { max_parts } => write!(f, "more than {max_parts} parts"),
+            Self::PartTooLarge { max_part_size } => {
+                write!(f, "part body exceeds {max_part_size} bytes")
+            }
+            Self::HeadersTooLarge { max_headers_size } => {
+                write!(f, "part headers exceed {max_headers_size} bytes")
+            }
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+impl crate::ValidationErrorKind for Error {
+    fn kind(&self) -> crate::ErrorKind {
+        match self {
+            Self::TooManyParts { .. }
+            | Self::PartTooLarge { .. }
+            | Self::HeadersTooLarge { .. } => crate::ErrorKind::TooLarge,
+            Self::NotMultipart
+            | Self::InvalidBoundary
+            | Self::MalformedBody
+            | Self::MalformedHeaders => crate::ErrorKind::Malformed,
+        }
+    }
+}
+
+/// The validator that parses the `multipart/*` body within the limits, and checks every part.
+///
+/// The check is any closure over the [`Part`]; use `|_: &Part<'_>| Ok::<_, core::convert::Infallible>(())`
+/// to only enforce the framing and the limits. The `Content-Type` is checked before the body
+/// is buffered. This is a [`crate::SyncValidator`].
+///
+/// ## Examples
+///
+/// ```
+/// use http_request_validator::{
+///     multipart::{Limits, MultipartValidator, Part},
+///     validate_blocking,
+/// };
+///
+/// let validator = MultipartValidator::new(|part: &Part<'_>| match part.filename() {
+///     Some(filename) if filename.ends_with(".exe") => Err("executables are not allowed"),
+///     _ => Ok(()),
+/// })
+/// .with_limits(Limits {
+///     max_parts: 2,
+///     ..Limits::default()
+/// });
+///
+/// let req = |filename: &str| {
+///     http::Request::post("/")
+///         .header("content-type", "multipart/form-data; boundary=XyZ")
+///         .body(bytes::Bytes::from(format!(
+///             "--XyZ\r\n\
+///              Content-Disposition: form-data; name=\"file\"; filename=\"{filename}\"\r\n\
+///              \r\n\
+///              hello\r\n\
+///              --XyZ--\r\n"
+///         )))
+///         .unwrap()
+/// };
+/// assert!(validate_blocking(&validator, req("a.txt")).is_ok());
+/// assert!(validate_blocking(&validator, req("a.exe")).is_err());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MultipartValidator<Check> {
+    /// The check of every part.
+    check: Check,
+
+    /// The limits to enforce.
+    limits: Limits,
+}
+
+impl<Check> MultipartValidator<Check> {
+    /// Create a new [`MultipartValidator`] with the given check of every part.
+    pub fn new(check: Check) -> Self {
+        Self {
+            check,
+            limits: Limits::default(),
+        }
+    }
+
+    /// Set the limits to enforce.
+    pub const fn with_limits(mut self, limits: Limits) -> Self {
+        self.limits = limits;
+        self
+    }
+}
+
+impl<Check, CheckError, Data> crate::SyncValidator<Data> for MultipartValidator<Check>
+where
+    Check: Fn(&Part<'_>) -> Result<(), CheckError>,
+    Data: bytes::Buf + AsRef<[u8]>,
+{
+    type Error = ValidatorError<CheckError>;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        let multipart = Multipart::from_request(parts, buffered_body.as_ref())
+            .map_err(ValidatorError::Multipart)?
+            .with_limits(self.limits);
+        for part in multipart {
+            let part = part.map_err(ValidatorError::Multipart)?;
+            (self.check)(&part).map_err(ValidatorError::Part)?;
+        }
+        Ok(())
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        let media_type = parts
+            .headers
+            .get(http::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(MediaType::parse)
+            .filter(|media_type| media_type.essence().starts_with("multipart/"))
+            .ok_or(ValidatorError::Multipart(Error::NotMultipart))?;
+        if media_type.parameter("boundary").is_none() {
+            return Err(ValidatorError::Multipart(Error::InvalidBoundary));
+        }
+        Ok(())
+    }
+}
+
+/// An error that can occur while validating the multipart body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidatorError<CheckError> {
+    /// The body can not be parsed, or is over the limits.
+    Multipart(Error),
+    /// A part has failed the check.
+    Part(CheckError),
+}
+
+impl<CheckError: core::fmt::Display> core::fmt::Display for ValidatorError<CheckError> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Multipart(error) => error.fmt(f),
+            Self::Part(error) => write!(f, "invalid multipart part: {error}"),
+        }
+    }
+}
+
+impl<CheckError: core::error::Error + 'static> core::error::Error for ValidatorError<CheckError> {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Multipart(error) => Some(error),
+            Self::Part(error) => Some(error),
+        }
+    }
+}
+
+impl<CheckError: crate::ValidationErrorKind> crate::ValidationErrorKind
+    for ValidatorError<CheckError>
+{
+    fn kind(&self) -> crate::ErrorKind {
+        match self {
+            Self::Multipart(error) => error.kind(),
+            Self::Part(error) => error.kind(),
+        }
+    }
+
+    fn www_authenticate(&self) -> Option<http::HeaderValue> {
+        match self {
+            Self::Multipart(_) => None,
+            Self::Part(error) => error.www_authenticate(),
+        }
+    }
+}
@@ -0,0 +1,762 @@
+//! [`OpenApiValidator`] for enforcing an `OpenAPI` 3.x contract on the requests.
+
+use alloc::{
+    borrow::{Cow, ToOwned as _},
+    format,
+    string::{String, ToString as _},
+    vec::Vec,
+};
+
+use serde_json::Value;
+
+use crate::json_schema::{violations, Violation};
+
+/// The URI the `OpenAPI` document is registered under, for resolving the `$ref`s in the schemas.
+const DOCUMENT_URI: &str = "urn:http-request-validator:openapi";
+
+/// The limit of the chained `$ref`s, to protect against the reference cycles.
+const MAX_REF_DEPTH: usize = 32;
+
+/// The HTTP methods an `OpenAPI` path item can describe an operation for.
+const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// The validator that checks the requests against the operations described in an `OpenAPI` 3.x
+/// document.
+///
+/// Matches the request method and path to an operation, then checks the path, query and header
+/// parameters, the content type and, for the JSON media types, the body against the request body
+/// schema. The document is loaded and all the schemas are compiled once, when the validator is
+/// created.
+///
+/// The paths are matched against the request path as is, without the server URL prefixes.
+/// Only the templates that occupy whole path segments, like `/pets/{petId}`, are supported, and
+/// the parameter values are matched as plain strings, or as JSON if they do not match as
+/// strings, so `style` and `explode` are not taken into account. Every value of a repeated query
+/// parameter or header is checked on its own. The `cookie` parameters are not checked. Only the
+/// local `$ref`s are resolved.
+///
+/// The schemas are interpreted as JSON Schema 2020-12, like in `OpenAPI` 3.1. For the `OpenAPI`
+/// 3.0 documents, the `nullable` and the boolean `exclusiveMinimum` and `exclusiveMaximum` are
+/// translated to their JSON Schema 2020-12 counterparts first.
+///
+/// This is a [`crate::SyncValidator`].
+///
+/// ## Examples
+///
+/// ```
+/// use http_request_validator::{openapi::OpenApiValidator, validate_blocking};
+///
+/// let validator = OpenApiValidator::new(&serde_json::json!({
+///     "openapi": "3.1.0",
+///     "info": { "title": "Pets", "version": "1" },
+///     "paths": {
+///         "/pets/{petId}": {
+///             "get": {
+///                 "parameters": [{
+///                     "name": "petId",
+///                     "in": "path",
+///                     "required": true,
+///                     "schema": { "type": "integer" },
+///                 }],
+///             },
+///         },
+///     },
+/// }))
+/// .unwrap();
+///
+/// let req = http::Request::get("/pets/42").body(bytes::Bytes::new()).unwrap();
+/// assert!(validate_blocking(&validator, req).is_ok());
+///
+/// let req = http::Request::get("/pets/rex").body(bytes::Bytes::new()).unwrap();
+/// assert!(validate_blocking(&validator, req).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct OpenApiValidator {
+    /// The operations, the ones with fewer templated path segments first.
+    operations: Vec<Operation>,
+}
+
+/// An operation from the document.
+#[derive(Debug, Clone)]
+struct Operation {
+    /// The HTTP method.
+    method: http::Method,
+
+    /// The path template.
+    path: Vec<Segment>,
+
+    /// The parameters, including the ones defined for the whole path item.
+    parameters: Vec<Parameter>,
+
+    /// The request body, if described.
+    request_body: Option<RequestBody>,
+}
+
+/// A segment of the path template.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    /// The segment that has to match exactly.
+    Literal(String),
+    /// The segment that holds the path parameter with the given name.
+    Parameter(String),
+}
+
+/// A parameter of the operation.
+#[derive(Debug, Clone)]
+struct Parameter {
+    /// The name of the parameter.
+    name: String,
+
+    /// Where the parameter is passed.
+    location: Location,
+
+    /// Whether the parameter has to be present.
+    required: bool,
+
+    /// The schema of the parameter value.
+    schema: Option<jsonschema::Validator>,
+}
+
+/// The request body of the operation.
+#[derive(Debug, Clone)]
+struct RequestBody {
+    /// Whether the body has to be present.
+    required: bool,
+
+    /// The allowed media types, or media type ranges, and the schemas of the corresponding
+    /// payloads.
+    content: Vec<(String, Option<jsonschema::Validator>)>,
+}
+
+/// Where the parameter is passed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    /// In the path.
+    Path,
+    /// In the query string.
+    Query,
+    /// In a header.
+    Header,
+}
+
+impl Location {
+    /// The name of this location in the `OpenAPI` documents.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Path => "path",
+            Self::Query => "query",
+            Self::Header => "header",
+        }
+    }
+}
+
+impl OpenApiValidator {
+    /// Load the `OpenAPI` document into a new [`OpenApiValidator`].
+    pub fn new(document: &Value) -> Result<Self, InvalidDocument> {
+        let version = document
+            .get("openapi")
+            .and_then(Value::as_str)
+            .filter(|version| version.starts_with("3."))
+            .ok_or_else(|| InvalidDocument::new("only OpenAPI 3.x documents are supported"))?;
+
+        let mut contents = document.clone();
+        if version == "3.0" || version.starts_with("3.0.") {
+            translate_3_0(&mut contents);
+        }
+        let registry = jsonschema::Registry::try_new(
+            DOCUMENT_URI,
+            jsonschema::Resource::from_contents(contents),
+        )
+        .map_err(|error| InvalidDocument::new(error.to_string()))?;
+        // The translation keeps the paths, the parameters and the request bodies where they
+        // are, so they are loaded from the document as given.
+        let loader = Loader {
+            document,
+            options: jsonschema::options().with_registry(registry),
+        };
+
+        let mut operations = Vec::new();
+        let paths = document.get("paths").and_then(Value::as_object);
+        for (path, path_item) in paths.into_iter().flatten() {
+            let pointer = format!("/paths/{}", escape(path));
+            let (pointer, path_item) = loader.resolve(pointer, path_item)?;
+            let common_parameters = loader.parameters(&pointer, path_item)?;
+
+            for method in METHODS {
+                let Some(operation) = path_item.get(method) else {
+                    continue;
+                };
+                let pointer = format!("{pointer}/{method}");
+
+                let mut parameters = loader.parameters(&pointer, operation)?;
+                for parameter in &common_parameters {
+                    let overridden = parameters.iter().any(|overriding| {
+                        overriding.name == parameter.name
+                            && overriding.location == parameter.location
+                    });
+                    if !overridden {
+                        parameters.push(parameter.clone());
+                    }
+                }
+
+                let request_body = match operation.get("requestBody") {
+                    Some(request_body) => {
+                        Some(loader.request_body(format!("{pointer}/requestBody"), request_body)?)
+                    }
+                    None => None,
+                };
+
+                operations.push(Operation {
+                    method: method
+                        .to_ascii_uppercase()
+                        .parse()
+                        .expect("the known methods are valid"),
+                    path: parse_template(path),
+                    parameters,
+                    request_body,
+                });
+            }
+        }
+
+        operations.sort_by_key(|operation| {
+            operation
+                .path
+                .iter()
+                .filter(|segment| matches!(segment, Segment::Parameter(_)))
+                .count()
+        });
+        Ok(Self { operations })
+    }
+
+    /// Validate the request head and body against the matching operation.
+    pub fn validate_request(&self, parts: &http::request::Parts, body: &[u8]) -> Result<(), Error> {
+        let (template, path_parameters) = self
+            .operations
+            .iter()
+            .find_map(|operation| Some((&operation.path, operation.match_path(parts.uri.path())?)))
+            .ok_or(Error::UnknownPath)?;
+
+        // Only the operations of the most specific matching path are considered, so a literal
+        // path does not fall through to a templated one for the other methods.
+        let operation = self
+            .operations
+            .iter()
+            .find(|operation| operation.path == *template && operation.method == parts.method)
+            .ok_or(Error::MethodNotAllowed)?;
+        operation.validate(parts, &path_parameters, body)
+    }
+}
+
+impl Operation {
+    /// Match the request path against the template, returning the path parameter values.
+    fn match_path<'a>(&'a self, path: &str) -> Option<Vec<(&'a str, String)>> {
+        let mut values = Vec::new();
+        let mut segments = path.strip_prefix('/')?.split('/');
+        for template in &self.path {
+            let segment = segments.next()?;
+            match template {
+                Segment::Literal(literal) if *literal == percent_decode(segment, false) => {}
+                Segment::Literal(_) => return None,
+                Segment::Parameter(_) if segment.is_empty() => return None,
+                Segment::Parameter(name) => values.push((&**name, percent_decode(segment, false))),
+            }
+        }
+        if segments.next().is_some() {
+            return None;
+        }
+        Some(values)
+    }
+
+    /// Validate the request against this operation.
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        path_parameters: &[(&str, String)],
+        body: &[u8],
+    ) -> Result<(), Error> {
+        let query = parts.uri.query().map(parse_query).unwrap_or_default();
+
+        for parameter in &self.parameters {
+            let values: Vec<Cow<'_, str>> = match parameter.location {
+                Location::Path => path_parameters
+                    .iter()
+                    .filter(|(name, _)| *name == parameter.name)
+                    .map(|(_, value)| Cow::Borrowed(&**value))
+                    .collect(),
+                Location::Query => query
+                    .iter()
+                    .filter(|(name, _)| *name == parameter.name)
+                    .map(|(_, value)| Cow::Borrowed(&**value))
+                    .collect(),
+                Location::Header => parts
+                    .headers
+                    .get_all(&*parameter.name)
+                    .iter()
+                    .map(|value| String::from_utf8_lossy(value.as_bytes()))
+                    .collect(),
+            };
+
+            if values.is_empty() {
+                if parameter.required {
+                    return Err(Error::MissingParameter {
+                        location: parameter.location,
+                        name: parameter.name.clone(),
+                    });
+                }
+                continue;
+            }
+
+            let Some(schema) = &parameter.schema else {
+                continue;
+            };
+            for value in &values {
+                let violations = parameter_violations(schema, value);
+                if !violations.is_empty() {
+                    return Err(Error::InvalidParameter {
+                        location: parameter.location,
+                        name: parameter.name.clone(),
+                        violations,
+                    });
+                }
+            }
+        }
+
+        if let Some(request_body) = &self.request_body {
+            request_body.validate(parts, body)?;
+        }
+        Ok(())
+    }
+}
+
+impl RequestBody {
+    /// Validate the request body.
+    fn validate(&self, parts: &http::request::Parts, body: &[u8]) -> Result<(), Error> {
+        let content_type = parts.headers.get(http::header::CONTENT_TYPE);
+        if body.is_empty() && content_type.is_none() {
+            if self.required {
+                return Err(Error::MissingBody);
+            }
+            return Ok(());
+        }
+
+        let essence = content_type
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase())
+            .ok_or(Error::UnsupportedContentType)?;
+        let range = essence
+            .split_once('/')
+            .map(|(kind, _)| format!("{kind}/*"))
+            .unwrap_or_default();
+
+        let schema = [&*essence, &*range, "*/*"]
+            .into_iter()
+            .find_map(|candidate| {
+                self.content
+                    .iter()
+                    .find(|(media_type, _)| media_type.eq_ignore_ascii_case(candidate))
+            })
+            .ok_or(Error::UnsupportedContentType)?
+            .1
+            .as_ref();
+
+        let is_json = essence == "application/json" || essence.ends_with("+json");
+        if let (Some(schema), true) = (schema, is_json) {
+            let payload = serde_json::from_slice(body)
+                .map_err(|error| Error::Body(crate::json_schema::Error::InvalidJson(error)))?;
+            let violations = violations(schema, &payload);
+            if !violations.is_empty() {
+                return Err(Error::Body(crate::json_schema::Error::Schema(violations)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The loader of the operations from the document.
+struct Loader<'a> {
+    /// The document.
+    document: &'a Value,
+
+    /// The options for compiling the schemas, with the registry holding the document.
+    options: jsonschema::ValidationOptions,
+}
+
+impl<'a> Loader<'a> {
+    /// Follow the `$ref`s, returning the JSON pointer to and the value of the referenced object.
+    fn resolve(
+        &self,
+        mut pointer: String,
+        mut value: &'a Value,
+    ) -> Result<(String, &'a Value), InvalidDocument> {
+        for _ in 0..MAX_REF_DEPTH {
+            let Some(reference) = value.get("$ref").and_then(Value::as_str) else {
+                return Ok((pointer, value));
+            };
+            let target = reference.strip_prefix('#').ok_or_else(|| {
+                InvalidDocument::new(format!("only local references are supported: {reference}"))
+            })?;
+            value = self.document.pointer(target).ok_or_else(|| {
+                InvalidDocument::new(format!("unresolvable reference: {reference}"))
+            })?;
+            pointer = target.to_owned();
+        }
+        Err(InvalidDocument::new(format!(
+            "too many nested references at {pointer}"
+        )))
+    }
+
+    /// Compile the schema at the given JSON pointer.
+    fn schema(&self, pointer: &str) -> Result<jsonschema::Validator, InvalidDocument> {
+        let reference = format!("{DOCUMENT_URI}#{}", encode_fragment(pointer));
+        self.options
+            .build(&serde_json::json!({ "$ref": reference }))
+            .map_err(|error| InvalidDocument::new(format!("invalid schema at {pointer}: {error}")))
+    }
+
+    /// Load the parameters of the path item or operation at the given JSON pointer.
+    fn parameters(&self, pointer: &str, object: &Value) -> Result<Vec<Parameter>, InvalidDocument> {
+        let mut parameters = Vec::new();
+        let list = object.get("parameters").and_then(Value::as_array);
+        for (index, parameter) in list.into_iter().flatten().enumerate() {
+            let (pointer, parameter) =
+                self.resolve(format!("{pointer}/parameters/{index}"), parameter)?;
+
+            let location = match parameter.get("in").and_then(Value::as_str) {
+                Some("path") => Location::Path,
+                Some("query") => Location::Query,
+                Some("header") => Location::Header,
+                Some("cookie") => continue,
+                _ => {
+                    return Err(InvalidDocument::new(format!(
+                        "invalid parameter at {pointer}"
+                    )))
+                }
+            };
+            let name = parameter
+                .get("name")
+                .and_then(Value::as_str)
+                .ok_or_else(|| InvalidDocument::new(format!("unnamed parameter at {pointer}")))?;
+
+            // These headers are described by other means, and the parameters for them are
+            // ignored per the specification.
+            if location == Location::Header
+                && ["accept", "content-type", "authorization"]
+                    .iter()
+                    .any(|ignored| name.eq_ignore_ascii_case(ignored))
+            {
+                continue;
+            }
+
+            let schema = match parameter.get("schema") {
+                Some(_) => Some(self.schema(&format!("{pointer}/schema"))?),
+                None => None,
+            };
+
+            parameters.push(Parameter {
+                name: name.into(),
+                location,
+                required: location == Location::Path
+                    || parameter.get("required").and_then(Value::as_bool) == Some(true),
+                schema,
+            });
+        }
+        Ok(parameters)
+    }
+
+    /// Load the request body at the given JSON pointer.
+    fn request_body(
+        &self,
+        pointer: String,
+        request_body: &'a Value,
+    ) -> Result<RequestBody, InvalidDocument> {
+        let (pointer, request_body) = self.resolve(pointer, request_body)?;
+
+        let mut content = Vec::new();
+        let media_types = request_body.get("content").and_then(Value::as_object);
+        for (media_type, object) in media_types.into_iter().flatten() {
+            let schema = match object.get("schema") {
+                Some(_) => {
+                    Some(self.schema(&format!("{pointer}/content/{}/schema", escape(media_type)))?)
+                }
+                None => None,
+            };
+            content.push((media_type.clone(), schema));
+        }
+
+        Ok(RequestBody {
+            required: request_body.get("required").and_then(Value::as_bool) == Some(true),
+            content,
+        })
+    }
+}
+
+/// Split the path template into segments.
+fn parse_template(path: &str) -> Vec<Segment> {
+    path.strip_prefix('/')
+        .unwrap_or(path)
+        .split('/')
+        .map(|segment| {
+            match segment
+                .strip_prefix('{')
+                .and_then(|segment| segment.strip_suffix('}'))
+            {
+                Some(name) => Segment::Parameter(name.into()),
+                None => Segment::Literal(segment.into()),
+            }
+        })
+        .collect()
+}
+
+/// Translate the `OpenAPI` 3.0 schema keywords that mean something else in JSON Schema 2020-12.
+///
+/// The `nullable: true` adds `null` to the `type` and the `enum`, and the boolean
+/// `exclusiveMinimum` and `exclusiveMaximum` turn the `minimum` and `maximum` into the exclusive
+/// ones. Every object in the document is translated, except within the example and default
+/// values, and only the boolean keywords are taken as the ones to translate, so the properties
+/// with the same names are left alone.
+fn translate_3_0(value: &mut Value) {
+    match value {
+        Value::Array(items) => items.iter_mut().for_each(translate_3_0),
+        Value::Object(object) => {
+            if let Some(&Value::Bool(nullable)) = object.get("nullable") {
+                object.remove("nullable");
+                if nullable {
+                    match object.get_mut("type") {
+                        Some(kind @ Value::String(_)) => {
+                            let single = kind.take();
+                            *kind = serde_json::json!([single, "null"]);
+                        }
+                        Some(Value::Array(kinds)) if !kinds.contains(&"null".into()) => {
+                            kinds.push("null".into());
+                        }
+                        _ => {}
+                    }
+                    if let Some(Value::Array(values)) = object.get_mut("enum") {
+                        if !values.contains(&Value::Null) {
+                            values.push(Value::Null);
+                        }
+                    }
+                }
+            }
+            for (exclusive, inclusive) in [
+                ("exclusiveMinimum", "minimum"),
+                ("exclusiveMaximum", "maximum"),
+            ] {
+                let Some(&Value::Bool(is_exclusive)) = object.get(exclusive) else {
+                    continue;
+                };
+                object.remove(exclusive);
+                if is_exclusive {
+                    if let Some(limit) = object.remove(inclusive) {
+                        object.insert(exclusive.into(), limit);
+                    }
+                }
+            }
+            for (key, value) in object.iter_mut() {
+                if !matches!(
+                    &**key,
+                    "example" | "examples" | "default" | "enum" | "const"
+                ) {
+                    translate_3_0(value);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Check the parameter value against the schema, as a string, and then as JSON.
+fn parameter_violations(schema: &jsonschema::Validator, value: &str) -> Vec<Violation> {
+    let as_string = violations(schema, &Value::String(value.into()));
+    if as_string.is_empty() {
+        return as_string;
+    }
+    match serde_json::from_str::<Value>(value) {
+        Ok(parsed) if !parsed.is_string() && schema.is_valid(&parsed) => Vec::new(),
+        _ => as_string,
+    }
+}
+
+/// Split the query string into the decoded name and value pairs.
+fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(name, true), percent_decode(value, true))
+        })
+        .collect()
+}
+
+/// Decode the percent-encoded string, optionally treating `+` as a space.
+fn percent_decode(input: &str, plus_as_space: bool) -> String {
+    let mut decoded = Vec::with_capacity(input.len());
+    let mut bytes = input.as_bytes().iter();
+    while let Some(&byte) = bytes.next() {
+        match byte {
+            b'%' => {
+                // `from_str_radix` alone would also accept a sign, like in `%+1`.
+                let hex = bytes
+                    .as_slice()
+                    .get(..2)
+                    .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+                    .and_then(|hex| {
+                        let hex = core::str::from_utf8(hex).ok()?;
+                        u8::from_str_radix(hex, 16).ok()
+                    });
+                match hex {
+                    Some(value) => {
+                        decoded.push(value);
+                        bytes.nth(1);
+                    }
+                    None => decoded.push(byte),
+                }
+            }
+            b'+' if plus_as_space => decoded.push(b' '),
+            _ => decoded.push(byte),
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Escape the key for use as a JSON pointer segment.
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Percent-encode the JSON pointer for use as a URI fragment.
+fn encode_fragment(pointer: &str) -> String {
+    let mut encoded = String::with_capacity(pointer.len());
+    for &byte in pointer.as_bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+/// The document can not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidDocument {
+    /// The description of the problem.
+    pub message: String,
+}
+
+impl InvalidDocument {
+    /// Create a new [`InvalidDocument`] with the given description.
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl core::fmt::Display for InvalidDocument {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid OpenAPI document: {}", self.message)
+    }
+}
+
+impl core::error::Error for InvalidDocument {}
+
+/// An error that can occur while validating the request.
+#[derive(Debug)]
+pub enum Error {
+    /// No path in the document matches the request path.
+    UnknownPath,
+    /// The path does not describe an operation for the request method.
+    MethodNotAllowed,
+    /// A required parameter is missing.
+    MissingParameter {
+        /// Where the parameter is expected.
+        location: Location,
+        /// The name of the parameter.
+        name: String,
+    },
+    /// A parameter value does not match its schema.
+    InvalidParameter {
+        /// Where the parameter is passed.
+        location: Location,
+        /// The name of the parameter.
+        name: String,
+        /// The mismatches between the value and the schema.
+        violations: Vec<Violation>,
+    },
+    /// The required body is missing.
+    MissingBody,
+    /// The content type is missing or not allowed for the operation.
+    UnsupportedContentType,
+    /// The body does not match the schema.
+    Body(crate::json_schema::Error),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnknownPath => f.write_str("unknown path"),
+            Self::MethodNotAllowed => f.write_str("method not allowed"),
+            Self::MissingParameter { location, name } => {
+                write!(f, "missing {} parameter {name:?}", location.as_str())
+            }
+            Self::InvalidParameter {
+                location,
+                name,
+                violations,
+            } => {
+                write!(f, "invalid {} parameter {name:?}", location.as_str())?;
+                for (index, violation) in violations.iter().enumerate() {
+                    let separator = if index == 0 { ": " } else { "; " };
+                    write!(f, "{separator}{}", violation.message)?;
+                }
+                Ok(())
+            }
+            Self::MissingBody => f.write_str("missing request body"),
+            Self::UnsupportedContentType => f.write_str("unsupported content type"),
+            Self::Body(error) => error.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::Body(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl crate::ValidationErrorKind for Error {
+    fn kind(&self) -> crate::ErrorKind {
+        match self {
+            Self::UnknownPath => crate::ErrorKind::NotFound,
+            Self::MethodNotAllowed => crate::ErrorKind::MethodNotAllowed,
+            Self::UnsupportedContentType => crate::ErrorKind::UnsupportedMediaType,
+            Self::Body(error) => crate::ValidationErrorKind::kind(error),
+            Self::MissingParameter { .. } | Self::InvalidParameter { .. } | Self::MissingBody => {
+                crate::ErrorKind::Malformed
+            }
+        }
+    }
+}
+
+impl<Data> crate::SyncValidator<Data> for OpenApiValidator
+where
+    Data: bytes::Buf + AsRef<[u8]>,
+{
+    type Error = Error;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        self.validate_request(parts, buffered_body.as_ref())
+    }
+}
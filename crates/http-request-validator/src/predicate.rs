@@ -0,0 +1,284 @@
+//! [`Predicate`] trait and [`When`] validator for conditional validation.
+
+/// The condition over the request head.
+///
+/// Implemented for closures, so you can provide any custom logic, and for a few common
+/// conditions in this module.
+pub trait Predicate {
+    /// Whether the request matches the predicate.
+    fn matches(&self, parts: &http::request::Parts) -> bool;
+
+    /// Match the requests that match both this and the `other` predicate.
+    fn and<Other: Predicate>(self, other: Other) -> And<Self, Other>
+    where
+        Self: Sized,
+    {
+        And(self, other)
+    }
+
+    /// Match the requests that match either this or the `other` predicate.
+    fn or<Other: Predicate>(self, other: Other) -> Or<Self, Other>
+    where
+        Self: Sized,
+    {
+        Or(self, other)
+    }
+
+    /// Match the requests that do not match this predicate.
+    fn not(self) -> Not<Self>
+    where
+        Self: Sized,
+    {
+        Not(self)
+    }
+}
+
+impl<F> Predicate for F
+where
+    F: Fn(&http::request::Parts) -> bool,
+{
+    fn matches(&self, parts: &http::request::Parts) -> bool {
+        self(parts)
+    }
+}
+
+/// Match the requests with any of the given methods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Methods<M>(pub M);
+
+impl<M: AsRef<[http::Method]>> Predicate for Methods<M> {
+    fn matches(&self, parts: &http::request::Parts) -> bool {
+        self.0.as_ref().contains(&parts.method)
+    }
+}
+
+/// Match the requests with the path matching the given glob pattern.
+///
+/// In the pattern, `?` matches any single character except `/`, `*` matches any sequence of
+/// characters except `/`, and `**` matches any sequence of characters.
+/// The query string is not taken into account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathGlob<P>(pub P);
+
+impl<P: AsRef<str>> Predicate for PathGlob<P> {
+    fn matches(&self, parts: &http::request::Parts) -> bool {
+        glob_matches(self.0.as_ref().as_bytes(), parts.uri.path().as_bytes())
+    }
+}
+
+/// Match the glob pattern against the path.
+///
+/// Iterative, so the attacker-controlled paths can't make it backtrack exponentially: only the
+/// last `*` and the last `**` are retried on a mismatch. Retrying an earlier `*` never helps, as
+/// neither it nor the literals after it can cross a `/`, and everything before the last `**` is
+/// matched at the earliest position, as the `**` can take up any of the rest.
+fn glob_matches(pattern: &[u8], path: &[u8]) -> bool {
+    let (mut pattern_pos, mut path_pos) = (0, 0);
+    // The pattern position after the wildcard, and the path position it has matched up to.
+    let mut any_star: Option<(usize, usize)> = None;
+    let mut segment_star: Option<(usize, usize)> = None;
+
+    while path_pos < path.len() {
+        match pattern.get(pattern_pos..) {
+            Some([b'*', b'*', ..]) => {
+                pattern_pos += 2;
+                any_star = Some((pattern_pos, path_pos));
+                segment_star = None;
+                continue;
+            }
+            Some([b'*', ..]) => {
+                pattern_pos += 1;
+                segment_star = Some((pattern_pos, path_pos));
+                continue;
+            }
+            Some([b'?', ..]) if path[path_pos] != b'/' => {
+                pattern_pos += 1;
+                path_pos += 1;
+                continue;
+            }
+            Some([expected, ..]) if *expected == path[path_pos] => {
+                pattern_pos += 1;
+                path_pos += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        // Mismatch: let the last wildcard take up one more character.
+        match (segment_star, any_star) {
+            (Some((star_pattern_pos, star_path_pos)), _) if path[star_path_pos] != b'/' => {
+                segment_star = Some((star_pattern_pos, star_path_pos + 1));
+                (pattern_pos, path_pos) = (star_pattern_pos, star_path_pos + 1);
+            }
+            (_, Some((star_pattern_pos, star_path_pos))) => {
+                any_star = Some((star_pattern_pos, star_path_pos + 1));
+                segment_star = None;
+                (pattern_pos, path_pos) = (star_pattern_pos, star_path_pos + 1);
+            }
+            _ => return false,
+        }
+    }
+
+    pattern[pattern_pos..].iter().all(|&c| c == b'*')
+}
+
+/// Match the requests that have the given header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderPresent(pub http::HeaderName);
+
+impl Predicate for HeaderPresent {
+    fn matches(&self, parts: &http::request::Parts) -> bool {
+        parts.headers.contains_key(&self.0)
+    }
+}
+
+/// Match the requests that match both predicates.
+///
+/// See [`Predicate::and`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct And<A, B>(pub A, pub B);
+
+impl<A: Predicate, B: Predicate> Predicate for And<A, B> {
+    fn matches(&self, parts: &http::request::Parts) -> bool {
+        self.0.matches(parts) && self.1.matches(parts)
+    }
+}
+
+/// Match the requests that match either predicate.
+///
+/// See [`Predicate::or`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Or<A, B>(pub A, pub B);
+
+impl<A: Predicate, B: Predicate> Predicate for Or<A, B> {
+    fn matches(&self, parts: &http::request::Parts) -> bool {
+        self.0.matches(parts) || self.1.matches(parts)
+    }
+}
+
+/// Match the requests that do not match the predicate.
+///
+/// See [`Predicate::not`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Not<P>(pub P);
+
+impl<P: Predicate> Predicate for Not<P> {
+    fn matches(&self, parts: &http::request::Parts) -> bool {
+        !self.0.matches(parts)
+    }
+}
+
+/// The validator that only validates the requests matching the predicate.
+///
+/// The requests that do not match are considered valid.
+///
+/// Note that this only skips the validation itself; the integrations may provide a way to
+/// skip the body buffering as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct When<P, V> {
+    /// The predicate to check.
+    pub predicate: P,
+
+    /// The validator to run for the matching requests.
+    pub inner: V,
+}
+
+impl<P, V> When<P, V> {
+    /// Create a new [`When`] validator.
+    pub const fn new(predicate: P, inner: V) -> Self {
+        Self { predicate, inner }
+    }
+}
+
+impl<P, V, Data> crate::Validator<Data> for When<P, V>
+where
+    P: Predicate,
+    V: crate::Validator<Data>,
+    Data: bytes::Buf,
+{
+    type Error = V::Error;
+
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + Send + 'a {
+        let validation = self
+            .predicate
+            .matches(parts)
+            .then(|| crate::Validator::validate(&self.inner, parts, buffered_body));
+        async move {
+            match validation {
+                Some(validation) => validation.await,
+                None => Ok(()),
+            }
+        }
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        if !self.predicate.matches(parts) {
+            return Ok(());
+        }
+        crate::Validator::pre_validate(&self.inner, parts)
+    }
+}
+
+impl<P, V, Data> crate::LocalValidator<Data> for When<P, V>
+where
+    P: Predicate,
+    V: crate::LocalValidator<Data>,
+    Data: bytes::Buf,
+{
+    type Error = V::Error;
+
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + 'a {
+        let validation = self
+            .predicate
+            .matches(parts)
+            .then(|| crate::LocalValidator::validate(&self.inner, parts, buffered_body));
+        async move {
+            match validation {
+                Some(validation) => validation.await,
+                None => Ok(()),
+            }
+        }
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        if !self.predicate.matches(parts) {
+            return Ok(());
+        }
+        crate::LocalValidator::pre_validate(&self.inner, parts)
+    }
+}
+
+impl<P, V, Data> crate::SyncValidator<Data> for When<P, V>
+where
+    P: Predicate,
+    V: crate::SyncValidator<Data>,
+    Data: bytes::Buf,
+{
+    type Error = V::Error;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        if !self.predicate.matches(parts) {
+            return Ok(());
+        }
+        crate::SyncValidator::validate(&self.inner, parts, buffered_body)
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        if !self.predicate.matches(parts) {
+            return Ok(());
+        }
+        crate::SyncValidator::pre_validate(&self.inner, parts)
+    }
+}
@@ -0,0 +1,67 @@
+//! [`ResponseValidator`] and [`ResponseSigner`], the response counterparts of the
+//! [`crate::Validator`] and [`crate::Signer`].
+
+/// The [`http::Response`] validator.
+///
+/// The same as the [`crate::Validator`], but for the responses, for instance to check the
+/// signatures of the responses from the providers that sign them.
+pub trait ResponseValidator<Data: bytes::Buf> {
+    /// An error that can occur during validation.
+    type Error;
+
+    /// Validate the response header and buffered body.
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::response::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + Send + 'a;
+}
+
+impl<T: ?Sized, Data> ResponseValidator<Data> for T
+where
+    T: core::ops::Deref + Send + Sync,
+    <T as core::ops::Deref>::Target: ResponseValidator<Data> + Send,
+    Data: bytes::Buf + Send + Sync,
+{
+    type Error = <<T as core::ops::Deref>::Target as ResponseValidator<Data>>::Error;
+
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::response::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + 'a {
+        ResponseValidator::validate(&**self, parts, buffered_body)
+    }
+}
+
+/// The [`http::Response`] signer.
+///
+/// The same as the [`crate::Signer`], but for the responses.
+pub trait ResponseSigner<Data: bytes::Buf> {
+    /// An error that can occur during signing.
+    type Error;
+
+    /// Sign the response by updating the response header according to the buffered body.
+    fn sign<'a>(
+        &'a self,
+        parts: &'a mut http::response::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + Send + 'a;
+}
+
+impl<T: ?Sized, Data> ResponseSigner<Data> for T
+where
+    T: core::ops::Deref + Send + Sync,
+    <T as core::ops::Deref>::Target: ResponseSigner<Data> + Send,
+    Data: bytes::Buf + Send + Sync,
+{
+    type Error = <<T as core::ops::Deref>::Target as ResponseSigner<Data>>::Error;
+
+    fn sign<'a>(
+        &'a self,
+        parts: &'a mut http::response::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + 'a {
+        ResponseSigner::sign(&**self, parts, buffered_body)
+    }
+}
@@ -0,0 +1,129 @@
+//! [`Signer`] traits, the outbound counterpart of the validators.
+//!
+//! Implement both the validator and the signer for a signature scheme to guarantee the requests
+//! signed by one service pass the validation at the other.
+
+/// The [`http::Request`] signer.
+///
+/// Mirrors the [`crate::Validator`]: runs over the buffered request body, and puts the signature
+/// into the request head.
+pub trait Signer<Data: bytes::Buf> {
+    /// An error that can occur during signing.
+    type Error;
+
+    /// Sign the request by updating the request header according to the buffered body.
+    fn sign<'a>(
+        &'a self,
+        parts: &'a mut http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + Send + 'a;
+}
+
+impl<T: ?Sized, Data> Signer<Data> for T
+where
+    T: core::ops::Deref + Send + Sync,
+    <T as core::ops::Deref>::Target: Signer<Data> + Send,
+    Data: bytes::Buf + Send + Sync,
+{
+    type Error = <<T as core::ops::Deref>::Target as Signer<Data>>::Error;
+
+    fn sign<'a>(
+        &'a self,
+        parts: &'a mut http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + 'a {
+        Signer::sign(&**self, parts, buffered_body)
+    }
+}
+
+/// The [`http::Request`] signer that runs synchronously.
+///
+/// Mirrors the [`crate::SyncValidator`]; use it from the blocking code via [`sign_blocking`]
+/// and from the async code via [`crate::AsAsync`].
+pub trait SyncSigner<Data: bytes::Buf> {
+    /// An error that can occur during signing.
+    type Error;
+
+    /// Sign the request by updating the request header according to the buffered body.
+    fn sign(
+        &self,
+        parts: &mut http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error>;
+}
+
+impl<T: ?Sized, Data> SyncSigner<Data> for T
+where
+    T: core::ops::Deref,
+    <T as core::ops::Deref>::Target: SyncSigner<Data>,
+    Data: bytes::Buf,
+{
+    type Error = <<T as core::ops::Deref>::Target as SyncSigner<Data>>::Error;
+
+    fn sign(
+        &self,
+        parts: &mut http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        SyncSigner::sign(&**self, parts, buffered_body)
+    }
+}
+
+impl<S, Data> Signer<Data> for crate::AsAsync<S>
+where
+    S: SyncSigner<Data, Error: Send>,
+    Data: bytes::Buf,
+{
+    type Error = S::Error;
+
+    fn sign<'a>(
+        &'a self,
+        parts: &'a mut http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + Send + 'a {
+        core::future::ready(SyncSigner::sign(&self.0, parts, buffered_body))
+    }
+}
+
+/// Sign the already buffered request without an async runtime.
+///
+/// Returns the signed request.
+///
+/// ## Examples
+///
+/// ```
+/// use http_request_validator::{sign_blocking, SyncSigner};
+///
+/// struct LengthSigner;
+///
+/// impl<Data: bytes::Buf> SyncSigner<Data> for LengthSigner {
+///     type Error = core::convert::Infallible;
+///
+///     fn sign(
+///         &self,
+///         parts: &mut http::request::Parts,
+///         buffered_body: &Data,
+///     ) -> Result<(), Self::Error> {
+///         parts
+///             .headers
+///             .insert("x-body-length", buffered_body.remaining().into());
+///         Ok(())
+///     }
+/// }
+///
+/// let req = http::Request::new(bytes::Bytes::from_static(b"payload"));
+/// let req = sign_blocking(&LengthSigner, req).unwrap();
+/// assert_eq!(req.headers()["x-body-length"], "7");
+/// ```
+pub fn sign_blocking<Signer, Data>(
+    signer: &Signer,
+    req: http::Request<Data>,
+) -> Result<http::Request<Data>, Signer::Error>
+where
+    Signer: SyncSigner<Data> + ?Sized,
+    Data: bytes::Buf,
+{
+    let (mut parts, body) = req.into_parts();
+    signer.sign(&mut parts, &body)?;
+    Ok(http::Request::from_parts(parts, body))
+}
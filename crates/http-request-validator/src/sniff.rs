@@ -0,0 +1,354 @@
+//! Checks that the buffered body looks like the declared format.
+//!
+//! The checks confirm the structure of the payload without building it, so they are cheap
+//! enough to run before the actual parsing, and catch the bodies sent with the wrong
+//! `Content-Type`.
+
+/// The limit of the nesting depth, to protect against the stack exhaustion.
+const MAX_DEPTH: usize = 128;
+
+/// The payload format that can be sniffed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// JSON.
+    Json,
+    /// `application/x-www-form-urlencoded`.
+    UrlEncoded,
+    /// XML.
+    Xml,
+}
+
+impl Format {
+    /// The format of the media type, given as a lowercase `type/subtype`, if known.
+    pub fn of(essence: &str) -> Option<Self> {
+        match essence {
+            "application/json" => Some(Self::Json),
+            "application/x-www-form-urlencoded" => Some(Self::UrlEncoded),
+            "application/xml" | "text/xml" => Some(Self::Xml),
+            _ if essence.ends_with("+json") => Some(Self::Json),
+            _ if essence.ends_with("+xml") => Some(Self::Xml),
+            _ => None,
+        }
+    }
+
+    /// Whether the data looks like this format.
+    pub fn sniff(self, data: &[u8]) -> bool {
+        match self {
+            Self::Json => is_json(data),
+            Self::UrlEncoded => is_urlencoded(data),
+            Self::Xml => is_xml(data),
+        }
+    }
+
+    /// The name of this format.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Json => "JSON",
+            Self::UrlEncoded => "urlencoded form",
+            Self::Xml => "XML",
+        }
+    }
+}
+
+/// Whether the data is a syntactically valid JSON text.
+pub fn is_json(data: &[u8]) -> bool {
+    if core::str::from_utf8(data).is_err() {
+        return false;
+    }
+    let mut json = Json { data, pos: 0 };
+    json.skip_whitespace();
+    if !json.value(0) {
+        return false;
+    }
+    json.skip_whitespace();
+    json.pos == data.len()
+}
+
+/// The JSON syntax checker.
+struct Json<'a> {
+    /// The data to check.
+    data: &'a [u8],
+
+    /// The current position.
+    pos: usize,
+}
+
+impl Json<'_> {
+    /// The byte at the current position.
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    /// Advance past the given byte, if it is at the current position.
+    fn eat(&mut self, byte: u8) -> bool {
+        if self.peek() == Some(byte) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    /// Advance past the given bytes, if they are at the current position.
+    fn eat_all(&mut self, bytes: &[u8]) -> bool {
+        if self.data[self.pos..].starts_with(bytes) {
+            self.pos += bytes.len();
+            return true;
+        }
+        false
+    }
+
+    /// Advance past the digits, returning whether there were any.
+    fn digits(&mut self) -> bool {
+        let start = self.pos;
+        while self.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        self.pos > start
+    }
+
+    /// Advance past the whitespace.
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Check a value at the given nesting depth.
+    fn value(&mut self, depth: usize) -> bool {
+        if depth > MAX_DEPTH {
+            return false;
+        }
+        match self.peek() {
+            Some(b'{') => self.object(depth),
+            Some(b'[') => self.array(depth),
+            Some(b'"') => self.string(),
+            Some(b't') => self.eat_all(b"true"),
+            Some(b'f') => self.eat_all(b"false"),
+            Some(b'n') => self.eat_all(b"null"),
+            Some(b'-' | b'0'..=b'9') => self.number(),
+            _ => false,
+        }
+    }
+
+    /// Check an object.
+    fn object(&mut self, depth: usize) -> bool {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.eat(b'}') {
+            return true;
+        }
+        loop {
+            self.skip_whitespace();
+            if !self.string() {
+                return false;
+            }
+            self.skip_whitespace();
+            if !self.eat(b':') {
+                return false;
+            }
+            self.skip_whitespace();
+            if !self.value(depth + 1) {
+                return false;
+            }
+            self.skip_whitespace();
+            if !self.eat(b',') {
+                return self.eat(b'}');
+            }
+        }
+    }
+
+    /// Check an array.
+    fn array(&mut self, depth: usize) -> bool {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.eat(b']') {
+            return true;
+        }
+        loop {
+            self.skip_whitespace();
+            if !self.value(depth + 1) {
+                return false;
+            }
+            self.skip_whitespace();
+            if !self.eat(b',') {
+                return self.eat(b']');
+            }
+        }
+    }
+
+    /// Check a string.
+    fn string(&mut self) -> bool {
+        if !self.eat(b'"') {
+            return false;
+        }
+        while let Some(byte) = self.peek() {
+            self.pos += 1;
+            match byte {
+                b'"' => return true,
+                b'\\' => match self.peek() {
+                    Some(b'"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => self.pos += 1,
+                    Some(b'u') => {
+                        let hex = self.data.get(self.pos + 1..self.pos + 5);
+                        if !hex.is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit)) {
+                            return false;
+                        }
+                        self.pos += 5;
+                    }
+                    _ => return false,
+                },
+                0..0x20 => return false,
+                _ => {}
+            }
+        }
+        false
+    }
+
+    /// Check a number.
+    fn number(&mut self) -> bool {
+        self.eat(b'-');
+        if !self.eat(b'0') && !self.digits() {
+            return false;
+        }
+        if self.eat(b'.') && !self.digits() {
+            return false;
+        }
+        if self.eat(b'e') || self.eat(b'E') {
+            let _ = self.eat(b'+') || self.eat(b'-');
+            if !self.digits() {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Whether the data is a valid `application/x-www-form-urlencoded` payload.
+///
+/// Only the bytes that the form serializers emit are accepted, and the percent-escapes have
+/// to be complete.
+pub fn is_urlencoded(data: &[u8]) -> bool {
+    let mut bytes = data.iter();
+    while let Some(&byte) = bytes.next() {
+        match byte {
+            b'%' => {
+                let hex = bytes.as_slice().get(..2);
+                if !hex.is_some_and(|hex| hex.iter().all(u8::is_ascii_hexdigit)) {
+                    return false;
+                }
+                bytes.nth(1);
+            }
+            b'&' | b'=' | b'+' | b'*' | b'-' | b'.' | b'_' | b'~' | b'!' | b'\'' | b'(' | b')' => {}
+            _ if byte.is_ascii_alphanumeric() => {}
+            _ => return false,
+        }
+    }
+    true
+}
+
+/// Whether the data is a well-formed UTF-8 XML document.
+///
+/// Checks the document structure: a single root element with the properly nested tags, and
+/// the comments, processing instructions, CDATA sections and the doctype in the allowed
+/// places. The names, the attributes and the entity references are not checked in detail.
+pub fn is_xml(data: &[u8]) -> bool {
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+    if core::str::from_utf8(data).is_err() {
+        return false;
+    }
+
+    let mut open = alloc::vec::Vec::new();
+    let mut seen_root = false;
+    let mut rest = data;
+    loop {
+        let Some(start) = rest.iter().position(|&byte| byte == b'<') else {
+            return open.is_empty() && seen_root && rest.trim_ascii().is_empty();
+        };
+        if open.is_empty() && !rest[..start].trim_ascii().is_empty() {
+            return false;
+        }
+        rest = &rest[start..];
+
+        let end = if rest.starts_with(b"<?") {
+            find(rest, b"?>").map(|end| end + 2)
+        } else if rest.starts_with(b"<!--") {
+            find(rest, b"-->").map(|end| end + 3)
+        } else if rest.starts_with(b"<![CDATA[") {
+            if open.is_empty() {
+                return false;
+            }
+            find(rest, b"]]>").map(|end| end + 3)
+        } else if rest.starts_with(b"<!") {
+            if seen_root {
+                return false;
+            }
+            doctype_end(rest)
+        } else if let Some(tag) = rest.strip_prefix(b"</") {
+            let Some(end) = tag.iter().position(|&byte| byte == b'>') else {
+                return false;
+            };
+            if open.pop() != Some(tag[..end].trim_ascii_end()) {
+                return false;
+            }
+            Some(end + 3)
+        } else {
+            let Some(end) = tag_end(rest) else {
+                return false;
+            };
+            let tag = &rest[1..end];
+            let name_len = tag
+                .iter()
+                .position(|&byte| byte.is_ascii_whitespace() || byte == b'/')
+                .unwrap_or(tag.len());
+            let name = &tag[..name_len];
+            if name.is_empty() || (open.is_empty() && seen_root) || open.len() >= MAX_DEPTH {
+                return false;
+            }
+            seen_root = true;
+            if !tag.ends_with(b"/") {
+                open.push(name);
+            }
+            Some(end + 1)
+        };
+
+        let Some(end) = end else {
+            return false;
+        };
+        rest = &rest[end..];
+    }
+}
+
+/// The position of the needle in the haystack.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+/// The position of the `>` that ends the tag, skipping the quoted attribute values.
+fn tag_end(tag: &[u8]) -> Option<usize> {
+    let mut quote = None;
+    for (pos, &byte) in tag.iter().enumerate() {
+        match (quote, byte) {
+            (None, b'"' | b'\'') => quote = Some(byte),
+            (None, b'>') => return Some(pos),
+            (None, b'<') if pos > 0 => return None,
+            (Some(open), _) if open == byte => quote = None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The position past the end of the doctype declaration, skipping the internal subset.
+fn doctype_end(doctype: &[u8]) -> Option<usize> {
+    let mut in_subset = false;
+    for (pos, &byte) in doctype.iter().enumerate() {
+        match byte {
+            b'[' => in_subset = true,
+            b']' => in_subset = false,
+            b'>' if !in_subset => return Some(pos + 1),
+            _ => {}
+        }
+    }
+    None
+}
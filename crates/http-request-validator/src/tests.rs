@@ -0,0 +1,940 @@
+//! The tests for the validators and predicates.
+
+use crate::predicate::{HeaderPresent, Methods, PathGlob, Predicate as _};
+
+fn parts(method: http::Method, uri: &str) -> http::request::Parts {
+    let (parts, ()) = http::Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("x-hub-signature-256", "sha256=00")
+        .body(())
+        .unwrap()
+        .into_parts();
+    parts
+}
+
+#[test]
+fn path_glob() {
+    let cases = [
+        ("/webhook", "/webhook", true),
+        ("/webhook", "/webhooks", false),
+        ("/hooks/*", "/hooks/github", true),
+        ("/hooks/*", "/hooks/github/push", false),
+        ("/hooks/**", "/hooks/github/push", true),
+        ("/hooks/*/push", "/hooks/github/push", true),
+        ("/hooks/?", "/hooks/a", true),
+        ("/hooks/?", "/hooks/ab", false),
+        ("**", "/anything/at/all", true),
+        ("/hooks/*", "/hooks/github?query=1", true),
+    ];
+
+    for (pattern, uri, expected) in cases {
+        assert_eq!(
+            PathGlob(pattern).matches(&parts(http::Method::POST, uri)),
+            expected,
+            "{pattern} vs {uri}"
+        );
+    }
+}
+
+#[test]
+fn path_glob_backtracking() {
+    let cases = [
+        ("/a/**/b/*c", "/a/x/b/y/b/zc", true),
+        ("/a/**/b/*c", "/a/x/b/zc/d", false),
+        ("/*a*b", "/xaab", true),
+        ("/*a*b", "/a/ab", false),
+        ("/**/*.json", "/v1/hooks/event.json", true),
+        ("/**/*.json", "/v1/hooks.json/event", false),
+        (
+            "/**a**a**a**a**a**b",
+            "/aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            false,
+        ),
+        (
+            "/*a*a*a*a*a*b",
+            "/aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa",
+            false,
+        ),
+    ];
+
+    for (pattern, uri, expected) in cases {
+        assert_eq!(
+            PathGlob(pattern).matches(&parts(http::Method::POST, uri)),
+            expected,
+            "{pattern} vs {uri}"
+        );
+    }
+
+    // The recursive matcher took exponential time on these.
+    let mut path = [b'a'; 4096];
+    path[0] = b'/';
+    let path = core::str::from_utf8(&path).unwrap();
+    let pattern = "/**a**a**a**a**a**a**a**a**a**a**a**a**a**a**a**ab";
+    assert!(!PathGlob(pattern).matches(&parts(http::Method::POST, path)));
+}
+
+#[test]
+fn combinators() {
+    let predicate = Methods([http::Method::POST])
+        .and(HeaderPresent(http::HeaderName::from_static(
+            "x-hub-signature-256",
+        )))
+        .or(|parts: &http::request::Parts| parts.uri.path() == "/always");
+
+    assert!(predicate.matches(&parts(http::Method::POST, "/")));
+    assert!(!predicate.matches(&parts(http::Method::GET, "/")));
+    assert!(predicate.matches(&parts(http::Method::GET, "/always")));
+    assert!(predicate.not().matches(&parts(http::Method::GET, "/")));
+}
+
+struct RejectAll;
+
+impl<Data: bytes::Buf> crate::SyncValidator<Data> for RejectAll {
+    type Error = &'static str;
+
+    fn validate(
+        &self,
+        _parts: &http::request::Parts,
+        _buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        Err("rejected")
+    }
+}
+
+#[test]
+fn when() {
+    let validator = crate::When::new(Methods([http::Method::POST]), RejectAll);
+    let body = bytes::Bytes::new();
+
+    let result = crate::SyncValidator::validate(&validator, &parts(http::Method::GET, "/"), &body);
+    assert_eq!(result, Ok(()));
+
+    let result = crate::SyncValidator::validate(&validator, &parts(http::Method::POST, "/"), &body);
+    assert_eq!(result, Err("rejected"));
+}
+
+#[test]
+fn dispatch() {
+    let dispatch = crate::Dispatch::<&'static str>::new()
+        .provider(
+            "github",
+            HeaderPresent(http::HeaderName::from_static("x-hub-signature-256")),
+            crate::AsAsync(RejectAll),
+        )
+        .provider("fallback", PathGlob("/hooks/**"), crate::AsAsync(RejectAll));
+
+    let mut github = parts(http::Method::POST, "/hooks/github");
+    assert_eq!(dispatch.select(&github), Some("github"));
+    assert!(crate::Validator::<bytes::Bytes>::pre_validate(&dispatch, &mut github).is_ok());
+    assert_eq!(
+        github.extensions.get::<crate::MatchedProvider>(),
+        Some(&crate::MatchedProvider("github"))
+    );
+
+    let mut unknown = parts(http::Method::POST, "/other");
+    unknown.headers.clear();
+    assert_eq!(dispatch.select(&unknown), None);
+    assert!(matches!(
+        crate::Validator::<bytes::Bytes>::pre_validate(&dispatch, &mut unknown),
+        Err(crate::dispatch::Error::NoProviderMatched)
+    ));
+    assert_eq!(unknown.extensions.get::<crate::MatchedProvider>(), None);
+
+    unknown.uri = http::Uri::from_static("/hooks/slack");
+    assert_eq!(dispatch.select(&unknown), Some("fallback"));
+
+    // Every predicate is checked at most once per pre-validation.
+    let calls = core::cell::Cell::new(0);
+    let counted = |matches: bool| {
+        let calls = &calls;
+        move |_: &http::request::Parts| {
+            calls.set(calls.get() + 1);
+            matches
+        }
+    };
+    let dispatch = crate::Dispatch::<&'static str>::new()
+        .provider("a", counted(false), RejectAll)
+        .provider("b", counted(false), RejectAll)
+        .provider("c", counted(true), RejectAll);
+    let mut req = parts(http::Method::POST, "/");
+    assert!(crate::SyncValidator::<bytes::Bytes>::pre_validate(&dispatch, &mut req).is_ok());
+    assert_eq!(calls.get(), 3);
+    assert_eq!(
+        req.extensions.get::<crate::MatchedProvider>(),
+        Some(&crate::MatchedProvider("c"))
+    );
+
+    // The synchronous and local validators are dispatched as well.
+    assert!(matches!(
+        crate::SyncValidator::validate(&dispatch, &req, &bytes::Bytes::new()),
+        Err(crate::dispatch::Error::Provider {
+            name: "c",
+            error: "rejected"
+        })
+    ));
+    let dispatch = crate::Dispatch::<&'static str>::new().provider(
+        "local",
+        PathGlob("/**"),
+        crate::AsLocal(crate::AsAsync(RejectAll)),
+    );
+    let body = bytes::Bytes::new();
+    let validation = crate::LocalValidator::validate(&dispatch, &req, &body);
+    let mut validation = core::pin::pin!(validation);
+    let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+    assert!(matches!(
+        core::future::Future::poll(validation.as_mut(), &mut cx),
+        core::task::Poll::Ready(Err(crate::dispatch::Error::Provider {
+            name: "local",
+            error: "rejected"
+        }))
+    ));
+}
+
+/// Puts the body length into the `x-body-length` header, and checks it.
+struct BodyLength;
+
+impl<Data: bytes::Buf> crate::SyncSigner<Data> for BodyLength {
+    type Error = core::convert::Infallible;
+
+    fn sign(
+        &self,
+        parts: &mut http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        parts
+            .headers
+            .insert("x-body-length", buffered_body.remaining().into());
+        Ok(())
+    }
+}
+
+impl<Data: bytes::Buf> crate::SyncValidator<Data> for BodyLength {
+    type Error = &'static str;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        let expected = http::HeaderValue::from(buffered_body.remaining());
+        if parts.headers.get("x-body-length") != Some(&expected) {
+            return Err("length mismatch");
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn sign_then_validate() {
+    let req = http::Request::new(bytes::Bytes::from_static(b"payload"));
+    assert_eq!(
+        crate::validate_blocking(&BodyLength, req).err(),
+        Some("length mismatch")
+    );
+
+    let req = http::Request::new(bytes::Bytes::from_static(b"payload"));
+    let req = crate::sign_blocking(&BodyLength, req).unwrap();
+    assert!(crate::validate_blocking(&BodyLength, req).is_ok());
+}
+
+/// The declared body length, parsed in the pre-validation.
+#[derive(Clone)]
+struct DeclaredLength(usize);
+
+/// Checks the `x-body-length` header before the body is buffered, and the body against it after.
+struct PreBodyLength;
+
+impl PreBodyLength {
+    fn declared(parts: &http::request::Parts) -> Result<usize, &'static str> {
+        parts
+            .headers
+            .get("x-body-length")
+            .and_then(|value| value.to_str().ok()?.parse().ok())
+            .ok_or("missing length")
+    }
+}
+
+impl<Data: bytes::Buf> crate::SyncValidator<Data> for PreBodyLength {
+    type Error = &'static str;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        let declared = match parts.extensions.get::<DeclaredLength>() {
+            Some(DeclaredLength(declared)) => *declared,
+            None => Self::declared(parts)?,
+        };
+        if declared != buffered_body.remaining() {
+            return Err("length mismatch");
+        }
+        Ok(())
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        let declared = Self::declared(parts)?;
+        parts.extensions.insert(DeclaredLength(declared));
+        Ok(())
+    }
+}
+
+#[test]
+fn pre_validate() {
+    let body = bytes::Bytes::from_static(b"payload");
+
+    let mut head = parts(http::Method::POST, "/");
+    assert_eq!(
+        crate::SyncValidator::<bytes::Bytes>::pre_validate(&PreBodyLength, &mut head),
+        Err("missing length")
+    );
+    head.headers.insert("x-body-length", 7.into());
+    assert_eq!(
+        crate::SyncValidator::<bytes::Bytes>::pre_validate(&PreBodyLength, &mut head),
+        Ok(())
+    );
+    assert!(head.extensions.get::<DeclaredLength>().is_some());
+    assert_eq!(
+        crate::validate_blocking(
+            &PreBodyLength,
+            http::Request::from_parts(head, body.clone())
+        )
+        .err(),
+        None
+    );
+
+    // The pre-validation is skipped when the predicate does not match.
+    let validator = crate::When::new(Methods([http::Method::POST]), PreBodyLength);
+    let mut head = parts(http::Method::GET, "/");
+    assert_eq!(
+        crate::SyncValidator::<bytes::Bytes>::pre_validate(&validator, &mut head),
+        Ok(())
+    );
+    let mut head = parts(http::Method::POST, "/");
+    assert_eq!(
+        crate::SyncValidator::<bytes::Bytes>::pre_validate(&validator, &mut head),
+        Err("missing length")
+    );
+
+    let dispatch = crate::Dispatch::<&'static str>::new().provider(
+        "length",
+        PathGlob("/hooks/**"),
+        crate::AsAsync(PreBodyLength),
+    );
+    let mut head = parts(http::Method::POST, "/hooks/length");
+    assert!(matches!(
+        crate::Validator::<bytes::Bytes>::pre_validate(&dispatch, &mut head),
+        Err(crate::dispatch::Error::Provider {
+            name: "length",
+            error: "missing length"
+        })
+    ));
+    let mut head = parts(http::Method::POST, "/other");
+    assert!(matches!(
+        crate::Validator::<bytes::Bytes>::pre_validate(&dispatch, &mut head),
+        Err(crate::dispatch::Error::NoProviderMatched)
+    ));
+}
+
+#[cfg(feature = "json-schema")]
+#[test]
+fn json_schema() {
+    use crate::json_schema::{Error, JsonSchemaValidator};
+
+    let validator = JsonSchemaValidator::new(&serde_json::json!({
+        "type": "object",
+        "properties": {
+            "items": { "type": "array", "items": { "type": "integer" } },
+        },
+    }))
+    .unwrap();
+    let validate = |body: &'static [u8]| {
+        crate::validate_blocking(
+            &validator,
+            http::Request::new(bytes::Bytes::from_static(body)),
+        )
+    };
+
+    assert!(validate(br#"{"items":[1,2]}"#).is_ok());
+    assert!(matches!(validate(b"{"), Err(Error::InvalidJson(_))));
+
+    let error = validate(br#"{"items":[1,"two",3.5]}"#).unwrap_err();
+    let paths: alloc::vec::Vec<_> = error
+        .violations()
+        .iter()
+        .map(|violation| violation.instance_path.as_str())
+        .collect();
+    assert_eq!(paths, ["/items/1", "/items/2"]);
+    assert_eq!(
+        crate::ValidationErrorKind::kind(&error),
+        crate::ErrorKind::Malformed
+    );
+
+    assert!(JsonSchemaValidator::new(&serde_json::json!({ "type": 1 })).is_err());
+}
+
+#[cfg(feature = "openapi")]
+#[test]
+fn openapi() {
+    use crate::openapi::{Error, Location, OpenApiValidator};
+
+    let validator = OpenApiValidator::new(&serde_json::json!({
+        "openapi": "3.1.0",
+        "info": { "title": "Pets", "version": "1" },
+        "paths": {
+            "/pets": {
+                "post": {
+                    "parameters": [
+                        { "$ref": "#/components/parameters/RequestId" },
+                        {
+                            "name": "dry_run",
+                            "in": "query",
+                            "schema": { "type": "boolean" },
+                        },
+                    ],
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "application/json": {
+                                "schema": { "$ref": "#/components/schemas/Pet" },
+                            },
+                        },
+                    },
+                },
+            },
+            "/pets/{petId}": {
+                "parameters": [{
+                    "name": "petId",
+                    "in": "path",
+                    "schema": { "type": "integer" },
+                }],
+                "get": {},
+                "delete": {},
+            },
+            "/pets/mine": {
+                "get": {
+                    "parameters": [{
+                        "name": "q",
+                        "in": "query",
+                        "schema": { "const": "% 1" },
+                    }],
+                },
+            },
+        },
+        "components": {
+            "parameters": {
+                "RequestId": {
+                    "name": "X-Request-Id",
+                    "in": "header",
+                    "required": true,
+                    "schema": { "type": "string", "minLength": 4 },
+                },
+            },
+            "schemas": {
+                "Pet": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": { "name": { "type": "string" } },
+                },
+            },
+        },
+    }))
+    .unwrap();
+    let validate = |method: &str, uri: &str, headers: &[(&str, &str)], body: &'static [u8]| {
+        let mut req = http::Request::builder().method(method).uri(uri);
+        for (name, value) in headers {
+            req = req.header(*name, *value);
+        }
+        crate::validate_blocking(
+            &validator,
+            req.body(bytes::Bytes::from_static(body)).unwrap(),
+        )
+    };
+    let json = ("content-type", "application/json; charset=utf-8");
+    let request_id = ("x-request-id", "abcd");
+
+    assert!(validate("GET", "/pets/42", &[], b"").is_ok());
+    assert!(validate("GET", "/pets/mine", &[], b"").is_ok());
+    assert!(matches!(
+        validate("GET", "/pets/rex", &[], b""),
+        Err(Error::InvalidParameter {
+            location: Location::Path,
+            ..
+        })
+    ));
+    assert!(matches!(
+        validate("GET", "/cats", &[], b""),
+        Err(Error::UnknownPath)
+    ));
+    // The literal path matches first, and the templated one is not tried for the other methods.
+    let error = validate("DELETE", "/pets/mine", &[], b"").unwrap_err();
+    assert!(matches!(error, Error::MethodNotAllowed));
+    assert_eq!(
+        crate::ValidationErrorKind::kind(&error),
+        crate::ErrorKind::MethodNotAllowed
+    );
+    assert!(validate("DELETE", "/pets/42", &[], b"").is_ok());
+    assert_eq!(
+        crate::ValidationErrorKind::kind(&validate("GET", "/cats", &[], b"").unwrap_err()),
+        crate::ErrorKind::NotFound
+    );
+    // Only the two hex digits are decoded, not the signed numbers.
+    assert!(validate("GET", "/pets/mine?q=%+1", &[], b"").is_ok());
+
+    let pet = br#"{"name":"Rex"}"#;
+    assert!(validate("POST", "/pets?dry_run=true", &[json, request_id], pet).is_ok());
+    assert!(matches!(
+        validate("POST", "/pets?dry_run=maybe", &[json, request_id], pet),
+        Err(Error::InvalidParameter {
+            location: Location::Query,
+            ..
+        })
+    ));
+    assert!(matches!(
+        validate("POST", "/pets", &[json], pet),
+        Err(Error::MissingParameter {
+            location: Location::Header,
+            ..
+        })
+    ));
+    assert!(matches!(
+        validate("POST", "/pets", &[request_id], b""),
+        Err(Error::MissingBody)
+    ));
+    assert!(matches!(
+        validate(
+            "POST",
+            "/pets",
+            &[("content-type", "text/plain"), request_id],
+            pet
+        ),
+        Err(Error::UnsupportedContentType)
+    ));
+    assert_eq!(
+        crate::ValidationErrorKind::kind(
+            &validate(
+                "POST",
+                "/pets",
+                &[("content-type", "text/plain"), request_id],
+                pet
+            )
+            .unwrap_err()
+        ),
+        crate::ErrorKind::UnsupportedMediaType
+    );
+
+    let error = validate("POST", "/pets", &[json, request_id], br#"{"name":1}"#).unwrap_err();
+    let Error::Body(error) = error else {
+        panic!("unexpected error: {error}");
+    };
+    assert_eq!(error.violations()[0].instance_path, "/name");
+
+    // Every value of the repeated parameters is checked.
+    assert!(matches!(
+        validate(
+            "POST",
+            "/pets?dry_run=true&dry_run=maybe",
+            &[json, request_id],
+            pet
+        ),
+        Err(Error::InvalidParameter {
+            location: Location::Query,
+            ..
+        })
+    ));
+    assert!(matches!(
+        validate(
+            "POST",
+            "/pets",
+            &[json, request_id, ("x-request-id", "abc")],
+            pet
+        ),
+        Err(Error::InvalidParameter {
+            location: Location::Header,
+            ..
+        })
+    ));
+
+    assert!(OpenApiValidator::new(&serde_json::json!({ "swagger": "2.0" })).is_err());
+}
+
+#[cfg(feature = "openapi")]
+#[test]
+fn openapi_3_0() {
+    let validator = crate::openapi::OpenApiValidator::new(&serde_json::json!({
+        "openapi": "3.0.3",
+        "info": { "title": "Pets", "version": "1" },
+        "paths": {
+            "/pets": {
+                "post": {
+                    "requestBody": {
+                        "content": {
+                            "application/json": {
+                                "schema": { "$ref": "#/components/schemas/Pet" },
+                            },
+                        },
+                    },
+                },
+            },
+        },
+        "components": {
+            "schemas": {
+                "Pet": {
+                    "type": "object",
+                    "properties": {
+                        "name": { "type": "string", "nullable": true },
+                        "kind": { "enum": ["cat", "dog"], "nullable": true },
+                        "age": { "type": "integer", "minimum": 0, "exclusiveMinimum": true },
+                        "weight": { "type": "number", "maximum": 50, "exclusiveMaximum": false },
+                        "nullable": { "type": "boolean" },
+                    },
+                    "example": { "nullable": true },
+                },
+            },
+        },
+    }))
+    .unwrap();
+    let validate = |body: &'static str| {
+        crate::validate_blocking(
+            &validator,
+            http::Request::post("/pets")
+                .header("content-type", "application/json")
+                .body(bytes::Bytes::from_static(body.as_bytes()))
+                .unwrap(),
+        )
+        .is_ok()
+    };
+
+    assert!(validate(r#"{"name":null,"kind":null,"age":1,"weight":50}"#));
+    assert!(validate(r#"{"nullable":false}"#));
+    assert!(!validate(r#"{"nullable":null}"#));
+    assert!(!validate(r#"{"name":1}"#));
+    assert!(!validate(r#"{"kind":"cow"}"#));
+    assert!(!validate(r#"{"age":0}"#));
+    assert!(!validate(r#"{"weight":50.5}"#));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn content_type() {
+    use crate::content_type::{ContentTypeValidator, Error, MediaType};
+    use crate::sniff::Format;
+
+    let validator = ContentTypeValidator::new()
+        .allow("application/json")
+        .allow("application/*+json")
+        .allow("application/x-www-form-urlencoded")
+        .allow("text/xml; version=\"1\"")
+        .with_required_charset("utf-8")
+        .with_sniffing(true);
+    let validate = |content_type: Option<&str>, body: &'static [u8]| {
+        let mut req = http::Request::post("/");
+        if let Some(content_type) = content_type {
+            req = req.header(http::header::CONTENT_TYPE, content_type);
+        }
+        crate::validate_blocking(
+            &validator,
+            req.body(bytes::Bytes::from_static(body)).unwrap(),
+        )
+        .map(drop)
+    };
+
+    assert_eq!(validate(None, b""), Ok(()));
+    assert_eq!(validate(None, b"{}"), Err(Error::MissingContentType));
+    assert_eq!(
+        validate(
+            Some("application/json; charset=\"UTF-8\""),
+            br#"{"a":[1,-2.5e3,null]}"#
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        validate(Some("application/vnd.api+json;charset=utf-8"), b"[]"),
+        Ok(())
+    );
+    assert_eq!(
+        validate(
+            Some("application/x-www-form-urlencoded; charset=utf-8"),
+            b"a=1&b=%20x+y"
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        validate(
+            Some("text/xml; charset=utf-8; version=1"),
+            b"<?xml version=\"1.0\"?><a x=\"1>2\"><b/><![CDATA[<c>]]></a>"
+        ),
+        Ok(())
+    );
+
+    assert_eq!(
+        validate(Some("application/json;"), b"{}"),
+        Err(Error::MissingCharset)
+    );
+    assert_eq!(
+        validate(Some("application/json; charset=latin1"), b"{}"),
+        Err(Error::CharsetMismatch("latin1".into()))
+    );
+    assert_eq!(
+        validate(Some("text/xml; charset=utf-8"), b"<a/>"),
+        Err(Error::UnsupportedMediaType(
+            MediaType::parse("text/xml; charset=utf-8").unwrap()
+        ))
+    );
+    assert_eq!(
+        crate::ValidationErrorKind::kind(&validate(Some("text/plain"), b"").unwrap_err()),
+        crate::ErrorKind::UnsupportedMediaType
+    );
+    assert_eq!(
+        validate(Some("application json"), b"{}"),
+        Err(Error::InvalidContentType)
+    );
+    assert_eq!(
+        validate(Some("application/json; charset=utf-8"), b"a=1"),
+        Err(Error::BodyMismatch(Format::Json))
+    );
+    assert_eq!(
+        validate(
+            Some("application/x-www-form-urlencoded; charset=utf-8"),
+            br#"{"a":1}"#
+        ),
+        Err(Error::BodyMismatch(Format::UrlEncoded))
+    );
+    assert_eq!(
+        validate(
+            Some("text/xml; charset=utf-8; version=1"),
+            b"<a><b></a></b>"
+        ),
+        Err(Error::BodyMismatch(Format::Xml))
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn sniff() {
+    use crate::sniff::{is_json, is_urlencoded, is_xml};
+
+    assert!(is_json(
+        b" {\"a\": \"\\u00e9\\n\", \"b\": [true, false, 0.5]} "
+    ));
+    for invalid in [
+        &b""[..],
+        b"{",
+        b"[1,]",
+        b"01",
+        b"1.",
+        b"\"\t\"",
+        b"{} {}",
+        b"{'a':1}",
+    ] {
+        assert!(!is_json(invalid), "{}", invalid.escape_ascii());
+    }
+    assert!(!is_json(&[b'['; 1000]));
+
+    assert!(is_urlencoded(b""));
+    assert!(!is_urlencoded(b"a=%2"));
+    assert!(!is_urlencoded(b"a=b c"));
+
+    assert!(is_xml(
+        b"\xEF\xBB\xBF<!DOCTYPE a [<!ELEMENT a ANY>]><!-- c --><a></a >\n"
+    ));
+    assert!(!is_xml(b"<a/><b/>"));
+    assert!(!is_xml(b"text<a/>"));
+    assert!(!is_xml(b"<a>"));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn multipart() {
+    use crate::multipart::{Error, Limits, Multipart};
+
+    let body = b"preamble\r\n\
+        --b\r\n\
+        Content-Disposition: form-data; name=\"manifest\"\r\n\
+        \r\n\
+        5\r\n\
+        --b  \r\n\
+        content-disposition: form-data; filename=\"a;b.txt\"; name=file\r\n\
+        Content-Type: text/plain\r\n\
+        \r\n\
+        hello\r\n\
+        --b\r\n\
+        \r\n\
+        \r\n\
+        --b--\r\n\
+        epilogue";
+    let (parts, ()) = http::Request::post("/")
+        .header("content-type", "multipart/mixed; boundary=\"b\"")
+        .body(())
+        .unwrap()
+        .into_parts();
+
+    let multipart = Multipart::from_request(&parts, body).unwrap();
+    let parsed: alloc::vec::Vec<_> = multipart.collect::<Result<_, _>>().unwrap();
+    assert_eq!(parsed.len(), 3);
+    assert_eq!(parsed[0].name().as_deref(), Some("manifest"));
+    assert_eq!(parsed[0].filename(), None);
+    assert_eq!(parsed[0].body(), b"5");
+    assert_eq!(parsed[1].name().as_deref(), Some("file"));
+    assert_eq!(parsed[1].filename().as_deref(), Some("a;b.txt"));
+    assert_eq!(parsed[1].header("CONTENT-TYPE"), Some(&b"text/plain"[..]));
+    assert_eq!(parsed[1].headers().count(), 2);
+    assert_eq!(parsed[1].body(), b"hello");
+    assert_eq!(parsed[2].headers().count(), 0);
+    assert_eq!(parsed[2].body(), b"");
+
+    // The parts borrow from the body.
+    assert!(body.as_ptr_range().contains(&parsed[1].body().as_ptr()));
+
+    let first_error = |limits: Limits| {
+        Multipart::new(body, "b")
+            .unwrap()
+            .with_limits(limits)
+            .find_map(Result::err)
+    };
+    assert_eq!(first_error(Limits::default()), None);
+    assert_eq!(
+        first_error(Limits {
+            max_parts: 2,
+            ..Limits::default()
+        }),
+        Some(Error::TooManyParts { max_parts: 2 })
+    );
+    assert_eq!(
+        first_error(Limits {
+            max_part_size: 4,
+            ..Limits::default()
+        }),
+        Some(Error::PartTooLarge { max_part_size: 4 })
+    );
+    assert_eq!(
+        first_error(Limits {
+            max_headers_size: 16,
+            ..Limits::default()
+        }),
+        Some(Error::HeadersTooLarge {
+            max_headers_size: 16
+        })
+    );
+
+    // The oversized part is rejected before its end is found.
+    assert_eq!(
+        Multipart::new(b"--b\r\n\r\nunterminated", "b")
+            .unwrap()
+            .with_limits(Limits {
+                max_part_size: 4,
+                ..Limits::default()
+            })
+            .find_map(Result::err),
+        Some(Error::PartTooLarge { max_part_size: 4 })
+    );
+    let escaped = Multipart::new(
+        b"--b\r\nContent-Disposition: form-data; name=\"a \\\"b\\\"; c\"\r\n\r\n\r\n--b--",
+        "b",
+    )
+    .unwrap()
+    .next()
+    .unwrap()
+    .unwrap();
+    assert_eq!(escaped.name().as_deref(), Some("a \"b\"; c"));
+
+    assert_eq!(
+        Multipart::new(b"--b\r\n\r\nunterminated", "b")
+            .unwrap()
+            .find_map(Result::err),
+        Some(Error::MalformedBody)
+    );
+    assert_eq!(
+        Multipart::new(b"--b\r\nno colon\r\n\r\n\r\n--b--", "b")
+            .unwrap()
+            .find_map(Result::err),
+        Some(Error::MalformedHeaders)
+    );
+    assert_eq!(
+        Multipart::new(b"no delimiter", "b").err(),
+        Some(Error::MalformedBody)
+    );
+    assert_eq!(Multipart::new(b"", "").err(), Some(Error::InvalidBoundary));
+
+    let (parts, ()) = http::Request::post("/")
+        .header("content-type", "application/json")
+        .body(())
+        .unwrap()
+        .into_parts();
+    assert_eq!(
+        Multipart::from_request(&parts, body).err(),
+        Some(Error::NotMultipart)
+    );
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn multipart_validator() {
+    use crate::multipart::{Error, Limits, MultipartValidator, Part, ValidatorError};
+    use crate::{ErrorKind, ValidationErrorKind as _};
+
+    let validator = MultipartValidator::new(|part: &Part<'_>| {
+        if part.name().is_none() {
+            return Err("unnamed part");
+        }
+        Ok(())
+    })
+    .with_limits(Limits {
+        max_parts: 2,
+        ..Limits::default()
+    });
+    let validate = |content_type: &str, body: &'static [u8]| {
+        crate::validate_blocking(
+            &validator,
+            http::Request::post("/")
+                .header(http::header::CONTENT_TYPE, content_type)
+                .body(bytes::Bytes::from_static(body))
+                .unwrap(),
+        )
+        .map(drop)
+    };
+
+    let form = "multipart/form-data; boundary=b";
+    assert_eq!(
+        validate(
+            form,
+            b"--b\r\nContent-Disposition: form-data; name=a\r\n\r\n1\r\n--b--"
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        validate(form, b"--b\r\nContent-Type: text/plain\r\n\r\n1\r\n--b--"),
+        Err(ValidatorError::Part("unnamed part"))
+    );
+    let error = validate(
+        form,
+        concat!(
+            "--b\r\nContent-Disposition: form-data; name=a\r\n\r\n1\r\n",
+            "--b\r\nContent-Disposition: form-data; name=b\r\n\r\n2\r\n",
+            "--b\r\nContent-Disposition: form-data; name=c\r\n\r\n3\r\n--b--",
+        )
+        .as_bytes(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        error,
+        ValidatorError::Multipart(Error::TooManyParts { max_parts: 2 })
+    );
+    assert_eq!(error.kind(), ErrorKind::TooLarge);
+
+    // Rejected before the body is buffered.
+    let (mut parts, ()) = http::Request::post("/")
+        .header(http::header::CONTENT_TYPE, "multipart/form-data")
+        .body(())
+        .unwrap()
+        .into_parts();
+    assert_eq!(
+        crate::SyncValidator::<bytes::Bytes>::pre_validate(&validator, &mut parts),
+        Err(ValidatorError::Multipart(Error::InvalidBoundary))
+    );
+    assert_eq!(
+        validate("application/json", b"{}"),
+        Err(ValidatorError::Multipart(Error::NotMultipart))
+    );
+}
@@ -0,0 +1,47 @@
+//! The conventions for exposing the TLS connection details to the validators.
+//!
+//! The validators only see the request head and body, so whatever terminates TLS, the server
+//! or a middleware in front of the validation, records the connection details in the
+//! [`http::Extensions`] of every request, and the validators read them from there.
+
+use alloc::vec::Vec;
+
+/// The certificate chain the client presented in the TLS handshake.
+///
+/// The certificates are in DER, the client's own, the leaf, first, followed by the
+/// intermediates in the order they were sent. The chain is inserted into the request extensions
+/// only after the TLS stack has verified it against the trusted roots; the requests over the
+/// connections without a client certificate have no [`PeerCertificates`].
+///
+/// Only the leaf certificate is bound to the key the client proved the possession of, the rest
+/// of the chain is whatever the client sent, so the validators make their decisions on the leaf.
+///
+/// With `rustls`, the chain is the `peer_certificates` of the connection.
+///
+/// ## Examples
+///
+/// ```
+/// use http_request_validator::tls::PeerCertificates;
+///
+/// # let der = bytes::Bytes::from_static(b"0");
+/// let mut req = http::Request::new(());
+/// req.extensions_mut().insert(PeerCertificates(vec![der.clone()]));
+///
+/// let (parts, _) = req.into_parts();
+/// let certificates = PeerCertificates::of(&parts).unwrap();
+/// assert_eq!(certificates.leaf(), Some(&*der));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PeerCertificates(pub Vec<bytes::Bytes>);
+
+impl PeerCertificates {
+    /// The certificate chain presented on the connection the request came over, if any.
+    pub fn of(parts: &http::request::Parts) -> Option<&Self> {
+        parts.extensions.get::<Self>()
+    }
+
+    /// The leaf certificate, in DER.
+    pub fn leaf(&self) -> Option<&[u8]> {
+        self.0.first().map(|certificate| &**certificate)
+    }
+}
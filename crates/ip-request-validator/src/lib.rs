@@ -0,0 +1,272 @@
+//! IP allow-list validator for the [`http_request_validator`].
+//!
+//! Many providers publish the address ranges they send the webhooks from, so the requests from
+//! anywhere else can be rejected regardless of their signatures, as a defense in depth.
+
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, PoisonError, RwLock},
+};
+
+pub mod ranges;
+
+pub use self::ranges::{InvalidRange, IpRange, IpRanges};
+
+/// The validator that only lets the requests from the allowed addresses through.
+///
+/// The client address is the peer address of the connection, taken from the request
+/// extensions, where the servers put it: the [`SocketAddr`] or the [`IpAddr`], or the
+/// `axum::extract::ConnectInfo<SocketAddr>` with the `axum` feature.
+///
+/// Behind the reverse proxies, the peer is the nearest proxy, so the client address is taken
+/// from the forwarding header the proxies set instead, the `X-Forwarded-For` by default, or
+/// the `Forwarded`, see [`IpAllowList::with_forwarded_header`]. Only that one header is read:
+/// the proxies pass the other one from the client through as is, so it can not be trusted.
+/// Every proxy appends the address it got the request from, so with `N` trusted proxies the
+/// client address is the `N`-th entry from the end, and the entries before it, which
+/// the client could have made up, are ignored. The header is only trusted if the peer is one
+/// of the proxies, see [`IpAllowList::with_trusted_proxies`].
+///
+/// The ranges are shared between the clones of the validator, and can be replaced at runtime
+/// with [`IpAllowList::reload`], for instance when a provider publishes the new ranges.
+///
+/// The validator only looks at the request head, so the address is checked in
+/// [`http_request_validator::SyncValidator::pre_validate`] too: the integrations that run it
+/// reject the requests from anywhere else before their bodies are buffered, except in
+/// the report-only mode, where the body is buffered anyway. The validation proper checks
+/// the address again, as not every caller runs the pre-validation.
+///
+/// ## Examples
+///
+/// ```
+/// use http_request_validator::validate_blocking;
+/// use ip_request_validator::{IpAllowList, IpRanges};
+///
+/// let github = IpRanges::parse(["192.30.252.0/22", "2a0a:a440::/29"]).unwrap();
+/// let proxies = IpRanges::parse(["10.0.0.0/8"]).unwrap();
+/// let validator = IpAllowList::new(github).with_trusted_proxies(1, proxies);
+///
+/// let mut req = http::Request::post("/")
+///     .header("x-forwarded-for", "192.30.252.41")
+///     .body(bytes::Bytes::new())
+///     .unwrap();
+/// req.extensions_mut()
+///     .insert(std::net::SocketAddr::from(([10, 0, 0, 2], 41000)));
+/// assert!(validate_blocking(&validator, req).is_ok());
+///
+/// validator.reload(IpRanges::parse(["185.199.108.0/22"]).unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct IpAllowList {
+    /// The allowed ranges, shared between the clones.
+    ranges: Arc<RwLock<Arc<IpRanges>>>,
+
+    /// The number of the trusted reverse proxies in front of the server.
+    trusted_proxies: usize,
+
+    /// The ranges the nearest trusted proxy connects from.
+    proxies: IpRanges,
+
+    /// The header the trusted proxies set.
+    forwarded_header: ForwardedHeader,
+}
+
+/// The forwarding header the trusted reverse proxies set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ForwardedHeader {
+    /// The de facto standard `X-Forwarded-For` header.
+    #[default]
+    XForwardedFor,
+    /// The standard `Forwarded` header, taking its `for` parameter.
+    Forwarded,
+}
+
+impl IpAllowList {
+    /// Create a new [`IpAllowList`] allowing the given ranges.
+    pub fn new(ranges: IpRanges) -> Self {
+        Self {
+            ranges: Arc::new(RwLock::new(Arc::new(ranges))),
+            trusted_proxies: 0,
+            proxies: IpRanges::new(),
+            forwarded_header: ForwardedHeader::XForwardedFor,
+        }
+    }
+
+    /// Take the client address from the forwarding headers, set by the given number of
+    /// the trusted reverse proxies, the nearest of which connects from the given ranges.
+    ///
+    /// The requests from any other peer are rejected, as whoever reaches the server directly
+    /// can make up the forwarding headers.
+    pub fn with_trusted_proxies(mut self, trusted_proxies: usize, proxies: IpRanges) -> Self {
+        self.trusted_proxies = trusted_proxies;
+        self.proxies = proxies;
+        self
+    }
+
+    /// Take the client address from the given forwarding header, the `X-Forwarded-For` by
+    /// default.
+    ///
+    /// Only has an effect with the trusted proxies.
+    pub const fn with_forwarded_header(mut self, forwarded_header: ForwardedHeader) -> Self {
+        self.forwarded_header = forwarded_header;
+        self
+    }
+
+    /// Replace the allowed ranges, for this validator and all its clones.
+    pub fn reload(&self, ranges: IpRanges) {
+        *self.ranges.write().unwrap_or_else(PoisonError::into_inner) = Arc::new(ranges);
+    }
+
+    /// The currently allowed ranges.
+    pub fn ranges(&self) -> Arc<IpRanges> {
+        Arc::clone(&self.ranges.read().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// The client address of the request.
+    pub fn client_addr(&self, parts: &http::request::Parts) -> Result<IpAddr, Error> {
+        let peer = peer_addr(&parts.extensions).ok_or(Error::MissingPeerAddr)?;
+        if self.trusted_proxies == 0 {
+            return Ok(peer);
+        }
+        if !self.proxies.contains(peer) {
+            return Err(Error::UntrustedPeer(peer));
+        }
+
+        let hops = match self.forwarded_header {
+            ForwardedHeader::XForwardedFor => x_forwarded_for_hops(&parts.headers)?,
+            ForwardedHeader::Forwarded => forwarded_hops(&parts.headers)?,
+        };
+        hops.len()
+            .checked_sub(self.trusted_proxies)
+            .and_then(|index| hops[index])
+            .ok_or(Error::InvalidForwarded)
+    }
+
+    /// Check the client address of the request, returning it if it is allowed.
+    pub fn check(&self, parts: &http::request::Parts) -> Result<IpAddr, Error> {
+        let addr = self.client_addr(parts)?;
+        if !self.ranges().contains(addr) {
+            return Err(Error::NotAllowed(addr));
+        }
+        Ok(addr)
+    }
+}
+
+/// The peer address of the connection, from the request extensions.
+fn peer_addr(extensions: &http::Extensions) -> Option<IpAddr> {
+    #[cfg(feature = "axum")]
+    if let Some(axum::extract::ConnectInfo(addr)) =
+        extensions.get::<axum::extract::ConnectInfo<SocketAddr>>()
+    {
+        return Some(addr.ip());
+    }
+    extensions
+        .get::<SocketAddr>()
+        .map(SocketAddr::ip)
+        .or_else(|| extensions.get::<IpAddr>().copied())
+}
+
+/// The addresses from the `Forwarded` headers, in the order the proxies appended them.
+///
+/// The entries that are not addresses, like the obfuscated identifiers, are [`None`].
+fn forwarded_hops(headers: &http::HeaderMap) -> Result<Vec<Option<IpAddr>>, Error> {
+    let mut hops = Vec::new();
+    for value in headers.get_all(http::header::FORWARDED) {
+        let value = value.to_str().map_err(|_| Error::InvalidForwarded)?;
+        for element in value.split(',') {
+            let node = element
+                .split(';')
+                .filter_map(|pair| pair.split_once('='))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("for"))
+                .map(|(_, node)| node.trim().trim_matches('"'));
+            hops.push(node.and_then(parse_node));
+        }
+    }
+    Ok(hops)
+}
+
+/// The addresses from the `X-Forwarded-For` headers, in the order the proxies appended them.
+///
+/// The entries that are not addresses are [`None`].
+fn x_forwarded_for_hops(headers: &http::HeaderMap) -> Result<Vec<Option<IpAddr>>, Error> {
+    let mut hops = Vec::new();
+    for value in headers.get_all("x-forwarded-for") {
+        let value = value.to_str().map_err(|_| Error::InvalidForwarded)?;
+        for node in value.split(',') {
+            hops.push(parse_node(node.trim()));
+        }
+    }
+    Ok(hops)
+}
+
+/// Parse the node address, optionally with a port, and with the IPv6 in the brackets.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    if let Ok(addr) = node.parse() {
+        return Some(addr);
+    }
+    node.parse::<SocketAddr>()
+        .ok()
+        .map(|addr| addr.ip())
+        .or_else(|| node.strip_prefix('[')?.strip_suffix(']')?.parse().ok())
+}
+
+/// An error that can occur while validating the client address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The peer address is not in the request extensions, the server does not provide it.
+    MissingPeerAddr,
+    /// The peer is not a trusted proxy, so its forwarding headers can not be trusted.
+    UntrustedPeer(IpAddr),
+    /// The forwarding headers do not have enough entries, or the client entry is not
+    /// an address.
+    InvalidForwarded,
+    /// The client address is not allowed.
+    NotAllowed(IpAddr),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingPeerAddr => f.write_str("missing peer address"),
+            Self::UntrustedPeer(addr) => write!(f, "peer {addr} is not a trusted proxy"),
+            Self::InvalidForwarded => f.write_str("invalid forwarding headers"),
+            Self::NotAllowed(addr) => write!(f, "address {addr} is not allowed"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl http_request_validator::ValidationErrorKind for Error {
+    fn kind(&self) -> http_request_validator::ErrorKind {
+        use http_request_validator::ErrorKind;
+
+        match self {
+            Self::MissingPeerAddr => ErrorKind::Other,
+            Self::InvalidForwarded => ErrorKind::Malformed,
+            Self::UntrustedPeer(_) | Self::NotAllowed(_) => ErrorKind::Forbidden,
+        }
+    }
+}
+
+impl<Data> http_request_validator::SyncValidator<Data> for IpAllowList
+where
+    Data: bytes::Buf,
+{
+    type Error = Error;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        _buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        self.check(parts).map(drop)
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        self.check(parts).map(drop)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,183 @@
+//! [`IpRanges`] for matching the addresses against the CIDR lists.
+
+use core::net::IpAddr;
+
+/// A CIDR range of addresses, like `192.30.252.0/22` or `2a0a:a440::/29`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct IpRange {
+    /// The first address of the range.
+    addr: IpAddr,
+
+    /// The length of the network prefix, in bits.
+    prefix_len: u8,
+}
+
+impl IpRange {
+    /// Create a new [`IpRange`] of the addresses sharing the first `prefix_len` bits with
+    /// the given address.
+    ///
+    /// Returns [`None`] if the prefix is longer than the address.
+    pub fn new(addr: IpAddr, prefix_len: u8) -> Option<Self> {
+        let addr = match addr {
+            IpAddr::V4(addr) if prefix_len <= 32 => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(prefix_len))
+                    .unwrap_or(0);
+                IpAddr::V4((u32::from(addr) & mask).into())
+            }
+            IpAddr::V6(addr) if prefix_len <= 128 => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(prefix_len))
+                    .unwrap_or(0);
+                IpAddr::V6((u128::from(addr) & mask).into())
+            }
+            _ => return None,
+        };
+        Some(Self { addr, prefix_len })
+    }
+
+    /// Parse the range in the CIDR notation.
+    ///
+    /// A bare address is a range of just that address. The host bits of the address are
+    /// ignored, so `10.1.2.3/8` is the same as `10.0.0.0/8`.
+    pub fn parse(value: &str) -> Result<Self, InvalidRange> {
+        let invalid = || InvalidRange {
+            value: value.into(),
+        };
+        let (addr, prefix_len) = match value.trim().split_once('/') {
+            Some((addr, prefix_len)) => {
+                let addr: IpAddr = addr.parse().map_err(|_| invalid())?;
+                (addr, Some(prefix_len.parse().map_err(|_| invalid())?))
+            }
+            None => (value.trim().parse().map_err(|_| invalid())?, None),
+        };
+        let prefix_len = prefix_len.unwrap_or(match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        });
+        // The IPv4-mapped IPv6 ranges are stored as the IPv4 ones.
+        let (addr, prefix_len) = match addr {
+            IpAddr::V6(v6) if v6.to_ipv4_mapped().is_some() && prefix_len >= 96 => {
+                (canonical(addr), prefix_len - 96)
+            }
+            _ => (addr, prefix_len),
+        };
+        Self::new(addr, prefix_len).ok_or_else(invalid)
+    }
+
+    /// Whether the address is within this range.
+    ///
+    /// The IPv4 addresses and their IPv4-mapped IPv6 forms are matched alike, against
+    /// the ranges of either family.
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        let addr = match (self.addr, canonical(addr)) {
+            (IpAddr::V6(_), IpAddr::V4(v4)) => IpAddr::V6(v4.to_ipv6_mapped()),
+            (_, addr) => addr,
+        };
+        Self::new(addr, self.prefix_len).is_some_and(|range| range.addr == self.addr)
+    }
+}
+
+impl core::str::FromStr for IpRange {
+    type Err = InvalidRange;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::parse(value)
+    }
+}
+
+impl core::fmt::Display for IpRange {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+/// The address with the IPv4-mapped IPv6 addresses converted to the IPv4 ones.
+fn canonical(addr: IpAddr) -> IpAddr {
+    match addr {
+        IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(addr, IpAddr::V4),
+        IpAddr::V4(_) => addr,
+    }
+}
+
+/// A list of the [`IpRange`]s.
+///
+/// The providers publish their ranges in different shapes, so the list is built from any
+/// strings: collected from the parsed ranges, or parsed from the lines of a plain text list.
+/// The lists of several providers are merged with [`Extend`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IpRanges {
+    /// The ranges.
+    ranges: Vec<IpRange>,
+}
+
+impl IpRanges {
+    /// Create a new empty [`IpRanges`], which contains no addresses.
+    pub const fn new() -> Self {
+        Self { ranges: Vec::new() }
+    }
+
+    /// Parse the ranges in the CIDR notation.
+    pub fn parse<'a>(ranges: impl IntoIterator<Item = &'a str>) -> Result<Self, InvalidRange> {
+        ranges.into_iter().map(IpRange::parse).collect()
+    }
+
+    /// Parse the ranges from a plain text list, one per line.
+    ///
+    /// The empty lines and the lines starting with `#` are skipped.
+    pub fn from_lines(text: &str) -> Result<Self, InvalidRange> {
+        Self::parse(
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#')),
+        )
+    }
+
+    /// Whether the address is within any of the ranges.
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        self.ranges.iter().any(|range| range.contains(addr))
+    }
+
+    /// The ranges.
+    pub fn iter(&self) -> core::slice::Iter<'_, IpRange> {
+        self.ranges.iter()
+    }
+}
+
+impl FromIterator<IpRange> for IpRanges {
+    fn from_iter<I: IntoIterator<Item = IpRange>>(iter: I) -> Self {
+        Self {
+            ranges: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl Extend<IpRange> for IpRanges {
+    fn extend<I: IntoIterator<Item = IpRange>>(&mut self, iter: I) {
+        self.ranges.extend(iter);
+    }
+}
+
+impl<'a> IntoIterator for &'a IpRanges {
+    type Item = &'a IpRange;
+    type IntoIter = core::slice::Iter<'a, IpRange>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The range can not be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidRange {
+    /// The value that can not be parsed.
+    pub value: String,
+}
+
+impl core::fmt::Display for InvalidRange {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid IP range: {:?}", self.value)
+    }
+}
+
+impl std::error::Error for InvalidRange {}
@@ -0,0 +1,251 @@
+use http_request_validator::{ErrorKind, ValidationErrorKind as _};
+use http_request_validator_test::{assert_invalid, validate_sync, TestRequest};
+
+use super::*;
+
+fn github() -> IpRanges {
+    IpRanges::from_lines(
+        "# hooks
+        192.30.252.0/22
+        185.199.108.0/22
+
+        2a0a:a440::/29
+        ",
+    )
+    .unwrap()
+}
+
+#[test]
+fn ranges() {
+    let ranges = github();
+    for addr in [
+        "192.30.252.0",
+        "192.30.255.255",
+        "2a0a:a447::1",
+        "::ffff:185.199.111.1",
+    ] {
+        assert!(ranges.contains(addr.parse().unwrap()), "{addr}");
+    }
+    for addr in [
+        "192.30.251.255",
+        "192.30.0.1",
+        "2a0a:a448::",
+        "::ffff:c01e:fb01",
+    ] {
+        assert!(!ranges.contains(addr.parse().unwrap()), "{addr}");
+    }
+
+    assert_eq!(
+        IpRange::parse("10.1.2.3/8").unwrap().to_string(),
+        "10.0.0.0/8"
+    );
+    assert_eq!(
+        IpRange::parse("10.1.2.3").unwrap().to_string(),
+        "10.1.2.3/32"
+    );
+    assert_eq!(
+        IpRange::parse("::ffff:10.0.0.0/104").unwrap().to_string(),
+        "10.0.0.0/8"
+    );
+    assert_eq!(
+        IpRange::parse("0.0.0.0/0").unwrap().to_string(),
+        "0.0.0.0/0"
+    );
+    assert!(IpRange::parse("0.0.0.0/0")
+        .unwrap()
+        .contains("8.8.8.8".parse().unwrap()));
+    assert!(!IpRange::parse("0.0.0.0/0")
+        .unwrap()
+        .contains("2001:db8::1".parse().unwrap()));
+    assert_eq!(IpRange::parse("::/0").unwrap().to_string(), "::/0");
+
+    // The IPv4-mapped addresses are matched as the IPv4 ones by the ranges of either family.
+    let mapped = IpRange::parse("::ffff:0:0/80").unwrap();
+    let v6 = IpRange::parse("::/0").unwrap();
+    for addr in ["192.30.252.1", "::ffff:192.30.252.1"] {
+        assert!(mapped.contains(addr.parse().unwrap()), "{addr}");
+        assert!(v6.contains(addr.parse().unwrap()), "{addr}");
+    }
+    assert!(!mapped.contains("2a0a:a447::1".parse().unwrap()));
+
+    for invalid in [
+        "10.0.0.0/33",
+        "::/129",
+        "10.0.0/8",
+        "10.0.0.0/",
+        "example.com",
+        "",
+    ] {
+        assert_eq!(
+            IpRange::parse(invalid).unwrap_err().value,
+            invalid,
+            "{invalid}"
+        );
+    }
+    assert!(IpRanges::from_lines("10.0.0.0/8\nnot a range").is_err());
+
+    let mut merged = github();
+    merged.extend(IpRanges::parse(["54.187.174.169"]).unwrap().iter().copied());
+    assert!(merged.contains("54.187.174.169".parse().unwrap()));
+    assert_eq!(merged.iter().count(), 4);
+}
+
+#[test]
+fn peer_addr() {
+    let validator = IpAllowList::new(github());
+    let validate = |req| validate_sync(&validator, req);
+
+    let allowed = SocketAddr::from(([192, 30, 252, 41], 41000));
+    assert!(validate(TestRequest::post("/").extension(allowed)).is_ok());
+    assert!(validate(TestRequest::post("/").extension(allowed.ip())).is_ok());
+
+    // The forwarding headers are ignored without the trusted proxies.
+    let req = TestRequest::post("/")
+        .header("x-forwarded-for", "192.30.252.41")
+        .extension(SocketAddr::from(([203, 0, 113, 7], 41000)));
+    let error = assert_invalid(validate(req));
+    assert_eq!(error, Error::NotAllowed([203, 0, 113, 7].into()));
+    assert_eq!(error.kind(), ErrorKind::Forbidden);
+
+    let error = assert_invalid(validate(TestRequest::post("/")));
+    assert_eq!(error, Error::MissingPeerAddr);
+    assert_eq!(error.kind(), ErrorKind::Other);
+}
+
+#[test]
+fn forwarded() {
+    let proxies = || IpRanges::parse(["10.0.0.0/8", "fd00::/8"]).unwrap();
+    let proxy = SocketAddr::from(([10, 0, 0, 3], 41000));
+    let validate_from = |validator: &IpAllowList, peer: SocketAddr, headers: &[(&str, &str)]| {
+        let req = headers
+            .iter()
+            .fold(TestRequest::post("/"), |req, (name, value)| {
+                req.header(name, value)
+            });
+        validate_sync(validator, req.extension(peer)).map(drop)
+    };
+    let validate = |validator: &IpAllowList, headers: &[(&str, &str)]| {
+        validate_from(validator, proxy, headers)
+    };
+
+    let validator = IpAllowList::new(github()).with_trusted_proxies(2, proxies());
+    let validate_xff = |headers: &[(&str, &str)]| validate(&validator, headers);
+
+    // The client has made up the first entry.
+    assert_eq!(
+        validate_xff(&[("x-forwarded-for", "203.0.113.7, 192.30.252.41, 10.0.0.2")]),
+        Ok(())
+    );
+    assert_eq!(
+        validate_xff(&[
+            ("x-forwarded-for", "192.30.252.41, 203.0.113.7"),
+            ("x-forwarded-for", "10.0.0.2")
+        ]),
+        Err(Error::NotAllowed([203, 0, 113, 7].into()))
+    );
+    assert_eq!(
+        validate_xff(&[("x-forwarded-for", "10.0.0.2")]),
+        Err(Error::InvalidForwarded)
+    );
+    assert_eq!(validate_xff(&[]), Err(Error::InvalidForwarded));
+    assert_eq!(
+        validate_xff(&[("x-forwarded-for", "unknown, 10.0.0.2")]),
+        Err(Error::InvalidForwarded)
+    );
+
+    // Whoever reaches the server directly can make up the header.
+    let direct = SocketAddr::from(([203, 0, 113, 7], 41000));
+    let spoofed = [("x-forwarded-for", "192.30.252.41, 10.0.0.2")];
+    assert_eq!(
+        validate_from(&validator, direct, &spoofed),
+        Err(Error::UntrustedPeer(direct.ip()))
+    );
+    assert_eq!(
+        validate_from(&validator, direct, &spoofed)
+            .unwrap_err()
+            .kind(),
+        ErrorKind::Forbidden
+    );
+    let mapped = SocketAddr::from((std::net::Ipv4Addr::new(10, 0, 0, 3).to_ipv6_mapped(), 41000));
+    assert_eq!(validate_from(&validator, mapped, &spoofed), Ok(()));
+    assert_eq!(
+        validate_sync(
+            &validator,
+            TestRequest::post("/").header(spoofed[0].0, spoofed[0].1)
+        )
+        .map(drop),
+        Err(Error::MissingPeerAddr)
+    );
+
+    // The `Forwarded` from the client is passed through by the proxies, and is not read.
+    assert_eq!(
+        validate_xff(&[
+            ("forwarded", "for=192.30.252.41, for=10.0.0.2"),
+            ("x-forwarded-for", "203.0.113.7, 10.0.0.2")
+        ]),
+        Err(Error::NotAllowed([203, 0, 113, 7].into()))
+    );
+
+    let validator = IpAllowList::new(github())
+        .with_trusted_proxies(2, proxies())
+        .with_forwarded_header(ForwardedHeader::Forwarded);
+    let validate_forwarded = |headers: &[(&str, &str)]| validate(&validator, headers);
+
+    assert_eq!(
+        validate_forwarded(&[(
+            "forwarded",
+            r#"for=_hidden, for="[2a0a:a440::17]:4711";proto=https, for=10.0.0.2"#
+        )]),
+        Ok(())
+    );
+    assert_eq!(
+        validate_forwarded(&[(
+            "forwarded",
+            "for=192.30.252.41:443;by=10.0.0.1, For=10.0.0.2"
+        )]),
+        Ok(())
+    );
+    // And the other way around.
+    assert_eq!(
+        validate_forwarded(&[
+            ("forwarded", "for=203.0.113.7, for=10.0.0.2"),
+            ("x-forwarded-for", "192.30.252.41, 10.0.0.2")
+        ]),
+        Err(Error::NotAllowed([203, 0, 113, 7].into()))
+    );
+    assert_eq!(
+        validate_forwarded(&[("x-forwarded-for", "192.30.252.41, 10.0.0.2")]),
+        Err(Error::InvalidForwarded)
+    );
+    assert_eq!(
+        validate_forwarded(&[("forwarded", "for=_hidden, by=10.0.0.1;proto=https")]),
+        Err(Error::InvalidForwarded)
+    );
+    assert_eq!(
+        validate_forwarded(&[("forwarded", "for=_hidden, by=10.0.0.1;proto=https")])
+            .unwrap_err()
+            .kind(),
+        ErrorKind::Malformed
+    );
+}
+
+#[test]
+fn reload() {
+    let validator = IpAllowList::new(github());
+    let clone = validator.clone();
+    let req = || TestRequest::post("/").extension(IpAddr::from([140, 82, 112, 1]));
+
+    assert!(validate_sync(&clone, req()).is_err());
+    validator.reload(IpRanges::parse(["140.82.112.0/20"]).unwrap());
+    assert!(validate_sync(&clone, req()).is_ok());
+    assert_eq!(clone.ranges().iter().count(), 1);
+}
+
+#[cfg(feature = "axum")]
+#[test]
+fn axum_connect_info() {
+    let validator = IpAllowList::new(github());
+    let peer = SocketAddr::from(([185, 199, 108, 153], 41000));
+    let req = TestRequest::post("/").extension(axum::extract::ConnectInfo(peer));
+    assert!(validate_sync(&validator, req).is_ok());
+}
@@ -0,0 +1,564 @@
+//! [`KeySource`] abstraction over where the verification keys come from.
+//!
+//! The keys are published as JWKS documents, and rotate. The [`StaticKeys`] are fixed, the
+//! [`FileKeys`] follow a local file, and the [`CachedKeys`] fetch the JWKS with a pluggable
+//! [`Fetch`] implementation and cache it.
+
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant, SystemTime},
+};
+
+use jsonwebtoken::{
+    jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet, KeyAlgorithm, PublicKeyUse},
+    Algorithm, DecodingKey,
+};
+
+/// The source of the verification keys.
+pub trait KeySource {
+    /// An error that can occur while loading the keys.
+    type Error;
+
+    /// The keys, loading them if they are not loaded yet or are stale.
+    fn keys(&self) -> impl core::future::Future<Output = Result<Arc<Keys>, Self::Error>> + Send;
+
+    /// Reload the keys, because a token references a key that is not known.
+    ///
+    /// The implementations limit the rate of the reloads, returning the current keys if
+    /// the last reload was too recent. Returns the [`KeySource::keys`] by default.
+    fn refresh(&self) -> impl core::future::Future<Output = Result<Arc<Keys>, Self::Error>> + Send {
+        self.keys()
+    }
+
+    /// The currently loaded keys, without loading them.
+    ///
+    /// Used where waiting for the keys is not possible.
+    fn current(&self) -> Option<Arc<Keys>>;
+}
+
+impl<T: ?Sized> KeySource for T
+where
+    T: core::ops::Deref + Send + Sync,
+    <T as core::ops::Deref>::Target: KeySource + Send + Sync,
+{
+    type Error = <<T as core::ops::Deref>::Target as KeySource>::Error;
+
+    fn keys(&self) -> impl core::future::Future<Output = Result<Arc<Keys>, Self::Error>> + Send {
+        KeySource::keys(&**self)
+    }
+
+    fn refresh(&self) -> impl core::future::Future<Output = Result<Arc<Keys>, Self::Error>> + Send {
+        KeySource::refresh(&**self)
+    }
+
+    fn current(&self) -> Option<Arc<Keys>> {
+        KeySource::current(&**self)
+    }
+}
+
+/// The set of the verification keys, parsed from a JWKS.
+#[derive(Clone)]
+pub struct Keys {
+    /// The keys.
+    keys: Vec<Key>,
+}
+
+/// A key from the JWKS.
+#[derive(Clone)]
+pub(crate) struct Key {
+    /// The key ID.
+    pub(crate) id: Option<String>,
+
+    /// The only algorithm the key is used with.
+    pub(crate) algorithm: Algorithm,
+
+    /// The key itself.
+    pub(crate) key: DecodingKey,
+}
+
+impl Keys {
+    /// Parse the keys from the JWKS.
+    ///
+    /// The encryption keys, the keys for the unsupported algorithms and the keys that can not be
+    /// parsed are skipped, so one bad key does not take the rest of the set down. The skipped
+    /// unparsable keys are logged with the `tracing` feature.
+    pub fn from_jwks(jwks: &JwkSet) -> Result<Self, InvalidJwks> {
+        let mut keys = Vec::new();
+        for jwk in &jwks.keys {
+            let Some(algorithm) = algorithm(jwk) else {
+                continue;
+            };
+            let key = match DecodingKey::from_jwk(jwk) {
+                Ok(key) => key,
+                Err(error) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(
+                        kid = jwk.common.key_id.as_deref(),
+                        %error,
+                        "skipping the JWK that can not be parsed",
+                    );
+                    #[cfg(not(feature = "tracing"))]
+                    let _ = error;
+                    continue;
+                }
+            };
+            keys.push(Key {
+                id: jwk.common.key_id.clone(),
+                algorithm,
+                key,
+            });
+        }
+        if keys.is_empty() {
+            return Err(InvalidJwks::new("no supported signature keys"));
+        }
+        Ok(Self { keys })
+    }
+
+    /// Parse the keys from the JWKS in the JSON form.
+    pub fn from_jwks_json(jwks: &[u8]) -> Result<Self, InvalidJwks> {
+        let jwks = serde_json::from_slice(jwks)
+            .map_err(|error| InvalidJwks::new(format!("invalid JSON: {error}")))?;
+        Self::from_jwks(&jwks)
+    }
+
+    /// The keys for the algorithm, with the given ID, if any.
+    pub(crate) fn matching<'a>(
+        &'a self,
+        algorithm: Algorithm,
+        id: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a Key> {
+        self.keys.iter().filter(move |key| {
+            key.algorithm == algorithm && (id.is_none() || key.id.as_deref() == id)
+        })
+    }
+}
+
+impl core::fmt::Debug for Keys {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list()
+            .entries(self.keys.iter().map(|key| (&key.id, key.algorithm)))
+            .finish()
+    }
+}
+
+/// The algorithm the key is used with, if the key is supported.
+fn algorithm(jwk: &Jwk) -> Option<Algorithm> {
+    if matches!(
+        jwk.common.public_key_use,
+        Some(PublicKeyUse::Encryption | PublicKeyUse::Other(_))
+    ) {
+        return None;
+    }
+
+    let (algorithm, declared) = match &jwk.algorithm {
+        AlgorithmParameters::RSA(_) => (Algorithm::RS256, KeyAlgorithm::RS256),
+        AlgorithmParameters::EllipticCurve(params) if params.curve == EllipticCurve::P256 => {
+            (Algorithm::ES256, KeyAlgorithm::ES256)
+        }
+        AlgorithmParameters::OctetKeyPair(params) if params.curve == EllipticCurve::Ed25519 => {
+            (Algorithm::EdDSA, KeyAlgorithm::EdDSA)
+        }
+        AlgorithmParameters::OctetKey(_) => (Algorithm::HS256, KeyAlgorithm::HS256),
+        AlgorithmParameters::EllipticCurve(_) | AlgorithmParameters::OctetKeyPair(_) => {
+            return None
+        }
+    };
+    match jwk.common.key_algorithm {
+        Some(key_algorithm) if key_algorithm != declared => None,
+        _ => Some(algorithm),
+    }
+}
+
+/// The JWKS can not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidJwks {
+    /// The description of the problem.
+    pub message: String,
+}
+
+impl InvalidJwks {
+    /// Create a new [`InvalidJwks`] with the given description.
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+impl core::fmt::Display for InvalidJwks {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid JWKS: {}", self.message)
+    }
+}
+
+impl std::error::Error for InvalidJwks {}
+
+/// An error that can occur while loading the keys.
+#[derive(Debug)]
+pub enum LoadError<E> {
+    /// The JWKS can not be read or fetched.
+    Fetch(E),
+    /// The JWKS can not be parsed.
+    Jwks(InvalidJwks),
+    /// The keys are not loaded, and the reload is not allowed yet.
+    NotLoaded,
+}
+
+impl<E: core::fmt::Display> core::fmt::Display for LoadError<E> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Fetch(error) => write!(f, "unable to load the JWKS: {error}"),
+            Self::Jwks(error) => error.fmt(f),
+            Self::NotLoaded => f.write_str("the keys are not loaded yet"),
+        }
+    }
+}
+
+impl<E> std::error::Error for LoadError<E>
+where
+    E: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Fetch(error) => Some(error),
+            Self::Jwks(error) => Some(error),
+            Self::NotLoaded => None,
+        }
+    }
+}
+
+/// The fixed keys.
+#[derive(Debug, Clone)]
+pub struct StaticKeys(pub Arc<Keys>);
+
+impl StaticKeys {
+    /// Create a new [`StaticKeys`].
+    pub fn new(keys: Keys) -> Self {
+        Self(Arc::new(keys))
+    }
+}
+
+impl KeySource for StaticKeys {
+    type Error = core::convert::Infallible;
+
+    fn keys(&self) -> impl core::future::Future<Output = Result<Arc<Keys>, Self::Error>> + Send {
+        core::future::ready(Ok(Arc::clone(&self.0)))
+    }
+
+    fn current(&self) -> Option<Arc<Keys>> {
+        Some(Arc::clone(&self.0))
+    }
+}
+
+/// Lock the mutex, ignoring the poisoning, as the state is always consistent.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The keys from a local JWKS file, reloaded when the file changes.
+///
+/// The modification time of the file is checked at most once per the check interval, and
+/// the file is reloaded if it has changed. If the changed file can not be read or parsed,
+/// the previous keys are kept, and the reload is retried on the next check.
+///
+/// The file is checked and read on the tokio blocking thread pool, so the keys have to be
+/// requested from within the tokio runtime; [`FileKeys::new`] reads the file right away.
+#[derive(Debug)]
+pub struct FileKeys {
+    /// The path to the JWKS file.
+    path: PathBuf,
+
+    /// The minimum interval between the checks of the file.
+    check_interval: Duration,
+
+    /// The loaded keys and the bookkeeping.
+    state: Mutex<FileState>,
+}
+
+/// The state of the [`FileKeys`].
+#[derive(Debug)]
+struct FileState {
+    /// The loaded keys.
+    keys: Arc<Keys>,
+
+    /// The modification time of the loaded file.
+    modified: Option<SystemTime>,
+
+    /// When the file was last checked.
+    checked_at: Instant,
+}
+
+impl FileKeys {
+    /// The default minimum interval between the checks of the file.
+    pub const DEFAULT_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+    /// Load the keys from the JWKS file into a new [`FileKeys`].
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self, LoadError<std::io::Error>> {
+        let path = path.into();
+        let (keys, modified) = Self::load(&path)?;
+        Ok(Self {
+            path,
+            check_interval: Self::DEFAULT_CHECK_INTERVAL,
+            state: Mutex::new(FileState {
+                keys: Arc::new(keys),
+                modified,
+                checked_at: Instant::now(),
+            }),
+        })
+    }
+
+    /// Set the minimum interval between the checks of the file.
+    pub const fn with_check_interval(mut self, check_interval: Duration) -> Self {
+        self.check_interval = check_interval;
+        self
+    }
+
+    /// Read and parse the file, returning the keys and the modification time.
+    fn load(
+        path: &std::path::Path,
+    ) -> Result<(Keys, Option<SystemTime>), LoadError<std::io::Error>> {
+        let modified = std::fs::metadata(path)
+            .map_err(LoadError::Fetch)?
+            .modified()
+            .ok();
+        let jwks = std::fs::read(path).map_err(LoadError::Fetch)?;
+        let keys = Keys::from_jwks_json(&jwks).map_err(LoadError::Jwks)?;
+        Ok((keys, modified))
+    }
+
+    /// Read and parse the file if its modification time differs from the given one.
+    fn load_if_changed(
+        path: &std::path::Path,
+        loaded: Option<SystemTime>,
+    ) -> Option<(Keys, Option<SystemTime>)> {
+        let modified = std::fs::metadata(path)
+            .ok()
+            .and_then(|metadata| metadata.modified().ok());
+        if modified.is_some() && modified == loaded {
+            return None;
+        }
+        Self::load(path).ok()
+    }
+
+    /// Reload the keys if the file has changed, and it is time to check.
+    async fn reload(&self) -> Arc<Keys> {
+        let loaded = {
+            let mut state = lock(&self.state);
+            if state.checked_at.elapsed() < self.check_interval {
+                return Arc::clone(&state.keys);
+            }
+            state.checked_at = Instant::now();
+            state.modified
+        };
+
+        let path = self.path.clone();
+        let reloaded = tokio::task::spawn_blocking(move || Self::load_if_changed(&path, loaded))
+            .await
+            .ok()
+            .flatten();
+
+        let mut state = lock(&self.state);
+        if let Some((keys, modified)) = reloaded {
+            state.keys = Arc::new(keys);
+            state.modified = modified;
+        }
+        Arc::clone(&state.keys)
+    }
+}
+
+impl KeySource for FileKeys {
+    type Error = core::convert::Infallible;
+
+    async fn keys(&self) -> Result<Arc<Keys>, Self::Error> {
+        Ok(self.reload().await)
+    }
+
+    fn current(&self) -> Option<Arc<Keys>> {
+        Some(Arc::clone(&lock(&self.state).keys))
+    }
+}
+
+/// The fetcher of the JWKS document.
+pub trait Fetch {
+    /// An error that can occur while fetching.
+    type Error;
+
+    /// Fetch the JWKS document in the JSON form.
+    fn fetch(&self)
+        -> impl core::future::Future<Output = Result<bytes::Bytes, Self::Error>> + Send;
+}
+
+/// The [`Fetch`] implementation that gets the JWKS over HTTP with [`reqwest`].
+#[cfg(feature = "reqwest")]
+#[derive(Debug, Clone)]
+pub struct ReqwestFetcher {
+    /// The HTTP client.
+    pub client: reqwest::Client,
+
+    /// The URL of the JWKS document.
+    pub url: reqwest::Url,
+}
+
+#[cfg(feature = "reqwest")]
+impl ReqwestFetcher {
+    /// Create a new [`ReqwestFetcher`] with a default client.
+    pub fn new(url: reqwest::Url) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url,
+        }
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl Fetch for ReqwestFetcher {
+    type Error = reqwest::Error;
+
+    fn fetch(
+        &self,
+    ) -> impl core::future::Future<Output = Result<bytes::Bytes, Self::Error>> + Send {
+        let request = self.client.get(self.url.clone());
+        async move { request.send().await?.error_for_status()?.bytes().await }
+    }
+}
+
+/// The keys fetched with the [`Fetch`] implementation and cached.
+///
+/// The keys are fetched on the first use, and refetched when they get older than the maximum
+/// age, or when a token references an unknown key. The fetches are serialized, and happen at
+/// most once per the minimum refresh interval, so the tokens with the made up key IDs can not
+/// flood the JWKS server. If a fetch fails, the previously fetched keys are kept.
+#[derive(Debug)]
+pub struct CachedKeys<Fetcher> {
+    /// The fetcher of the JWKS.
+    fetcher: Fetcher,
+
+    /// The age after which the keys are refetched.
+    max_age: Duration,
+
+    /// The minimum interval between the fetches.
+    min_refresh_interval: Duration,
+
+    /// The fetched keys and the bookkeeping.
+    state: Mutex<CacheState>,
+
+    /// Held while fetching, to serialize the fetches.
+    fetching: tokio::sync::Mutex<()>,
+}
+
+/// The state of the [`CachedKeys`].
+#[derive(Debug, Default)]
+struct CacheState {
+    /// The fetched keys.
+    keys: Option<Arc<Keys>>,
+
+    /// When the keys were fetched.
+    fetched_at: Option<Instant>,
+
+    /// When the last fetch was started.
+    attempted_at: Option<Instant>,
+}
+
+impl<Fetcher> CachedKeys<Fetcher> {
+    /// The default age after which the keys are refetched.
+    pub const DEFAULT_MAX_AGE: Duration = Duration::from_secs(15 * 60);
+
+    /// The default minimum interval between the fetches.
+    pub const DEFAULT_MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+
+    /// Create a new [`CachedKeys`] with the given fetcher.
+    ///
+    /// Nothing is fetched until the keys are needed.
+    pub fn new(fetcher: Fetcher) -> Self {
+        Self {
+            fetcher,
+            max_age: Self::DEFAULT_MAX_AGE,
+            min_refresh_interval: Self::DEFAULT_MIN_REFRESH_INTERVAL,
+            state: Mutex::default(),
+            fetching: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Set the age after which the keys are refetched.
+    pub const fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Set the minimum interval between the fetches.
+    pub const fn with_min_refresh_interval(mut self, min_refresh_interval: Duration) -> Self {
+        self.min_refresh_interval = min_refresh_interval;
+        self
+    }
+
+    /// The fetcher of the JWKS.
+    pub const fn fetcher(&self) -> &Fetcher {
+        &self.fetcher
+    }
+}
+
+impl<Fetcher: Fetch + Sync> CachedKeys<Fetcher> {
+    /// Return the cached keys if they are fresh, or fetch them, if allowed.
+    async fn load(&self, force: bool) -> Result<Arc<Keys>, LoadError<Fetcher::Error>> {
+        let observed = {
+            let state = lock(&self.state);
+            let fresh = state
+                .fetched_at
+                .is_some_and(|fetched_at| fetched_at.elapsed() < self.max_age);
+            if let (Some(keys), true, false) = (&state.keys, fresh, force) {
+                return Ok(Arc::clone(keys));
+            }
+            state.attempted_at
+        };
+
+        let _fetching = self.fetching.lock().await;
+        {
+            let mut state = lock(&self.state);
+            // Somebody else has fetched while we were waiting, or it is too early to refetch.
+            let too_early = state
+                .attempted_at
+                .is_some_and(|attempted_at| attempted_at.elapsed() < self.min_refresh_interval);
+            if state.attempted_at != observed || too_early {
+                return state.keys.clone().ok_or(LoadError::NotLoaded);
+            }
+            state.attempted_at = Some(Instant::now());
+        }
+
+        let result = match self.fetcher.fetch().await {
+            Ok(jwks) => Keys::from_jwks_json(&jwks).map_err(LoadError::Jwks),
+            Err(error) => Err(LoadError::Fetch(error)),
+        };
+
+        let mut state = lock(&self.state);
+        match result {
+            Ok(keys) => {
+                let keys = Arc::new(keys);
+                state.keys = Some(Arc::clone(&keys));
+                state.fetched_at = Some(Instant::now());
+                Ok(keys)
+            }
+            Err(error) => state.keys.clone().ok_or(error),
+        }
+    }
+}
+
+impl<Fetcher> KeySource for CachedKeys<Fetcher>
+where
+    Fetcher: Fetch + Sync,
+    Fetcher::Error: Send,
+{
+    type Error = LoadError<Fetcher::Error>;
+
+    fn keys(&self) -> impl core::future::Future<Output = Result<Arc<Keys>, Self::Error>> + Send {
+        self.load(false)
+    }
+
+    fn refresh(&self) -> impl core::future::Future<Output = Result<Arc<Keys>, Self::Error>> + Send {
+        self.load(true)
+    }
+
+    fn current(&self) -> Option<Arc<Keys>> {
+        lock(&self.state).keys.clone()
+    }
+}
@@ -0,0 +1,424 @@
+//! JWT bearer token validator for the [`http_request_validator`].
+//!
+//! Verifies the tokens against the keys from JWKS documents, see [`key_source`] for where
+//! the keys can come from. Supports the `RS256`, `ES256`, `EdDSA` and `HS256` algorithms.
+
+use std::sync::{Arc, OnceLock};
+
+use jsonwebtoken::jwk::JwkSet;
+use sha2::Digest as _;
+
+pub mod key_source;
+
+pub use self::key_source::{InvalidJwks, KeySource, Keys, StaticKeys};
+pub use jsonwebtoken;
+
+/// The validator that authenticates the requests with a JWT bearer token.
+///
+/// Takes the token from the `Authorization: Bearer` header, or from a configurable header,
+/// verifies its signature with a key from the JWKS, and checks the `exp`, `nbf`, and,
+/// if configured, the `iss` and `aud` claims. Optionally checks that a claim holds the
+/// hex-encoded SHA-256 of the buffered body.
+///
+/// The keys come from a [`KeySource`], the [`StaticKeys`] by default. The key is picked by
+/// the `kid` of the token, or, if there is none, every key is tried. The token `alg` has to
+/// match the algorithm of the key, so a key can not be used with an algorithm it is not meant
+/// for. If no key matches, the key source is asked to refresh the keys, and the token is
+/// checked once more.
+///
+/// The requests without a token, or with a token that can not be decoded, are rejected in
+/// [`http_request_validator::Validator::pre_validate`] already, before the body is buffered.
+/// The signature is verified once, in the validation proper, and the [`VerifiedToken`] it
+/// produced is made available to the downstream handlers through the [`VerifiedTokenSlot`]
+/// that the pre-validation inserts into the request extensions.
+///
+/// ## Examples
+///
+/// ```
+/// use jwt_request_validator::{jsonwebtoken, JwtValidator};
+///
+/// let jwks = br#"{"keys":[{"kty":"oct","kid":"1","k":"c2VjcmV0"}]}"#;
+/// let validator = JwtValidator::from_jwks_json(jwks)
+///     .unwrap()
+///     .with_issuer(&["https://issuer.example"])
+///     .with_body_hash_claim("request_body_sha256");
+///
+/// let body = bytes::Bytes::from_static(b"{}");
+/// let claims = serde_json::json!({
+///     "iss": "https://issuer.example",
+///     "exp": 4_000_000_000_u64,
+///     "request_body_sha256":
+///         "44136fa355b3678a1146ad16f7e8649e94fb4fc21fe77e8310c060f61caaff8a",
+/// });
+/// let mut header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::HS256);
+/// header.kid = Some("1".into());
+/// let key = jsonwebtoken::EncodingKey::from_secret(b"secret");
+/// let token = jsonwebtoken::encode(&header, &claims, &key).unwrap();
+///
+/// let req = http::Request::post("/")
+///     .header("authorization", format!("Bearer {token}"))
+///     .body(body)
+///     .unwrap();
+/// # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+/// assert!(http_request_validator_test::validate(&validator, req).await.is_ok());
+/// # });
+/// ```
+#[derive(Debug, Clone)]
+pub struct JwtValidator<Source = StaticKeys> {
+    /// The source of the keys to verify the tokens with.
+    source: Source,
+
+    /// The header to take the token from.
+    header: http::HeaderName,
+
+    /// The allowed issuers, if restricted.
+    issuers: Option<Vec<String>>,
+
+    /// The allowed audiences, if restricted.
+    audiences: Option<Vec<String>>,
+
+    /// The allowed clock skew, in seconds.
+    leeway: u64,
+
+    /// The claim holding the hex-encoded SHA-256 of the body, if checked.
+    body_hash_claim: Option<String>,
+}
+
+impl JwtValidator {
+    /// Create a new [`JwtValidator`] with the keys from the JWKS.
+    ///
+    /// The encryption keys and the keys for the unsupported algorithms are skipped.
+    pub fn new(jwks: &JwkSet) -> Result<Self, InvalidJwks> {
+        let keys = Keys::from_jwks(jwks)?;
+        Ok(Self::from_key_source(StaticKeys::new(keys)))
+    }
+
+    /// Create a new [`JwtValidator`] with the keys from the JWKS in the JSON form.
+    pub fn from_jwks_json(jwks: &[u8]) -> Result<Self, InvalidJwks> {
+        let keys = Keys::from_jwks_json(jwks)?;
+        Ok(Self::from_key_source(StaticKeys::new(keys)))
+    }
+}
+
+impl<Source> JwtValidator<Source> {
+    /// The default allowed clock skew, in seconds.
+    pub const DEFAULT_LEEWAY: u64 = 60;
+
+    /// Create a new [`JwtValidator`] with the keys from the given source.
+    pub fn from_key_source(source: Source) -> Self {
+        Self {
+            source,
+            header: http::header::AUTHORIZATION,
+            issuers: None,
+            audiences: None,
+            leeway: Self::DEFAULT_LEEWAY,
+            body_hash_claim: None,
+        }
+    }
+
+    /// Take the token from the given header instead of the `Authorization`.
+    ///
+    /// The header holds the token as is, or prefixed with `Bearer`.
+    pub fn with_header(mut self, header: http::HeaderName) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Require the `iss` claim to be one of the given issuers.
+    pub fn with_issuer(mut self, issuers: &[&str]) -> Self {
+        self.issuers = Some(issuers.iter().map(|&issuer| issuer.into()).collect());
+        self
+    }
+
+    /// Require the `aud` claim to contain one of the given audiences.
+    pub fn with_audience(mut self, audiences: &[&str]) -> Self {
+        self.audiences = Some(audiences.iter().map(|&audience| audience.into()).collect());
+        self
+    }
+
+    /// Set the allowed clock skew for the `exp` and `nbf` checks, in seconds.
+    pub const fn with_leeway(mut self, leeway: u64) -> Self {
+        self.leeway = leeway;
+        self
+    }
+
+    /// Require the given claim to hold the hex-encoded SHA-256 of the body.
+    pub fn with_body_hash_claim(mut self, claim: &str) -> Self {
+        self.body_hash_claim = Some(claim.into());
+        self
+    }
+
+    /// Verify the token with the given keys.
+    fn verify_with<E>(
+        &self,
+        keys: &Keys,
+        token: &str,
+        header: &jsonwebtoken::Header,
+    ) -> Result<VerifiedToken, Error<E>> {
+        let mut validation = jsonwebtoken::Validation::new(header.alg);
+        validation.leeway = self.leeway;
+        validation.validate_nbf = true;
+        validation.validate_aud = self.audiences.is_some();
+        if let Some(issuers) = &self.issuers {
+            validation.set_issuer(issuers);
+        }
+        if let Some(audiences) = &self.audiences {
+            validation.set_audience(audiences);
+        }
+
+        // Without the `kid`, try all the keys for the algorithm.
+        let mut result = Err(Error::UnknownKey);
+        for key in keys.matching(header.alg, header.kid.as_deref()) {
+            result = jsonwebtoken::decode(token, &key.key, &validation).map_err(Error::Invalid);
+            if result.is_ok() {
+                break;
+            }
+        }
+        let data = result?;
+        Ok(VerifiedToken {
+            header: data.header,
+            claims: data.claims,
+        })
+    }
+
+    /// Take the token and its decoded header from the request head.
+    fn token<'a, E>(
+        &self,
+        headers: &'a http::HeaderMap,
+    ) -> Result<(&'a str, jsonwebtoken::Header), Error<E>> {
+        let value = headers
+            .get(&self.header)
+            .and_then(|value| value.to_str().ok())
+            .ok_or(Error::MissingToken)?
+            .trim();
+        let token = match value.split_once(' ') {
+            Some((scheme, token)) if scheme.eq_ignore_ascii_case("bearer") => token.trim_start(),
+            Some(_) => return Err(Error::MissingToken),
+            None if self.header == http::header::AUTHORIZATION => return Err(Error::MissingToken),
+            None => value,
+        };
+        if token.is_empty() {
+            return Err(Error::MissingToken);
+        }
+        let header = jsonwebtoken::decode_header(token).map_err(Error::Invalid)?;
+        Ok((token, header))
+    }
+
+    /// Check the token in the request head without verifying it, and insert an empty
+    /// [`VerifiedTokenSlot`] for the validation to fill.
+    fn pre_check<E>(&self, parts: &mut http::request::Parts) -> Result<(), Error<E>> {
+        self.token::<E>(&parts.headers)?;
+        // Replaces the slot inserted by anyone else, so only this validation can fill it.
+        parts.extensions.insert(VerifiedTokenSlot::default());
+        Ok(())
+    }
+
+    /// Check the body hash claim of the verified token, and put the token into the
+    /// [`VerifiedTokenSlot`], if any.
+    fn accept<E>(
+        &self,
+        parts: &http::request::Parts,
+        token: VerifiedToken,
+        body: &[u8],
+    ) -> Result<(), Error<E>> {
+        self.check_body_hash(&token, body)?;
+        if let Some(slot) = parts.extensions.get::<VerifiedTokenSlot>() {
+            let _ = slot.0.set(token);
+        }
+        Ok(())
+    }
+
+    /// Check the body hash claim, if configured.
+    fn check_body_hash<E>(&self, token: &VerifiedToken, body: &[u8]) -> Result<(), Error<E>> {
+        let Some(claim) = &self.body_hash_claim else {
+            return Ok(());
+        };
+        let hash = token
+            .claims
+            .get(claim)
+            .and_then(serde_json::Value::as_str)
+            .ok_or(Error::MissingBodyHash)?;
+        if hex::decode(hash).ok().as_deref() != Some(&sha2::Sha256::digest(body)[..]) {
+            return Err(Error::BodyHashMismatch);
+        }
+        Ok(())
+    }
+}
+
+impl<Source: KeySource> JwtValidator<Source> {
+    /// Verify the token in the request head, without checking the body hash.
+    pub async fn verify(
+        &self,
+        parts: &http::request::Parts,
+    ) -> Result<VerifiedToken, Error<Source::Error>> {
+        let (token, header) = self.token(&parts.headers)?;
+
+        let keys = self.source.keys().await.map_err(Error::KeySource)?;
+        match self.verify_with(&keys, token, &header) {
+            Err(Error::UnknownKey) => {
+                let keys = self.source.refresh().await.map_err(Error::KeySource)?;
+                self.verify_with(&keys, token, &header)
+            }
+            result => result,
+        }
+    }
+
+    /// Verify the token in the request head with the keys the source has at hand, without
+    /// loading them, and without checking the body hash.
+    pub fn verify_current(
+        &self,
+        parts: &http::request::Parts,
+    ) -> Result<VerifiedToken, Error<Source::Error>> {
+        let (token, header) = self.token(&parts.headers)?;
+        let keys = self.source.current().ok_or(Error::UnknownKey)?;
+        self.verify_with(&keys, token, &header)
+    }
+}
+
+/// The verified token.
+///
+/// Available to the downstream handlers through the [`VerifiedTokenSlot`], see
+/// [`VerifiedToken::from_extensions`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerifiedToken {
+    /// The token header.
+    pub header: jsonwebtoken::Header,
+
+    /// The token claims.
+    pub claims: serde_json::Map<String, serde_json::Value>,
+}
+
+impl VerifiedToken {
+    /// The token verified by the [`JwtValidator`], from the request extensions.
+    pub fn from_extensions(extensions: &http::Extensions) -> Option<&Self> {
+        extensions.get::<VerifiedTokenSlot>()?.get()
+    }
+}
+
+/// The place in the request extensions for the [`VerifiedToken`].
+///
+/// Inserted empty by the [`JwtValidator`] pre-validation, and filled by the validation once the
+/// request passes it.
+#[derive(Debug, Clone, Default)]
+pub struct VerifiedTokenSlot(Arc<OnceLock<VerifiedToken>>);
+
+impl VerifiedTokenSlot {
+    /// The verified token, if the request has passed the validation.
+    pub fn get(&self) -> Option<&VerifiedToken> {
+        self.0.get()
+    }
+}
+
+/// An error that can occur while validating the token.
+#[derive(Debug)]
+pub enum Error<KeySourceError = core::convert::Infallible> {
+    /// The token is missing.
+    MissingToken,
+    /// No key matches the `kid` and `alg` of the token.
+    UnknownKey,
+    /// The token is malformed, its signature does not match, or the claims are not valid.
+    Invalid(jsonwebtoken::errors::Error),
+    /// The body hash claim is missing.
+    MissingBodyHash,
+    /// The body hash claim does not match the body.
+    BodyHashMismatch,
+    /// The keys can not be loaded.
+    KeySource(KeySourceError),
+}
+
+impl<KeySourceError: core::fmt::Display> core::fmt::Display for Error<KeySourceError> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingToken => f.write_str("missing bearer token"),
+            Self::UnknownKey => f.write_str("no key matches the token"),
+            Self::Invalid(error) => write!(f, "invalid token: {error}"),
+            Self::MissingBodyHash => f.write_str("missing body hash claim"),
+            Self::BodyHashMismatch => f.write_str("body hash mismatch"),
+            Self::KeySource(error) => write!(f, "unable to load the keys: {error}"),
+        }
+    }
+}
+
+impl<KeySourceError> std::error::Error for Error<KeySourceError>
+where
+    KeySourceError: std::error::Error + 'static,
+{
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Invalid(error) => Some(error),
+            Self::KeySource(error) => Some(error),
+            Self::MissingToken
+            | Self::UnknownKey
+            | Self::MissingBodyHash
+            | Self::BodyHashMismatch => None,
+        }
+    }
+}
+
+impl<KeySourceError> http_request_validator::ValidationErrorKind for Error<KeySourceError> {
+    fn kind(&self) -> http_request_validator::ErrorKind {
+        use http_request_validator::ErrorKind;
+        use jsonwebtoken::errors::ErrorKind as JwtErrorKind;
+
+        match self {
+            Self::MissingToken => ErrorKind::MissingCredentials,
+            Self::UnknownKey | Self::BodyHashMismatch => ErrorKind::BadSignature,
+            Self::MissingBodyHash => ErrorKind::Malformed,
+            Self::KeySource(_) => ErrorKind::Unavailable,
+            Self::Invalid(error) => match error.kind() {
+                JwtErrorKind::InvalidSignature | JwtErrorKind::InvalidAlgorithm => {
+                    ErrorKind::BadSignature
+                }
+                JwtErrorKind::ExpiredSignature | JwtErrorKind::ImmatureSignature => {
+                    ErrorKind::Expired
+                }
+                JwtErrorKind::InvalidIssuer
+                | JwtErrorKind::InvalidAudience
+                | JwtErrorKind::InvalidSubject => ErrorKind::Forbidden,
+                _ => ErrorKind::Malformed,
+            },
+        }
+    }
+
+    fn www_authenticate(&self) -> Option<http::HeaderValue> {
+        use http_request_validator::ErrorKind;
+
+        // The challenge of RFC 6750 goes with `401 Unauthorized` only; the token is not at
+        // fault with the other statuses.
+        match self.kind() {
+            ErrorKind::MissingCredentials => Some(http::HeaderValue::from_static("Bearer")),
+            kind if kind.status_code() == http::StatusCode::UNAUTHORIZED => Some(
+                http::HeaderValue::from_static("Bearer error=\"invalid_token\""),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl<Source, Data> http_request_validator::Validator<Data> for JwtValidator<Source>
+where
+    Source: KeySource + Sync,
+    Source::Error: Send,
+    Data: bytes::Buf + AsRef<[u8]> + Sync,
+{
+    type Error = Error<Source::Error>;
+
+    fn validate<'a>(
+        &'a self,
+        parts: &'a http::request::Parts,
+        buffered_body: &'a Data,
+    ) -> impl core::future::Future<Output = Result<(), Self::Error>> + Send + 'a {
+        let verification = self.verify(parts);
+        async move {
+            let token = verification.await?;
+            self.accept(parts, token, buffered_body.as_ref())
+        }
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        self.pre_check(parts)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,257 @@
+//! Client certificate validator for the [`http_request_validator`].
+//!
+//! Authenticates the callers by the certificate they presented over mutual TLS. The TLS
+//! handshake itself, and the verification of the chain against the trusted roots, is up to
+//! whatever terminates TLS; it exposes the chain to the validators as the
+//! [`PeerCertificates`] in the request extensions, and the [`ClientCertValidator`] checks
+//! the leaf certificate against an allow-list.
+
+use sha2::Digest as _;
+use x509_parser::{certificate::X509Certificate, extensions::GeneralName, prelude::FromDer as _};
+
+pub use http_request_validator::tls::PeerCertificates;
+
+/// The validator that authenticates the requests with the client certificate.
+///
+/// The leaf certificate of the [`PeerCertificates`] is allowed if its SHA-256 fingerprint,
+/// its subject, or any of its subject alternative names are in the allow-list. Nothing is
+/// allowed until the allow-list is populated.
+///
+/// The validator does not look at the body, so it is combined with the body signature
+/// validators, like any other [`http_request_validator::SyncValidator`], to require both
+/// a known caller and a signed payload.
+///
+/// The certificate is checked in [`http_request_validator::SyncValidator::pre_validate`] too, so
+/// the unknown callers are rejected before their bodies are buffered, and the
+/// [`ClientCertificate`] of the allowed ones is inserted into the request extensions for the
+/// downstream handlers. The validation proper checks the certificate again, so the extensions
+/// are never trusted.
+///
+/// ## Examples
+///
+/// ```
+/// use http_request_validator::validate_blocking;
+/// use mtls_request_validator::{ClientCertValidator, SubjectAltName};
+///
+/// let validator = ClientCertValidator::new()
+///     .allow_fingerprint(
+///         "DB:85:5C:E1:B7:BA:44:D6:53:C3:5C:4A:DD:0A:CE:97:\
+///          FB:DF:D9:6B:DD:20:9B:79:8D:80:92:90:51:FA:42:7E",
+///     )
+///     .allow_subject("O=Example, CN=billing")
+///     .allow_subject_alt_name(SubjectAltName::Uri("spiffe://example.org/billing".into()));
+///
+/// // No client certificate on the connection.
+/// let req = http::Request::post("/").body(bytes::Bytes::new()).unwrap();
+/// assert!(validate_blocking(&validator, req).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientCertValidator {
+    /// The allowed SHA-256 fingerprints of the leaf certificate.
+    fingerprints: Vec<[u8; 32]>,
+
+    /// The allowed subjects.
+    subjects: Vec<String>,
+
+    /// The allowed subject alternative names.
+    subject_alt_names: Vec<SubjectAltName>,
+}
+
+impl ClientCertValidator {
+    /// Create a new [`ClientCertValidator`] that allows no certificates.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow the certificate with the given SHA-256 fingerprint of its DER.
+    ///
+    /// The fingerprint is hex-encoded, optionally with the bytes separated by colons, like
+    /// `openssl x509 -fingerprint -sha256` prints it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the fingerprint is not a hex-encoded SHA-256.
+    #[track_caller]
+    pub fn allow_fingerprint(mut self, fingerprint: &str) -> Self {
+        let mut decoded = [0; 32];
+        if hex::decode_to_slice(fingerprint.replace(':', ""), &mut decoded).is_err() {
+            panic!("invalid SHA-256 fingerprint: {fingerprint:?}");
+        }
+        self.fingerprints.push(decoded);
+        self
+    }
+
+    /// Allow the certificates with the given subject.
+    ///
+    /// The subject is compared as a string of the comma-separated attributes in the order
+    /// they appear in the certificate, like `O=Example, CN=billing`.
+    pub fn allow_subject(mut self, subject: &str) -> Self {
+        self.subjects.push(subject.into());
+        self
+    }
+
+    /// Allow the certificates with the given subject alternative name.
+    pub fn allow_subject_alt_name(mut self, name: SubjectAltName) -> Self {
+        self.subject_alt_names.push(name.normalized());
+        self
+    }
+
+    /// Check the leaf certificate of the chain.
+    pub fn check(
+        &self,
+        certificates: Option<&PeerCertificates>,
+    ) -> Result<ClientCertificate, Error> {
+        let der = certificates
+            .and_then(PeerCertificates::leaf)
+            .ok_or(Error::MissingCertificate)?;
+        let certificate = ClientCertificate::parse(der).ok_or(Error::InvalidCertificate)?;
+
+        let allowed = self.fingerprints.contains(&certificate.fingerprint)
+            || self.subjects.contains(&certificate.subject)
+            || certificate
+                .subject_alt_names
+                .iter()
+                .any(|name| self.subject_alt_names.contains(name));
+        if !allowed {
+            return Err(Error::NotAllowed);
+        }
+        Ok(certificate)
+    }
+}
+
+/// A subject alternative name of a certificate.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SubjectAltName {
+    /// A DNS name, compared case-insensitively.
+    Dns(String),
+    /// A URI, like a SPIFFE ID.
+    Uri(String),
+    /// An email address.
+    Email(String),
+    /// An IP address.
+    Ip(core::net::IpAddr),
+}
+
+impl SubjectAltName {
+    /// The name with the case-insensitive parts lowercased.
+    fn normalized(self) -> Self {
+        match self {
+            Self::Dns(name) => Self::Dns(name.to_ascii_lowercase()),
+            name => name,
+        }
+    }
+
+    /// Convert the general name from the certificate, if it is of a supported type.
+    fn from_general_name(name: &GeneralName<'_>) -> Option<Self> {
+        let name = match *name {
+            GeneralName::DNSName(name) => Self::Dns(name.into()),
+            GeneralName::URI(uri) => Self::Uri(uri.into()),
+            GeneralName::RFC822Name(email) => Self::Email(email.into()),
+            GeneralName::IPAddress(octets) => match octets.len() {
+                4 => Self::Ip(<[u8; 4]>::try_from(octets).ok()?.into()),
+                16 => Self::Ip(<[u8; 16]>::try_from(octets).ok()?.into()),
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(name.normalized())
+    }
+}
+
+/// The details of the allowed client certificate.
+///
+/// Inserted into the request extensions by the [`ClientCertValidator`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientCertificate {
+    /// The SHA-256 fingerprint of the DER.
+    pub fingerprint: [u8; 32],
+
+    /// The subject, as the comma-separated attributes.
+    pub subject: String,
+
+    /// The subject alternative names of the supported types.
+    pub subject_alt_names: Vec<SubjectAltName>,
+}
+
+impl ClientCertificate {
+    /// Parse the certificate details from the DER.
+    fn parse(der: &[u8]) -> Option<Self> {
+        let (rest, certificate) = X509Certificate::from_der(der).ok()?;
+        if !rest.is_empty() {
+            return None;
+        }
+        let subject_alt_names = match certificate.subject_alternative_name().ok()? {
+            Some(extension) => extension
+                .value
+                .general_names
+                .iter()
+                .filter_map(SubjectAltName::from_general_name)
+                .collect(),
+            None => Vec::new(),
+        };
+        Some(Self {
+            fingerprint: sha2::Sha256::digest(der).into(),
+            subject: certificate.subject().to_string(),
+            subject_alt_names,
+        })
+    }
+}
+
+/// An error that can occur while validating the client certificate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The client did not present a certificate.
+    MissingCertificate,
+    /// The leaf certificate can not be parsed.
+    InvalidCertificate,
+    /// The leaf certificate is not in the allow-list.
+    NotAllowed,
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::MissingCertificate => f.write_str("missing client certificate"),
+            Self::InvalidCertificate => f.write_str("invalid client certificate"),
+            Self::NotAllowed => f.write_str("client certificate is not allowed"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl http_request_validator::ValidationErrorKind for Error {
+    fn kind(&self) -> http_request_validator::ErrorKind {
+        use http_request_validator::ErrorKind;
+
+        match self {
+            Self::MissingCertificate => ErrorKind::MissingCredentials,
+            Self::InvalidCertificate => ErrorKind::Malformed,
+            Self::NotAllowed => ErrorKind::Forbidden,
+        }
+    }
+}
+
+impl<Data> http_request_validator::SyncValidator<Data> for ClientCertValidator
+where
+    Data: bytes::Buf,
+{
+    type Error = Error;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        _buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        self.check(PeerCertificates::of(parts)).map(drop)
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        let certificate = self.check(PeerCertificates::of(parts))?;
+        parts.extensions.insert(certificate);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
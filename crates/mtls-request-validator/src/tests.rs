@@ -0,0 +1,192 @@
+use http_request_validator::{ErrorKind, SyncValidator, ValidationErrorKind as _};
+use http_request_validator_test::{assert_invalid, assert_valid, validate_sync, TestRequest};
+
+use super::*;
+
+const BILLING_CERTIFICATE: &[u8] = b"-----BEGIN CERTIFICATE-----
+MIIBtzCCAWmgAwIBAgIUWHYNxYpMBEcNlOps33/RneiM2LwwBQYDK2VwMCQxEDAO
+BgNVBAoMB0V4YW1wbGUxEDAOBgNVBAMMB2JpbGxpbmcwIBcNMjYxMDE4MTk1MzU2
+WhgPMjEyNjA5MjQxOTUzNTZaMCQxEDAOBgNVBAoMB0V4YW1wbGUxEDAOBgNVBAMM
+B2JpbGxpbmcwKjAFBgMrZXADIQAVgBYoxQ4FT/st5WH9YlfPBhqwfNRWxnLNt7kO
+daZGwKOBqjCBpzAdBgNVHQ4EFgQUPvIzX6dFv1zOonyb7k7uJ6I2tFYwHwYDVR0j
+BBgwFoAUPvIzX6dFv1zOonyb7k7uJ6I2tFYwDwYDVR0TAQH/BAUwAwEB/zBUBgNV
+HREETTBLghBiaWxsaW5nLmludGVybmFshhxzcGlmZmU6Ly9leGFtcGxlLm9yZy9i
+aWxsaW5nhwQKAAAHgRNiaWxsaW5nQGV4YW1wbGUub3JnMAUGAytlcANBALhWX/RL
+47oxTwjmltXM6qbdiipORv6nJeyUnze5H07AEaKJRCiigdWJyMZUlTJuUzz9UMtW
+RRBp7zL0xehbLgc=
+-----END CERTIFICATE-----
+";
+
+const BILLING_FINGERPRINT: &str =
+    "db855ce1b7ba44d653c35c4add0ace97fbdfd96bdd209b798d80929051fa427e";
+
+const OTHER_CERTIFICATE: &[u8] = b"-----BEGIN CERTIFICATE-----
+MIIBrTCCAV+gAwIBAgIUB5Fq2TtTX8gsaB2RZhgx4qmhPhswBQYDK2VwMCIxEDAO
+BgNVBAoMB0V4YW1wbGUxDjAMBgNVBAMMBW90aGVyMCAXDTI2MTAxODE5NTM1NloY
+DzIxMjYwOTI0MTk1MzU2WjAiMRAwDgYDVQQKDAdFeGFtcGxlMQ4wDAYDVQQDDAVv
+dGhlcjAqMAUGAytlcAMhAKP7y8lLsgBiZmxCnjct7CkZ99oalGwRxYaE2UrNOZwf
+o4GkMIGhMB0GA1UdDgQWBBQsAV13js24dcQ7eecqQlRoXmkcODAfBgNVHSMEGDAW
+gBQsAV13js24dcQ7eecqQlRoXmkcODAPBgNVHRMBAf8EBTADAQH/ME4GA1UdEQRH
+MEWCDm90aGVyLmludGVybmFshhpzcGlmZmU6Ly9leGFtcGxlLm9yZy9vdGhlcocE
+CgAACIERb3RoZXJAZXhhbXBsZS5vcmcwBQYDK2VwA0EAJzvcBoriiqJOsiAILVtt
+DyQ69lhDf/RvOqHwP7BTLXG0gzyc75Aouhfjx5jAuhnkmcwAY0Q6TzoVW2uOVmpF
+CA==
+-----END CERTIFICATE-----
+";
+
+fn der(pem: &[u8]) -> bytes::Bytes {
+    let (_, pem) = x509_parser::pem::parse_x509_pem(pem).unwrap();
+    pem.contents.into()
+}
+
+fn request(chain: &[&[u8]]) -> TestRequest {
+    let chain = chain.iter().map(|&pem| der(pem)).collect();
+    TestRequest::post("/").extension(PeerCertificates(chain))
+}
+
+#[test]
+fn allowed_certificates() {
+    for validator in [
+        ClientCertValidator::new().allow_fingerprint(BILLING_FINGERPRINT),
+        ClientCertValidator::new().allow_fingerprint(
+            "DB:85:5C:E1:B7:BA:44:D6:53:C3:5C:4A:DD:0A:CE:97:\
+             FB:DF:D9:6B:DD:20:9B:79:8D:80:92:90:51:FA:42:7E",
+        ),
+        ClientCertValidator::new().allow_subject("O=Example, CN=billing"),
+        ClientCertValidator::new()
+            .allow_subject_alt_name(SubjectAltName::Dns("Billing.Internal".into())),
+        ClientCertValidator::new()
+            .allow_subject_alt_name(SubjectAltName::Uri("spiffe://example.org/billing".into())),
+        ClientCertValidator::new()
+            .allow_subject_alt_name(SubjectAltName::Email("billing@example.org".into())),
+        ClientCertValidator::new().allow_subject_alt_name(SubjectAltName::Ip([10, 0, 0, 7].into())),
+    ] {
+        let req = assert_valid(validate_sync(&validator, request(&[BILLING_CERTIFICATE])));
+        let certificate = req.extensions().get::<ClientCertificate>().unwrap();
+        assert_eq!(hex::encode(certificate.fingerprint), BILLING_FINGERPRINT);
+        assert_eq!(certificate.subject, "O=Example, CN=billing");
+        assert_eq!(
+            certificate.subject_alt_names,
+            [
+                SubjectAltName::Dns("billing.internal".into()),
+                SubjectAltName::Uri("spiffe://example.org/billing".into()),
+                SubjectAltName::Ip([10, 0, 0, 7].into()),
+                SubjectAltName::Email("billing@example.org".into()),
+            ]
+        );
+
+        let error = assert_invalid(validate_sync(&validator, request(&[OTHER_CERTIFICATE])));
+        assert_eq!(error, Error::NotAllowed);
+        assert_eq!(error.kind(), ErrorKind::Forbidden);
+    }
+}
+
+#[test]
+fn rejected_certificates() {
+    let validator = ClientCertValidator::new().allow_fingerprint(BILLING_FINGERPRINT);
+    let validate = |req| assert_invalid(validate_sync(&validator, req));
+
+    assert_eq!(validate(TestRequest::post("/")), Error::MissingCertificate);
+    assert_eq!(validate(request(&[])), Error::MissingCertificate);
+    assert_eq!(validate(request(&[])).kind(), ErrorKind::MissingCredentials);
+
+    let garbage =
+        TestRequest::post("/").extension(PeerCertificates(vec![bytes::Bytes::from_static(
+            b"\x30\x03\x02\x01\x00",
+        )]));
+    assert_eq!(validate(garbage), Error::InvalidCertificate);
+
+    // Only the leaf counts, the rest of the chain is whatever the client sent.
+    assert_eq!(
+        validate(request(&[OTHER_CERTIFICATE, BILLING_CERTIFICATE])),
+        Error::NotAllowed
+    );
+
+    // Nothing is allowed by default.
+    assert_eq!(
+        validate_sync(&ClientCertValidator::new(), request(&[BILLING_CERTIFICATE])).unwrap_err(),
+        Error::NotAllowed
+    );
+}
+
+#[test]
+#[should_panic = "invalid SHA-256 fingerprint"]
+fn invalid_fingerprint() {
+    let _ = ClientCertValidator::new().allow_fingerprint("db855ce1");
+}
+
+/// The body signature check, standing in for a real one: the hex-encoded SHA-256 of the body.
+struct BodyDigest;
+
+impl SyncValidator<bytes::Bytes> for BodyDigest {
+    type Error = &'static str;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &bytes::Bytes,
+    ) -> Result<(), Self::Error> {
+        let expected = hex::encode(sha2::Sha256::digest(buffered_body));
+        match parts.headers.get("x-body-sha256") {
+            Some(value) if value == &*expected => Ok(()),
+            _ => Err("bad signature"),
+        }
+    }
+}
+
+/// Both the client certificate and the body signature are required.
+struct Both(ClientCertValidator, BodyDigest);
+
+impl SyncValidator<bytes::Bytes> for Both {
+    type Error = String;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &bytes::Bytes,
+    ) -> Result<(), Self::Error> {
+        SyncValidator::validate(&self.0, parts, buffered_body)
+            .map_err(|error| error.to_string())?;
+        SyncValidator::validate(&self.1, parts, buffered_body).map_err(Into::into)
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        SyncValidator::<bytes::Bytes>::pre_validate(&self.0, parts)
+            .map_err(|error| error.to_string())
+    }
+}
+
+#[test]
+fn combined_with_body_signature() {
+    let validator = Both(
+        ClientCertValidator::new().allow_subject("O=Example, CN=billing"),
+        BodyDigest,
+    );
+    let signed = |req: TestRequest| {
+        req.body("{}").sign(|parts, body| {
+            let digest = hex::encode(sha2::Sha256::digest(body));
+            parts
+                .headers
+                .insert("x-body-sha256", digest.try_into().unwrap());
+        })
+    };
+
+    let req = assert_valid(validate_sync(
+        &validator,
+        signed(request(&[BILLING_CERTIFICATE])),
+    ));
+    assert!(req.extensions().get::<ClientCertificate>().is_some());
+
+    let unsigned = request(&[BILLING_CERTIFICATE]).body("{}");
+    assert_eq!(
+        assert_invalid(validate_sync(&validator, unsigned)),
+        "bad signature"
+    );
+    assert_eq!(
+        assert_invalid(validate_sync(
+            &validator,
+            signed(request(&[OTHER_CERTIFICATE]))
+        )),
+        "client certificate is not allowed"
+    );
+}
@@ -0,0 +1,133 @@
+//! The [`http_request_validator::Signer`] integration with [`reqwest_middleware`].
+//!
+//! Buffers the outgoing request body, signs the request with the given signer and sends it
+//! along with the signature headers.
+//!
+//! ## Examples
+//!
+//! ```
+//! let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+//!     .with(reqwest_request_signer::SigningMiddleware::new(
+//!         http_request_validator::AsAsync(webhook_request_validator::GitHub::new("secret")),
+//!     ))
+//!     .build();
+//! # let _ = client;
+//! ```
+//!
+//! See the `webhook-request-validator` crate for the signature schemes, like the plain HMAC,
+//! Standard Webhooks and RFC 9421 HTTP Message Signatures, or implement
+//! the [`http_request_validator::Signer`] for your own.
+
+use bytes::Bytes;
+
+/// An error that can occur while signing the request.
+#[derive(Debug)]
+pub enum Error<S> {
+    /// The buffering of the request body failed.
+    BodyBuffering(reqwest::Error),
+    /// The request URL can not be represented as [`http::Uri`].
+    InvalidUri(http::uri::InvalidUri),
+    /// The signing failed.
+    Signing(S),
+}
+
+impl<S: std::fmt::Display> std::fmt::Display for Error<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BodyBuffering(error) => write!(f, "body buffering failed: {error}"),
+            Self::InvalidUri(error) => write!(f, "invalid request URI: {error}"),
+            Self::Signing(error) => write!(f, "signing failed: {error}"),
+        }
+    }
+}
+
+impl<S: std::error::Error + 'static> std::error::Error for Error<S> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::BodyBuffering(error) => Some(error),
+            Self::InvalidUri(error) => Some(error),
+            Self::Signing(error) => Some(error),
+        }
+    }
+}
+
+/// The [`reqwest_middleware::Middleware`] that signs the outgoing requests.
+#[derive(Debug, Clone)]
+pub struct SigningMiddleware<Signer> {
+    /// The signer.
+    pub signer: Signer,
+}
+
+impl<Signer> SigningMiddleware<Signer> {
+    /// Create a new [`SigningMiddleware`].
+    pub const fn new(signer: Signer) -> Self {
+        Self { signer }
+    }
+}
+
+/// Sign the request with the given signer.
+///
+/// Buffers the request body, so the streaming bodies are read into memory.
+///
+/// The signer sees the given extensions, the [`reqwest_middleware`] ones in the middleware,
+/// as the request extensions, and the changes it makes to them are written back.
+pub async fn sign<Signer>(
+    signer: &Signer,
+    mut req: reqwest::Request,
+    extensions: &mut http::Extensions,
+) -> Result<reqwest::Request, Error<Signer::Error>>
+where
+    Signer: http_request_validator::Signer<Bytes> + ?Sized,
+{
+    let body = match req.body_mut().take() {
+        Some(body) => http_body_util::BodyExt::collect(body)
+            .await
+            .map_err(Error::BodyBuffering)?
+            .to_bytes(),
+        None => Bytes::new(),
+    };
+
+    let mut parts = parts_of(&req).map_err(Error::InvalidUri)?;
+    parts.headers = std::mem::take(req.headers_mut());
+    parts.extensions = std::mem::take(extensions);
+
+    let result = signer.sign(&mut parts, &body).await;
+
+    *req.headers_mut() = parts.headers;
+    *extensions = parts.extensions;
+    *req.body_mut() = Some(body.into());
+    result.map_err(Error::Signing)?;
+
+    Ok(req)
+}
+
+/// Create the request head with the method, URI and version of the given request.
+fn parts_of(req: &reqwest::Request) -> Result<http::request::Parts, http::uri::InvalidUri> {
+    let mut parts = http::Request::new(()).into_parts().0;
+    parts.method = req.method().clone();
+    parts.version = req.version();
+    parts.uri = req.url().as_str().parse()?;
+    Ok(parts)
+}
+
+#[async_trait::async_trait]
+impl<Signer> reqwest_middleware::Middleware for SigningMiddleware<Signer>
+where
+    Signer: http_request_validator::Signer<Bytes> + Send + Sync + 'static,
+    Signer::Error: std::error::Error + Send + Sync + 'static,
+{
+    async fn handle(
+        &self,
+        req: reqwest::Request,
+        extensions: &mut http::Extensions,
+        next: reqwest_middleware::Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        let req = sign(&self.signer, req, extensions)
+            .await
+            .map_err(reqwest_middleware::Error::middleware)?;
+        next.run(req, extensions).await
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,241 @@
+//! The round-trip tests against the axum integration.
+
+/// Signs the request with the reversed body in the `x-signature` header.
+#[derive(Debug, Clone)]
+struct ReversedBodySignature;
+
+/// The signature mismatch error.
+#[derive(Debug)]
+struct BadSignature;
+
+impl std::fmt::Display for BadSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("bad signature")
+    }
+}
+
+impl std::error::Error for BadSignature {}
+
+impl http_request_validator::SyncSigner<bytes::Bytes> for ReversedBodySignature {
+    type Error = BadSignature;
+
+    fn sign(
+        &self,
+        parts: &mut http::request::Parts,
+        buffered_body: &bytes::Bytes,
+    ) -> Result<(), Self::Error> {
+        let signature: Vec<u8> = buffered_body.iter().rev().copied().collect();
+        let signature = http::HeaderValue::from_bytes(&signature).map_err(|_| BadSignature)?;
+        parts.headers.insert("x-signature", signature);
+        Ok(())
+    }
+}
+
+impl http_request_validator::SyncValidator<bytes::Bytes> for ReversedBodySignature {
+    type Error = &'static str;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &bytes::Bytes,
+    ) -> Result<(), Self::Error> {
+        let signature = parts
+            .headers
+            .get("x-signature")
+            .ok_or("missing signature")?;
+        if !signature.as_bytes().iter().eq(buffered_body.iter().rev()) {
+            return Err("bad signature");
+        }
+        Ok(())
+    }
+}
+
+/// Serve the app validating the requests on a random local port, and return its URL.
+async fn serve<Validator>(validator: Validator) -> String
+where
+    Validator: http_request_validator::SyncValidator<bytes::Bytes> + Clone + Send + Sync + 'static,
+    Validator::Error: std::fmt::Display + Send + Sync + 'static,
+{
+    let app: axum::Router<()> = axum::Router::new()
+        .route("/", axum::routing::post(|body: String| async move { body }))
+        .route_layer(axum_request_validator::new(
+            http_request_validator::AsAsync(validator),
+        ));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{addr}/")
+}
+
+#[tokio::test]
+async fn round_trip() {
+    let url = serve(ReversedBodySignature).await;
+
+    let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+        .with(crate::SigningMiddleware::new(
+            http_request_validator::AsAsync(ReversedBodySignature),
+        ))
+        .build();
+    let res = client.post(&url).body("payload").send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    assert_eq!(res.text().await.unwrap(), "payload");
+
+    let res = reqwest::Client::new()
+        .post(&url)
+        .body("payload")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn webhook_schemes() {
+    let url = serve(webhook_request_validator::GitHub::new("secret")).await;
+    let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+        .with(crate::SigningMiddleware::new(
+            http_request_validator::AsAsync(webhook_request_validator::GitHub::new("secret")),
+        ))
+        .build();
+    let res = client.post(&url).body("payload").send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+    let url = serve(webhook_request_validator::Stripe::new("secret")).await;
+    let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+        .with(crate::SigningMiddleware::new(
+            http_request_validator::AsAsync(webhook_request_validator::Stripe::new("secret")),
+        ))
+        .build();
+    let res = client.post(&url).body("payload").send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+    let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+        .with(crate::SigningMiddleware::new(
+            http_request_validator::AsAsync(webhook_request_validator::Stripe::new("other")),
+        ))
+        .build();
+    let res = client.post(&url).body("payload").send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::FORBIDDEN);
+
+    let hmac = webhook_request_validator::HmacHeader::new("x-signature", "secret");
+    let url = serve(hmac.clone()).await;
+    let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+        .with(crate::SigningMiddleware::new(
+            http_request_validator::AsAsync(hmac),
+        ))
+        .build();
+    let res = client.post(&url).body("payload").send().await.unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+
+    let standard = webhook_request_validator::StandardWebhooks::new("secret");
+    let url = serve(standard.clone()).await;
+    let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+        .with(crate::SigningMiddleware::new(
+            http_request_validator::AsAsync(standard),
+        ))
+        .build();
+    let res = client
+        .post(&url)
+        .header("webhook-id", "msg_1")
+        .body("payload")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    // The sender has to pick the message ID.
+    let error = client.post(&url).body("payload").send().await.unwrap_err();
+    assert!(error.is_middleware());
+
+    // The client signs the authority from the URL, and the server checks the `Host` header.
+    let signature = webhook_request_validator::MessageSignature::new("deliveries", "secret");
+    let url = serve(signature.clone()).await;
+    let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+        .with(crate::SigningMiddleware::new(
+            http_request_validator::AsAsync(signature),
+        ))
+        .build();
+    let res = client
+        .post(format!("{url}?attempt=1"))
+        .body("payload")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), reqwest::StatusCode::OK);
+    assert_eq!(res.text().await.unwrap(), "payload");
+}
+
+#[tokio::test]
+async fn signing_error() {
+    let client = reqwest_middleware::ClientBuilder::new(reqwest::Client::new())
+        .with(crate::SigningMiddleware::new(
+            http_request_validator::AsAsync(ReversedBodySignature),
+        ))
+        .build();
+
+    // The newline can not be put into the header value.
+    let error = client
+        .post("http://127.0.0.1:1/")
+        .body("\npayload")
+        .send()
+        .await
+        .unwrap_err();
+    assert!(error.is_middleware());
+}
+
+/// The key ID to sign with, passed in the request extensions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct KeyId(&'static str);
+
+/// Puts the [`KeyId`] from the extensions into the `x-key-id` header.
+struct KeyIdSigner;
+
+impl http_request_validator::SyncSigner<bytes::Bytes> for KeyIdSigner {
+    type Error = BadSignature;
+
+    fn sign(
+        &self,
+        parts: &mut http::request::Parts,
+        _buffered_body: &bytes::Bytes,
+    ) -> Result<(), Self::Error> {
+        let KeyId(key_id) = *parts.extensions.get::<KeyId>().ok_or(BadSignature)?;
+        parts
+            .headers
+            .insert("x-key-id", http::HeaderValue::from_static(key_id));
+        parts.extensions.insert(ReversedBodySignature);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn extensions() {
+    let signer = http_request_validator::AsAsync(KeyIdSigner);
+    let url = "http://127.0.0.1:1/".parse().unwrap();
+
+    let mut extensions = http::Extensions::new();
+    extensions.insert(KeyId("k1"));
+    let req = crate::sign(
+        &signer,
+        reqwest::Request::new(reqwest::Method::POST, url),
+        &mut extensions,
+    )
+    .await
+    .unwrap();
+    assert_eq!(req.headers()["x-key-id"], "k1");
+    assert_eq!(extensions.get::<KeyId>(), Some(&KeyId("k1")));
+    assert!(extensions.get::<ReversedBodySignature>().is_some());
+}
+
+#[tokio::test]
+async fn invalid_uri() {
+    // The URL is valid, but too long for the URI.
+    let url = format!("http://127.0.0.1:1/{}", "a".repeat(70_000));
+    let error = crate::sign(
+        &http_request_validator::AsAsync(ReversedBodySignature),
+        reqwest::Request::new(reqwest::Method::POST, url.parse().unwrap()),
+        &mut http::Extensions::new(),
+    )
+    .await
+    .unwrap_err();
+    assert!(matches!(error, crate::Error::InvalidUri(_)));
+}
@@ -0,0 +1,104 @@
+//! [`GitHub`] scheme.
+
+use crate::Error;
+
+/// The GitHub scheme: the `X-Hub-Signature-256: sha256=<hex HMAC-SHA256 of the body>` header.
+///
+/// The header presence and format are checked in
+/// [`http_request_validator::SyncValidator::pre_validate`] too, so the unsigned requests are
+/// rejected before the body is buffered.
+#[derive(Debug, Clone)]
+pub struct GitHub {
+    /// The webhook secret.
+    secret: Vec<u8>,
+}
+
+impl GitHub {
+    /// The signature header.
+    pub const HEADER: &'static str = "x-hub-signature-256";
+
+    /// The prefix of the signature in the header value.
+    const PREFIX: &'static str = "sha256=";
+
+    /// Create a new [`GitHub`] scheme with the given webhook secret.
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            secret: secret.into(),
+        }
+    }
+
+    /// The header value with the signature of the given body.
+    fn signature(&self, body: &[u8]) -> String {
+        format!(
+            "{}{}",
+            Self::PREFIX,
+            crate::hmac_sha256_hex(&self.secret, &[body])
+        )
+    }
+
+    /// Take the hex-encoded signature from the request head.
+    fn received(parts: &http::request::Parts) -> Result<&str, Error> {
+        parts
+            .headers
+            .get(Self::HEADER)
+            .ok_or(Error::MissingSignature)?
+            .to_str()
+            .ok()
+            .and_then(|value| value.strip_prefix(Self::PREFIX))
+            .ok_or(Error::MalformedSignature)
+    }
+
+    /// Check the request signature, explaining the outcome.
+    pub fn diagnose(&self, parts: &http::request::Parts, body: &[u8]) -> crate::Diagnosis {
+        let problem = http_request_validator::SyncValidator::validate(self, parts, &body).err();
+        crate::Diagnosis {
+            header: Self::HEADER,
+            canonical: body.to_vec(),
+            expected: self.signature(body),
+            received: crate::received(parts.headers.get(Self::HEADER)),
+            timestamp_skew: None,
+            problem,
+        }
+    }
+}
+
+impl<Data> http_request_validator::SyncValidator<Data> for GitHub
+where
+    Data: bytes::Buf + AsRef<[u8]>,
+{
+    type Error = Error;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        let signature = Self::received(parts)?;
+        if !crate::hmac_sha256_matches(&self.secret, &[buffered_body.as_ref()], signature) {
+            return Err(Error::SignatureMismatch);
+        }
+        Ok(())
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        Self::received(parts).map(drop)
+    }
+}
+
+impl<Data> http_request_validator::SyncSigner<Data> for GitHub
+where
+    Data: bytes::Buf + AsRef<[u8]>,
+{
+    type Error = core::convert::Infallible;
+
+    fn sign(
+        &self,
+        parts: &mut http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        let value = http::HeaderValue::try_from(self.signature(buffered_body.as_ref()))
+            .expect("hex is a valid header value");
+        parts.headers.insert(Self::HEADER, value);
+        Ok(())
+    }
+}
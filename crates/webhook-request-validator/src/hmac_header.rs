@@ -0,0 +1,136 @@
+//! [`HmacHeader`] scheme.
+
+use crate::Error;
+
+/// The plain HMAC scheme: the hex HMAC-SHA256 of the body in the given header, optionally after
+/// a fixed prefix, like `sha256=`.
+///
+/// Suits the many providers that sign the body alone, like [`crate::GitHub`] does under its own
+/// header. The header presence and format are checked in
+/// [`http_request_validator::SyncValidator::pre_validate`] too, so the unsigned requests are
+/// rejected before the body is buffered.
+///
+/// ## Examples
+///
+/// ```
+/// use http_request_validator::{sign_blocking, validate_blocking};
+/// use webhook_request_validator::HmacHeader;
+///
+/// let scheme = HmacHeader::new("x-signature", "secret").with_prefix("sha256=");
+///
+/// let req = http::Request::post("/webhook")
+///     .body(bytes::Bytes::from_static(b"Hello, World!"))
+///     .unwrap();
+/// let req = sign_blocking(&scheme, req).unwrap();
+/// assert!(req.headers()["x-signature"].to_str().unwrap().starts_with("sha256="));
+/// assert!(validate_blocking(&scheme, req).is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct HmacHeader {
+    /// The signature header.
+    header: &'static str,
+
+    /// The prefix of the signature in the header value.
+    prefix: String,
+
+    /// The webhook secret.
+    secret: Vec<u8>,
+}
+
+impl HmacHeader {
+    /// Create a new [`HmacHeader`] scheme with the given signature header and webhook secret.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the header name is not a valid lowercase header name.
+    #[track_caller]
+    pub fn new(header: &'static str, secret: impl Into<Vec<u8>>) -> Self {
+        let _ = http::HeaderName::from_static(header);
+        Self {
+            header,
+            prefix: String::new(),
+            secret: secret.into(),
+        }
+    }
+
+    /// Set the prefix of the signature in the header value.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+
+    /// The header value with the signature of the given body.
+    fn signature(&self, body: &[u8]) -> String {
+        format!(
+            "{}{}",
+            self.prefix,
+            crate::hmac_sha256_hex(&self.secret, &[body])
+        )
+    }
+
+    /// Take the hex-encoded signature from the request head.
+    fn received<'a>(&self, parts: &'a http::request::Parts) -> Result<&'a str, Error> {
+        parts
+            .headers
+            .get(self.header)
+            .ok_or(Error::MissingSignature)?
+            .to_str()
+            .ok()
+            .and_then(|value| value.strip_prefix(&*self.prefix))
+            .ok_or(Error::MalformedSignature)
+    }
+
+    /// Check the request signature, explaining the outcome.
+    pub fn diagnose(&self, parts: &http::request::Parts, body: &[u8]) -> crate::Diagnosis {
+        let problem = http_request_validator::SyncValidator::validate(self, parts, &body).err();
+        crate::Diagnosis {
+            header: self.header,
+            canonical: body.to_vec(),
+            expected: self.signature(body),
+            received: crate::received(parts.headers.get(self.header)),
+            timestamp_skew: None,
+            problem,
+        }
+    }
+}
+
+impl<Data> http_request_validator::SyncValidator<Data> for HmacHeader
+where
+    Data: bytes::Buf + AsRef<[u8]>,
+{
+    type Error = Error;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        let signature = self.received(parts)?;
+        if !crate::hmac_sha256_matches(&self.secret, &[buffered_body.as_ref()], signature) {
+            return Err(Error::SignatureMismatch);
+        }
+        Ok(())
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        self.received(parts).map(drop)
+    }
+}
+
+impl<Data> http_request_validator::SyncSigner<Data> for HmacHeader
+where
+    Data: bytes::Buf + AsRef<[u8]>,
+{
+    type Error = http::header::InvalidHeaderValue;
+
+    fn sign(
+        &self,
+        parts: &mut http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        // The prefix is up to the caller, so it may not fit into a header value.
+        let value = http::HeaderValue::try_from(self.signature(buffered_body.as_ref()))?;
+        parts.headers.insert(self.header, value);
+        Ok(())
+    }
+}
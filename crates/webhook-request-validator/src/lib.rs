@@ -0,0 +1,169 @@
+//! Webhook signature schemes for the [`http_request_validator`].
+//!
+//! Each scheme is both a [`http_request_validator::SyncValidator`] and
+//! a [`http_request_validator::SyncSigner`], so the receiving and the sending sides agree on
+//! the algorithm by construction.
+//!
+//! The schemes are:
+//!
+//! - [`HmacHeader`], the hex HMAC-SHA256 of the body in a header of your choice;
+//! - [`GitHub`] and [`Stripe`], the way these services sign their webhooks;
+//! - [`StandardWebhooks`], the [Standard Webhooks](https://www.standardwebhooks.com) scheme;
+//! - [`MessageSignature`], the HTTP Message Signatures of
+//!   [RFC 9421](https://www.rfc-editor.org/rfc/rfc9421) with the `hmac-sha256` algorithm.
+//!
+//! The schemes can also explain why a request is not valid, see [`Diagnosis`].
+//!
+//! ## Examples
+//!
+//! ```
+//! use http_request_validator::{sign_blocking, validate_blocking};
+//! use webhook_request_validator::GitHub;
+//!
+//! let scheme = GitHub::new("It's a Secret to Everybody");
+//!
+//! let req = http::Request::post("/webhook")
+//!     .body(bytes::Bytes::from_static(b"Hello, World!"))
+//!     .unwrap();
+//! let req = sign_blocking(&scheme, req).unwrap();
+//! assert!(validate_blocking(&scheme, req).is_ok());
+//! ```
+
+use hmac::Mac as _;
+
+mod github;
+mod hmac_header;
+mod message_signature;
+mod standard_webhooks;
+mod stripe;
+mod structured_fields;
+
+pub use self::github::GitHub;
+pub use self::hmac_header::HmacHeader;
+pub use self::message_signature::MessageSignature;
+pub use self::standard_webhooks::{InvalidSecret, StandardWebhooks};
+pub use self::stripe::Stripe;
+
+/// The HMAC-SHA256 implementation.
+type HmacSha256 = hmac::Hmac<sha2::Sha256>;
+
+/// The detailed result of the signature check.
+#[derive(Debug)]
+pub struct Diagnosis {
+    /// The header the signature is taken from.
+    pub header: &'static str,
+
+    /// The string the signature is computed over.
+    pub canonical: Vec<u8>,
+
+    /// The signature computed with the given secret.
+    pub expected: String,
+
+    /// The signature found in the request, if any.
+    pub received: Option<String>,
+
+    /// The difference between now and the signed timestamp, in seconds, for the timestamped
+    /// schemes.
+    pub timestamp_skew: Option<i128>,
+
+    /// Why the request is not valid, if it is not.
+    pub problem: Option<Error>,
+}
+
+/// An error that can occur while validating the request signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The signature header is missing.
+    MissingSignature,
+    /// The signature header can not be parsed.
+    MalformedSignature,
+    /// The signed timestamp is too far from now.
+    TimestampOutOfTolerance,
+    /// The signature does not match.
+    SignatureMismatch,
+    /// The message ID header, required by the scheme, is missing.
+    MissingMessageId,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::MissingSignature => "the signature header is missing",
+            Self::MalformedSignature => "the signature header is malformed",
+            Self::TimestampOutOfTolerance => "the timestamp is out of tolerance",
+            Self::SignatureMismatch => "the signature does not match",
+            Self::MissingMessageId => "the message ID header is missing",
+        })
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl http_request_validator::ValidationErrorKind for Error {
+    fn kind(&self) -> http_request_validator::ErrorKind {
+        use http_request_validator::ErrorKind;
+
+        match self {
+            Self::MissingSignature => ErrorKind::MissingCredentials,
+            Self::MalformedSignature | Self::MissingMessageId => ErrorKind::Malformed,
+            Self::TimestampOutOfTolerance => ErrorKind::Expired,
+            Self::SignatureMismatch => ErrorKind::BadSignature,
+        }
+    }
+}
+
+/// Compute the HMAC-SHA256 of the message given in chunks.
+fn hmac_sha256(secret: &[u8], message: &[&[u8]]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    for chunk in message {
+        mac.update(chunk);
+    }
+    mac
+}
+
+/// The hex-encoded HMAC-SHA256 of the message given in chunks.
+fn hmac_sha256_hex(secret: &[u8], message: &[&[u8]]) -> String {
+    hex::encode(hmac_sha256(secret, message).finalize().into_bytes())
+}
+
+/// Whether the hex-encoded signature is the valid HMAC-SHA256 of the message given in chunks.
+///
+/// The comparison is constant-time.
+fn hmac_sha256_matches(secret: &[u8], message: &[&[u8]], signature: &str) -> bool {
+    let Ok(signature) = hex::decode(signature) else {
+        return false;
+    };
+    hmac_sha256_verify(secret, message, &signature)
+}
+
+/// Whether the signature is the valid HMAC-SHA256 of the message given in chunks.
+///
+/// The comparison is constant-time.
+fn hmac_sha256_verify(secret: &[u8], message: &[&[u8]], signature: &[u8]) -> bool {
+    hmac_sha256(secret, message).verify_slice(signature).is_ok()
+}
+
+/// The base64-encoded HMAC-SHA256 of the message given in chunks.
+fn hmac_sha256_base64(secret: &[u8], message: &[&[u8]]) -> String {
+    use base64::Engine as _;
+
+    base64::engine::general_purpose::STANDARD
+        .encode(hmac_sha256(secret, message).finalize().into_bytes())
+}
+
+/// The current UNIX timestamp, in seconds, unless the fixed one is given.
+fn now(fixed: Option<u64>) -> u64 {
+    fixed.unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_secs())
+    })
+}
+
+/// The received signature header value, for the [`Diagnosis`].
+fn received(value: Option<&http::HeaderValue>) -> Option<String> {
+    value.map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+}
+
+#[cfg(test)]
+mod tests;
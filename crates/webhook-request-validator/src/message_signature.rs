@@ -0,0 +1,442 @@
+//! [`MessageSignature`] scheme.
+
+use hmac::Mac as _;
+use sha2::Digest as _;
+
+use crate::structured_fields::{self, Item, Value};
+use crate::Error;
+
+/// The HTTP Message Signatures of [RFC 9421](https://www.rfc-editor.org/rfc/rfc9421), with the
+/// `hmac-sha256` algorithm and the shared key with the given key ID.
+///
+/// The signature covers the method, the authority, the path and the query, and the body
+/// through the `Content-Digest` of [RFC 9530](https://www.rfc-editor.org/rfc/rfc9530). The
+/// signatures that cover less are rejected as malformed, and the signatures by the other keys
+/// are not looked at. The derived components other than these, and the component parameters,
+/// are not supported.
+///
+/// The `created` parameter is required, and has to be within the tolerance from now, and the
+/// `expires` one, if any, has to be in the future. The headers and the timestamps are checked in
+/// [`http_request_validator::SyncValidator::pre_validate`] too, so such requests are rejected
+/// before the body is buffered.
+///
+/// The authority is taken from the request URI, or the `Host` header if the URI has none, so
+/// the server sees the same authority as the client only if no proxy rewrites it on the way.
+/// The signing fails with [`Error::MalformedSignature`] if the request has neither.
+///
+/// ## Examples
+///
+/// ```
+/// use http_request_validator::{sign_blocking, validate_blocking};
+/// use webhook_request_validator::MessageSignature;
+///
+/// let scheme = MessageSignature::new("deliveries-2024", "secret");
+///
+/// let req = http::Request::post("https://example.com/webhook?tenant=1")
+///     .body(bytes::Bytes::from_static(br#"{"type":"ping"}"#))
+///     .unwrap();
+/// let req = sign_blocking(&scheme, req).unwrap();
+/// assert!(req.headers()["signature-input"]
+///     .to_str()
+///     .unwrap()
+///     .contains(r#"keyid="deliveries-2024""#));
+/// assert!(validate_blocking(&scheme, req).is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct MessageSignature {
+    /// The key ID.
+    key_id: String,
+
+    /// The shared key.
+    secret: Vec<u8>,
+
+    /// The maximum allowed difference between now and the `created` timestamp, in seconds.
+    tolerance: u64,
+
+    /// The fixed current UNIX timestamp, in seconds, to use instead of the system time.
+    now: Option<u64>,
+}
+
+/// The signature taken from the request head.
+struct Received {
+    /// The covered components, in order.
+    components: Vec<String>,
+
+    /// The serialized signature parameters, the last line of the signature base.
+    parameters: String,
+
+    /// The `created` timestamp.
+    created: u64,
+
+    /// The `expires` timestamp, if any.
+    expires: Option<u64>,
+
+    /// The signature.
+    signature: Vec<u8>,
+}
+
+impl MessageSignature {
+    /// The signature header.
+    pub const HEADER: &'static str = "signature";
+
+    /// The signature parameters header.
+    pub const INPUT_HEADER: &'static str = "signature-input";
+
+    /// The body digest header.
+    pub const DIGEST_HEADER: &'static str = "content-digest";
+
+    /// The default tolerance of the `created` timestamp, in seconds.
+    pub const DEFAULT_TOLERANCE: u64 = 300;
+
+    /// The algorithm.
+    const ALGORITHM: &'static str = "hmac-sha256";
+
+    /// The label of the produced signatures.
+    const LABEL: &'static str = "sig1";
+
+    /// The components the signatures have to cover, in the order they are signed.
+    const COMPONENTS: [&'static str; 5] =
+        ["@method", "@authority", "@path", "@query", "content-digest"];
+
+    /// Create a new [`MessageSignature`] scheme with the given key ID and shared key, and the
+    /// default tolerance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key ID has the characters other than the printable ASCII ones.
+    #[track_caller]
+    pub fn new(key_id: impl Into<String>, secret: impl Into<Vec<u8>>) -> Self {
+        let key_id = key_id.into();
+        assert!(
+            structured_fields::is_string(&key_id),
+            "invalid key ID: {key_id:?}"
+        );
+        Self {
+            key_id,
+            secret: secret.into(),
+            tolerance: Self::DEFAULT_TOLERANCE,
+            now: None,
+        }
+    }
+
+    /// Set the maximum allowed difference between now and the `created` timestamp, in seconds.
+    pub const fn with_tolerance(mut self, tolerance: u64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Use the given UNIX timestamp, in seconds, as now instead of the system time.
+    ///
+    /// Useful for checking the captured requests, and for the tests.
+    pub const fn with_now(mut self, now: u64) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// The current UNIX timestamp, in seconds.
+    fn now(&self) -> u64 {
+        crate::now(self.now)
+    }
+
+    /// The serialized parameters of the signature created at the given timestamp.
+    fn parameters(&self, created: u64) -> String {
+        let components: Vec<Item> = Self::COMPONENTS
+            .iter()
+            .map(|component| Item::String((*component).into()))
+            .collect();
+        structured_fields::serialize_inner_list(
+            &components,
+            &[
+                (
+                    "created".into(),
+                    Item::Integer(created.try_into().unwrap_or(i64::MAX)),
+                ),
+                ("keyid".into(), Item::String(self.key_id.clone())),
+                ("alg".into(), Item::String(Self::ALGORITHM.into())),
+            ],
+        )
+    }
+
+    /// The `Signature` value with the signature of the given signature base.
+    fn signature(&self, base: &[u8]) -> String {
+        let mac = crate::hmac_sha256(&self.secret, &[base]).finalize();
+        let mut signature = format!("{}=", Self::LABEL);
+        structured_fields::serialize_item(&mut signature, &Item::Bytes(mac.into_bytes().to_vec()));
+        signature
+    }
+
+    /// The `Content-Digest` value of the body.
+    fn digest(body: &[u8]) -> String {
+        let mut digest = String::from("sha-256=");
+        structured_fields::serialize_item(
+            &mut digest,
+            &Item::Bytes(sha2::Sha256::digest(body).to_vec()),
+        );
+        digest
+    }
+
+    /// Take the signature by this key from the request head.
+    fn received(&self, parts: &http::request::Parts) -> Result<Received, Error> {
+        let (Some(input), Some(signatures)) = (
+            parts.headers.get(Self::INPUT_HEADER),
+            parts.headers.get(Self::HEADER),
+        ) else {
+            return Err(Error::MissingSignature);
+        };
+        let parse =
+            |value: &http::HeaderValue| structured_fields::parse_dictionary(value.to_str().ok()?);
+        let input = parse(input).ok_or(Error::MalformedSignature)?;
+        let signatures = parse(signatures).ok_or(Error::MalformedSignature)?;
+
+        let key_id = Item::String(self.key_id.clone());
+        let (label, member) = input
+            .iter()
+            .find(|(_, member)| member.parameter("keyid") == Some(&key_id))
+            .ok_or(Error::MissingSignature)?;
+        let Value::InnerList(items) = &member.value else {
+            return Err(Error::MalformedSignature);
+        };
+        if member
+            .parameter("alg")
+            .is_some_and(|alg| *alg != Item::String(Self::ALGORITHM.into()))
+        {
+            return Err(Error::MalformedSignature);
+        }
+
+        let components = items
+            .iter()
+            .map(|item| match item {
+                Item::String(component) => Some(component.clone()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Error::MalformedSignature)?;
+        if !Self::COMPONENTS
+            .iter()
+            .all(|required| components.iter().any(|component| component == required))
+        {
+            return Err(Error::MalformedSignature);
+        }
+
+        let timestamp = |name| match member.parameter(name) {
+            Some(Item::Integer(timestamp)) => u64::try_from(*timestamp).ok().map(Some),
+            Some(_) => None,
+            None => Some(None),
+        };
+        let created = timestamp("created")
+            .flatten()
+            .ok_or(Error::MalformedSignature)?;
+        let expires = timestamp("expires").ok_or(Error::MalformedSignature)?;
+
+        let signature = signatures
+            .iter()
+            .find(|(candidate, _)| candidate == label)
+            .ok_or(Error::MissingSignature)?;
+        let Value::Item(Item::Bytes(signature)) = &signature.1.value else {
+            return Err(Error::MalformedSignature);
+        };
+
+        Ok(Received {
+            components,
+            parameters: structured_fields::serialize_inner_list(items, &member.parameters),
+            created,
+            expires,
+            signature: signature.clone(),
+        })
+    }
+
+    /// Check the timestamps of the signature.
+    fn check_timestamps(&self, now: u64, received: &Received) -> Result<(), Error> {
+        if now.abs_diff(received.created) > self.tolerance
+            || received.expires.is_some_and(|expires| expires < now)
+        {
+            return Err(Error::TimestampOutOfTolerance);
+        }
+        Ok(())
+    }
+
+    /// Check the request signature, explaining the outcome.
+    pub fn diagnose(&self, parts: &http::request::Parts, body: &[u8]) -> crate::Diagnosis {
+        let received = self.received(parts);
+
+        // Without a valid signature input, show what the signature would be now.
+        let now = self.now();
+        let (components, parameters, created) = match &received {
+            Ok(received) => (
+                received.components.clone(),
+                received.parameters.clone(),
+                received.created,
+            ),
+            Err(_) => (
+                Self::COMPONENTS
+                    .iter()
+                    .map(|&component| component.into())
+                    .collect(),
+                self.parameters(now),
+                now,
+            ),
+        };
+        let canonical = signature_base(parts, &components, &parameters).unwrap_or_default();
+
+        crate::Diagnosis {
+            header: Self::HEADER,
+            expected: self.signature(&canonical),
+            canonical,
+            received: crate::received(parts.headers.get(Self::HEADER)),
+            timestamp_skew: received
+                .is_ok()
+                .then(|| i128::from(now) - i128::from(created)),
+            problem: self.check(now, parts, received, body).err(),
+        }
+    }
+
+    /// Check the body digest and the signature taken from the request head.
+    fn check(
+        &self,
+        now: u64,
+        parts: &http::request::Parts,
+        received: Result<Received, Error>,
+        body: &[u8],
+    ) -> Result<(), Error> {
+        let received = received?;
+        self.check_timestamps(now, &received)?;
+
+        let digests = parts
+            .headers
+            .get(Self::DIGEST_HEADER)
+            .and_then(|value| structured_fields::parse_dictionary(value.to_str().ok()?))
+            .ok_or(Error::MalformedSignature)?;
+        let digest = digests
+            .iter()
+            .find(|(algorithm, _)| algorithm == "sha-256")
+            .ok_or(Error::MalformedSignature)?;
+        let Value::Item(Item::Bytes(digest)) = &digest.1.value else {
+            return Err(Error::MalformedSignature);
+        };
+        if *digest != sha2::Sha256::digest(body).as_slice() {
+            return Err(Error::SignatureMismatch);
+        }
+
+        let base = signature_base(parts, &received.components, &received.parameters)
+            .ok_or(Error::MalformedSignature)?;
+        if !crate::hmac_sha256_verify(&self.secret, &[&base], &received.signature) {
+            return Err(Error::SignatureMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// The signature base over the given components, or `None` if some of them are not present in
+/// the request or are not supported.
+fn signature_base(
+    parts: &http::request::Parts,
+    components: &[String],
+    parameters: &str,
+) -> Option<Vec<u8>> {
+    let mut base = String::new();
+    for component in components {
+        let value = component_value(parts, component)?;
+        base.push_str(&format!("\"{component}\": {value}\n"));
+    }
+    base.push_str(&format!("\"@signature-params\": {parameters}"));
+    Some(base.into_bytes())
+}
+
+/// The value of the component, or `None` if it is not present in the request or is not
+/// supported.
+fn component_value(parts: &http::request::Parts, component: &str) -> Option<String> {
+    match component {
+        "@method" => Some(parts.method.as_str().into()),
+        "@authority" => {
+            let authority = match parts.uri.authority() {
+                Some(authority) => authority.as_str(),
+                None => parts.headers.get(http::header::HOST)?.to_str().ok()?,
+            };
+            // The user info is not a part of the authority the server sees.
+            let authority = authority.rsplit('@').next().unwrap_or(authority);
+            Some(authority.to_ascii_lowercase())
+        }
+        "@path" => Some(match parts.uri.path() {
+            "" => "/".into(),
+            path => path.into(),
+        }),
+        "@query" => Some(format!("?{}", parts.uri.query().unwrap_or_default())),
+        _ if component.starts_with('@') => None,
+        name => {
+            let values = parts
+                .headers
+                .get_all(name)
+                .iter()
+                .map(|value| Some(value.to_str().ok()?.trim()))
+                .collect::<Option<Vec<_>>>()?;
+            if values.is_empty() {
+                return None;
+            }
+            Some(values.join(", "))
+        }
+    }
+}
+
+impl<Data> http_request_validator::SyncValidator<Data> for MessageSignature
+where
+    Data: bytes::Buf + AsRef<[u8]>,
+{
+    type Error = Error;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        self.check(
+            self.now(),
+            parts,
+            self.received(parts),
+            buffered_body.as_ref(),
+        )
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        let received = self.received(parts)?;
+        self.check_timestamps(self.now(), &received)
+    }
+}
+
+impl<Data> http_request_validator::SyncSigner<Data> for MessageSignature
+where
+    Data: bytes::Buf + AsRef<[u8]>,
+{
+    type Error = Error;
+
+    fn sign(
+        &self,
+        parts: &mut http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        let digest = Self::digest(buffered_body.as_ref());
+        parts.headers.insert(
+            Self::DIGEST_HEADER,
+            http::HeaderValue::try_from(digest).expect("base64 is a valid header value"),
+        );
+
+        let parameters = self.parameters(self.now());
+        let components: Vec<String> = Self::COMPONENTS
+            .iter()
+            .map(|&component| component.into())
+            .collect();
+        let base =
+            signature_base(parts, &components, &parameters).ok_or(Error::MalformedSignature)?;
+
+        let signature = self.signature(&base);
+        let input = format!("{}={parameters}", Self::LABEL);
+        parts.headers.insert(
+            Self::INPUT_HEADER,
+            http::HeaderValue::try_from(input).expect("the key ID is printable ASCII"),
+        );
+        parts.headers.insert(
+            Self::HEADER,
+            http::HeaderValue::try_from(signature).expect("base64 is a valid header value"),
+        );
+        Ok(())
+    }
+}
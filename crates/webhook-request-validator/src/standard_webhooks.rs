@@ -0,0 +1,269 @@
+//! [`StandardWebhooks`] scheme.
+
+use base64::Engine as _;
+
+use crate::Error;
+
+/// The [Standard Webhooks](https://www.standardwebhooks.com) scheme: the
+/// `webhook-signature: v1,<base64 HMAC-SHA256>` header, with the signature over
+/// `<webhook-id>.<webhook-timestamp>.<body>`.
+///
+/// The signed timestamp has to be within the tolerance from now, so the captured requests can
+/// not be replayed later. The headers and the timestamp are checked in
+/// [`http_request_validator::SyncValidator::pre_validate`] too, so such requests are rejected
+/// before the body is buffered.
+///
+/// The message ID is up to the sender, and stays the same when the message is redelivered, so
+/// the signing takes it from the `webhook-id` header of the request, and fails with
+/// [`Error::MissingMessageId`] without it.
+///
+/// ## Examples
+///
+/// ```
+/// use http_request_validator::{sign_blocking, validate_blocking};
+/// use webhook_request_validator::StandardWebhooks;
+///
+/// let scheme = StandardWebhooks::from_secret("whsec_MfKQ9r8GKYqrTwjUPD8ILPZIo2LaLaSw").unwrap();
+///
+/// let req = http::Request::post("/webhook")
+///     .header(StandardWebhooks::ID_HEADER, "msg_p5jXN8AQM9LWM0D4loKWxJek")
+///     .body(bytes::Bytes::from_static(br#"{"type":"ping"}"#))
+///     .unwrap();
+/// let req = sign_blocking(&scheme, req).unwrap();
+/// assert!(validate_blocking(&scheme, req).is_ok());
+/// ```
+#[derive(Debug, Clone)]
+pub struct StandardWebhooks {
+    /// The signing key.
+    key: Vec<u8>,
+
+    /// The maximum allowed difference between now and the signed timestamp, in seconds.
+    tolerance: u64,
+
+    /// The fixed current UNIX timestamp, in seconds, to use instead of the system time.
+    now: Option<u64>,
+}
+
+impl StandardWebhooks {
+    /// The signature header.
+    pub const HEADER: &'static str = "webhook-signature";
+
+    /// The message ID header.
+    pub const ID_HEADER: &'static str = "webhook-id";
+
+    /// The signed timestamp header.
+    pub const TIMESTAMP_HEADER: &'static str = "webhook-timestamp";
+
+    /// The default tolerance, in seconds, the same as the Standard Webhooks libraries use.
+    pub const DEFAULT_TOLERANCE: u64 = 300;
+
+    /// The prefix of the secrets in their textual form.
+    const SECRET_PREFIX: &'static str = "whsec_";
+
+    /// The version prefix of the signatures.
+    const VERSION: &'static str = "v1,";
+
+    /// Create a new [`StandardWebhooks`] scheme with the given raw signing key and the default
+    /// tolerance.
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self {
+            key: key.into(),
+            tolerance: Self::DEFAULT_TOLERANCE,
+            now: None,
+        }
+    }
+
+    /// Create a new [`StandardWebhooks`] scheme with the secret in its textual form, the
+    /// base64-encoded key, optionally prefixed with `whsec_`.
+    pub fn from_secret(secret: &str) -> Result<Self, InvalidSecret> {
+        let encoded = secret.strip_prefix(Self::SECRET_PREFIX).unwrap_or(secret);
+        let key = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|_| InvalidSecret)?;
+        Ok(Self::new(key))
+    }
+
+    /// Set the maximum allowed difference between now and the signed timestamp, in seconds.
+    pub const fn with_tolerance(mut self, tolerance: u64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Use the given UNIX timestamp, in seconds, as now instead of the system time.
+    ///
+    /// Useful for checking the captured requests, and for the tests.
+    pub const fn with_now(mut self, now: u64) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// The current UNIX timestamp, in seconds.
+    fn now(&self) -> u64 {
+        crate::now(self.now)
+    }
+
+    /// The string the signature is computed over.
+    fn canonical(id: &str, timestamp: u64, body: &[u8]) -> Vec<u8> {
+        let mut canonical = format!("{id}.{timestamp}.").into_bytes();
+        canonical.extend_from_slice(body);
+        canonical
+    }
+
+    /// The base64-encoded signature of the body of the message with the given ID, signed at
+    /// the given timestamp.
+    fn signature(&self, id: &str, timestamp: u64, body: &[u8]) -> String {
+        let prefix = format!("{id}.{timestamp}.");
+        crate::hmac_sha256_base64(&self.key, &[prefix.as_bytes(), body])
+    }
+
+    /// Take the message ID, the timestamp and the `v1` signatures from the request head.
+    fn received(parts: &http::request::Parts) -> Result<(&str, u64, Vec<&str>), Error> {
+        let signatures = parts
+            .headers
+            .get(Self::HEADER)
+            .ok_or(Error::MissingSignature)?;
+        let id = parts
+            .headers
+            .get(Self::ID_HEADER)
+            .ok_or(Error::MissingMessageId)?
+            .to_str()
+            .map_err(|_| Error::MalformedSignature)?;
+        let timestamp = parts
+            .headers
+            .get(Self::TIMESTAMP_HEADER)
+            .and_then(|value| value.to_str().ok()?.parse().ok())
+            .ok_or(Error::MalformedSignature)?;
+
+        // The signatures of the other versions are skipped, as the keys are rotated.
+        let signatures: Vec<&str> = signatures
+            .to_str()
+            .map_err(|_| Error::MalformedSignature)?
+            .split(' ')
+            .filter_map(|signature| signature.strip_prefix(Self::VERSION))
+            .collect();
+        if signatures.is_empty() {
+            return Err(Error::MalformedSignature);
+        }
+        Ok((id, timestamp, signatures))
+    }
+
+    /// Check that the signed timestamp is within the tolerance from now.
+    const fn check_timestamp(&self, now: u64, timestamp: u64) -> Result<(), Error> {
+        if now.abs_diff(timestamp) > self.tolerance {
+            return Err(Error::TimestampOutOfTolerance);
+        }
+        Ok(())
+    }
+
+    /// Check the request signature, explaining the outcome.
+    pub fn diagnose(&self, parts: &http::request::Parts, body: &[u8]) -> crate::Diagnosis {
+        let received = Self::received(parts);
+
+        // Without a valid timestamp, show what the signature would be now.
+        let now = self.now();
+        let timestamp = received
+            .as_ref()
+            .map_or(now, |(_, timestamp, _)| *timestamp);
+        let id = parts
+            .headers
+            .get(Self::ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+
+        crate::Diagnosis {
+            header: Self::HEADER,
+            canonical: Self::canonical(id, timestamp, body),
+            expected: format!("{}{}", Self::VERSION, self.signature(id, timestamp, body)),
+            received: crate::received(parts.headers.get(Self::HEADER)),
+            timestamp_skew: received
+                .is_ok()
+                .then(|| i128::from(now) - i128::from(timestamp)),
+            problem: self.check(now, received, body).err(),
+        }
+    }
+
+    /// Check the timestamp and the signatures taken from the request head.
+    fn check(
+        &self,
+        now: u64,
+        received: Result<(&str, u64, Vec<&str>), Error>,
+        body: &[u8],
+    ) -> Result<(), Error> {
+        let (id, timestamp, signatures) = received?;
+        self.check_timestamp(now, timestamp)?;
+
+        let prefix = format!("{id}.{timestamp}.");
+        let message = [prefix.as_bytes(), body];
+        let matches = signatures.iter().any(|signature| {
+            base64::engine::general_purpose::STANDARD
+                .decode(signature)
+                .is_ok_and(|signature| crate::hmac_sha256_verify(&self.key, &message, &signature))
+        });
+        if !matches {
+            return Err(Error::SignatureMismatch);
+        }
+        Ok(())
+    }
+}
+
+impl<Data> http_request_validator::SyncValidator<Data> for StandardWebhooks
+where
+    Data: bytes::Buf + AsRef<[u8]>,
+{
+    type Error = Error;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        self.check(self.now(), Self::received(parts), buffered_body.as_ref())
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        let (_, timestamp, _) = Self::received(parts)?;
+        self.check_timestamp(self.now(), timestamp)
+    }
+}
+
+impl<Data> http_request_validator::SyncSigner<Data> for StandardWebhooks
+where
+    Data: bytes::Buf + AsRef<[u8]>,
+{
+    type Error = Error;
+
+    fn sign(
+        &self,
+        parts: &mut http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        let id = parts
+            .headers
+            .get(Self::ID_HEADER)
+            .ok_or(Error::MissingMessageId)?
+            .to_str()
+            .map_err(|_| Error::MalformedSignature)?;
+        let now = self.now();
+        let signature = self.signature(id, now, buffered_body.as_ref());
+
+        parts
+            .headers
+            .insert(Self::TIMESTAMP_HEADER, http::HeaderValue::from(now));
+        let value = http::HeaderValue::try_from(format!("{}{signature}", Self::VERSION))
+            .expect("base64 is a valid header value");
+        parts.headers.insert(Self::HEADER, value);
+        Ok(())
+    }
+}
+
+/// The Standard Webhooks secret is not valid base64.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidSecret;
+
+impl std::fmt::Display for InvalidSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("the secret is not valid base64")
+    }
+}
+
+impl std::error::Error for InvalidSecret {}
@@ -0,0 +1,183 @@
+//! [`Stripe`] scheme.
+
+use crate::Error;
+
+/// The Stripe scheme: the `Stripe-Signature: t=<timestamp>,v1=<hex HMAC-SHA256>` header, with
+/// the signature over `<timestamp>.<body>`.
+///
+/// The signed timestamp has to be within the tolerance from now, so the captured requests can
+/// not be replayed later. The header format and the timestamp are checked in
+/// [`http_request_validator::SyncValidator::pre_validate`] too, so such requests are rejected
+/// before the body is buffered.
+#[derive(Debug, Clone)]
+pub struct Stripe {
+    /// The webhook secret.
+    secret: Vec<u8>,
+
+    /// The maximum allowed difference between now and the signed timestamp, in seconds.
+    tolerance: u64,
+
+    /// The fixed current UNIX timestamp, in seconds, to use instead of the system time.
+    now: Option<u64>,
+}
+
+impl Stripe {
+    /// The signature header.
+    pub const HEADER: &'static str = "stripe-signature";
+
+    /// The default tolerance, in seconds, the same as the Stripe libraries use.
+    pub const DEFAULT_TOLERANCE: u64 = 300;
+
+    /// Create a new [`Stripe`] scheme with the given webhook secret and the default tolerance.
+    pub fn new(secret: impl Into<Vec<u8>>) -> Self {
+        Self {
+            secret: secret.into(),
+            tolerance: Self::DEFAULT_TOLERANCE,
+            now: None,
+        }
+    }
+
+    /// Set the maximum allowed difference between now and the signed timestamp, in seconds.
+    pub const fn with_tolerance(mut self, tolerance: u64) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Use the given UNIX timestamp, in seconds, as now instead of the system time.
+    ///
+    /// Useful for checking the captured requests, and for the tests.
+    pub const fn with_now(mut self, now: u64) -> Self {
+        self.now = Some(now);
+        self
+    }
+
+    /// The current UNIX timestamp, in seconds.
+    fn now(&self) -> u64 {
+        crate::now(self.now)
+    }
+
+    /// The hex-encoded signature of the body signed at the given timestamp.
+    fn signature(&self, timestamp: u64, body: &[u8]) -> String {
+        let timestamp = format!("{timestamp}.");
+        crate::hmac_sha256_hex(&self.secret, &[timestamp.as_bytes(), body])
+    }
+
+    /// Take the timestamp and the `v1` signatures from the request head.
+    fn received(parts: &http::request::Parts) -> Result<(u64, Vec<&str>), Error> {
+        let value = parts
+            .headers
+            .get(Self::HEADER)
+            .ok_or(Error::MissingSignature)?;
+        Self::parse(value.as_bytes()).ok_or(Error::MalformedSignature)
+    }
+
+    /// Parse the header value into the timestamp and the `v1` signatures.
+    fn parse(value: &[u8]) -> Option<(u64, Vec<&str>)> {
+        let value = core::str::from_utf8(value).ok()?;
+        let mut timestamp = None;
+        let mut signatures = Vec::new();
+        for item in value.split(',') {
+            match item.trim().split_once('=')? {
+                ("t", value) => timestamp = Some(value.parse().ok()?),
+                ("v1", value) => signatures.push(value),
+                _ => {}
+            }
+        }
+        if signatures.is_empty() {
+            return None;
+        }
+        Some((timestamp?, signatures))
+    }
+
+    /// Check that the signed timestamp is within the tolerance from now.
+    const fn check_timestamp(&self, now: u64, timestamp: u64) -> Result<(), Error> {
+        if now.abs_diff(timestamp) > self.tolerance {
+            return Err(Error::TimestampOutOfTolerance);
+        }
+        Ok(())
+    }
+
+    /// Check the request signature, explaining the outcome.
+    pub fn diagnose(&self, parts: &http::request::Parts, body: &[u8]) -> crate::Diagnosis {
+        let received = Self::received(parts);
+
+        // Without a valid timestamp, show what the signature would be now.
+        let now = self.now();
+        let timestamp = received.as_ref().map_or(now, |(timestamp, _)| *timestamp);
+
+        let mut canonical = format!("{timestamp}.").into_bytes();
+        canonical.extend_from_slice(body);
+
+        crate::Diagnosis {
+            header: Self::HEADER,
+            canonical,
+            expected: format!("t={timestamp},v1={}", self.signature(timestamp, body)),
+            received: crate::received(parts.headers.get(Self::HEADER)),
+            timestamp_skew: received
+                .is_ok()
+                .then(|| i128::from(now) - i128::from(timestamp)),
+            problem: self.check(now, received, body).err(),
+        }
+    }
+
+    /// Check the timestamp and the signatures taken from the request head.
+    fn check(
+        &self,
+        now: u64,
+        received: Result<(u64, Vec<&str>), Error>,
+        body: &[u8],
+    ) -> Result<(), Error> {
+        let (timestamp, signatures) = received?;
+        self.check_timestamp(now, timestamp)?;
+
+        let timestamp = format!("{timestamp}.");
+        let message = [timestamp.as_bytes(), body];
+        if !signatures
+            .iter()
+            .any(|signature| crate::hmac_sha256_matches(&self.secret, &message, signature))
+        {
+            return Err(Error::SignatureMismatch);
+        }
+        Ok(())
+    }
+}
+
+impl<Data> http_request_validator::SyncValidator<Data> for Stripe
+where
+    Data: bytes::Buf + AsRef<[u8]>,
+{
+    type Error = Error;
+
+    fn validate(
+        &self,
+        parts: &http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        self.check(self.now(), Self::received(parts), buffered_body.as_ref())
+    }
+
+    fn pre_validate(&self, parts: &mut http::request::Parts) -> Result<(), Self::Error> {
+        let (timestamp, _) = Self::received(parts)?;
+        self.check_timestamp(self.now(), timestamp)
+    }
+}
+
+impl<Data> http_request_validator::SyncSigner<Data> for Stripe
+where
+    Data: bytes::Buf + AsRef<[u8]>,
+{
+    type Error = core::convert::Infallible;
+
+    fn sign(
+        &self,
+        parts: &mut http::request::Parts,
+        buffered_body: &Data,
+    ) -> Result<(), Self::Error> {
+        let now = self.now();
+        let signature = self.signature(now, buffered_body.as_ref());
+        let value = http::HeaderValue::try_from(format!("t={now},v1={signature}"))
+            .expect("digits and hex are a valid header value");
+        parts.headers.insert(Self::HEADER, value);
+        Ok(())
+    }
+}
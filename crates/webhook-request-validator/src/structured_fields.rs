@@ -0,0 +1,292 @@
+//! The subset of the [RFC 8941](https://www.rfc-editor.org/rfc/rfc8941) structured fields the
+//! HTTP Message Signatures use: the dictionaries of the inner lists and the byte sequences.
+//!
+//! The decimals and the parameters of the inner list items are not supported, and make the
+//! field invalid.
+
+use base64::Engine as _;
+
+/// A bare item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Item {
+    /// An integer.
+    Integer(i64),
+    /// A string.
+    String(String),
+    /// A token.
+    Token(String),
+    /// A byte sequence.
+    Bytes(Vec<u8>),
+    /// A boolean.
+    Boolean(bool),
+}
+
+/// A dictionary member value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Value {
+    /// An item.
+    Item(Item),
+    /// An inner list.
+    InnerList(Vec<Item>),
+}
+
+/// A dictionary member, with its parameters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Member {
+    /// The value.
+    pub(crate) value: Value,
+
+    /// The parameters, in order.
+    pub(crate) parameters: Vec<(String, Item)>,
+}
+
+impl Member {
+    /// The value of the parameter with the given name.
+    pub(crate) fn parameter(&self, name: &str) -> Option<&Item> {
+        self.parameters
+            .iter()
+            .find(|(candidate, _)| candidate == name)
+            .map(|(_, value)| value)
+    }
+}
+
+/// Parse the dictionary; the later members override the earlier ones with the same key.
+pub(crate) fn parse_dictionary(input: &str) -> Option<Vec<(String, Member)>> {
+    let mut parser = Parser {
+        rest: input.trim_matches(' '),
+    };
+    let mut members: Vec<(String, Member)> = Vec::new();
+    while !parser.rest.is_empty() {
+        let key = parser.key()?;
+        let value = if parser.eat('=') {
+            if parser.rest.starts_with('(') {
+                Value::InnerList(parser.inner_list()?)
+            } else {
+                Value::Item(parser.item()?)
+            }
+        } else {
+            Value::Item(Item::Boolean(true))
+        };
+        let member = Member {
+            value,
+            parameters: parser.parameters()?,
+        };
+        members.retain(|(candidate, _)| *candidate != key);
+        members.push((key, member));
+
+        parser.skip_whitespace();
+        if parser.rest.is_empty() {
+            break;
+        }
+        if !parser.eat(',') {
+            return None;
+        }
+        parser.skip_whitespace();
+        if parser.rest.is_empty() {
+            return None;
+        }
+    }
+    Some(members)
+}
+
+/// Serialize the inner list with its parameters.
+pub(crate) fn serialize_inner_list(items: &[Item], parameters: &[(String, Item)]) -> String {
+    let mut serialized = String::from("(");
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            serialized.push(' ');
+        }
+        serialize_item(&mut serialized, item);
+    }
+    serialized.push(')');
+    for (name, value) in parameters {
+        serialized.push(';');
+        serialized.push_str(name);
+        if *value != Item::Boolean(true) {
+            serialized.push('=');
+            serialize_item(&mut serialized, value);
+        }
+    }
+    serialized
+}
+
+/// Serialize the bare item.
+pub(crate) fn serialize_item(serialized: &mut String, item: &Item) {
+    match item {
+        Item::Integer(value) => serialized.push_str(&value.to_string()),
+        Item::String(value) => {
+            serialized.push('"');
+            for char in value.chars() {
+                if matches!(char, '"' | '\\') {
+                    serialized.push('\\');
+                }
+                serialized.push(char);
+            }
+            serialized.push('"');
+        }
+        Item::Token(value) => serialized.push_str(value),
+        Item::Bytes(value) => {
+            serialized.push(':');
+            serialized.push_str(&base64::engine::general_purpose::STANDARD.encode(value));
+            serialized.push(':');
+        }
+        Item::Boolean(value) => serialized.push_str(if *value { "?1" } else { "?0" }),
+    }
+}
+
+/// Whether the string can be serialized as a string item.
+pub(crate) fn is_string(value: &str) -> bool {
+    value.bytes().all(|byte| (b' '..=b'~').contains(&byte))
+}
+
+/// The parser over the rest of the input.
+struct Parser<'a> {
+    /// The input left to parse.
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    /// Skip the given character, if it is next.
+    fn eat(&mut self, char: char) -> bool {
+        match self.rest.strip_prefix(char) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Skip the optional whitespace.
+    fn skip_whitespace(&mut self) {
+        self.rest = self.rest.trim_start_matches([' ', '\t']);
+    }
+
+    /// Take the characters while they match.
+    fn take_while(&mut self, matches: impl Fn(char) -> bool) -> &'a str {
+        let end = self
+            .rest
+            .find(|char| !matches(char))
+            .unwrap_or(self.rest.len());
+        let (taken, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        taken
+    }
+
+    /// Parse a key.
+    fn key(&mut self) -> Option<String> {
+        if !self
+            .rest
+            .starts_with(|char: char| char.is_ascii_lowercase() || char == '*')
+        {
+            return None;
+        }
+        let key = self.take_while(|char| {
+            char.is_ascii_lowercase() || char.is_ascii_digit() || "_-.*".contains(char)
+        });
+        Some(key.into())
+    }
+
+    /// Parse the parameters.
+    fn parameters(&mut self) -> Option<Vec<(String, Item)>> {
+        let mut parameters: Vec<(String, Item)> = Vec::new();
+        while self.eat(';') {
+            self.rest = self.rest.trim_start_matches(' ');
+            let key = self.key()?;
+            let value = if self.eat('=') {
+                self.item()?
+            } else {
+                Item::Boolean(true)
+            };
+            parameters.retain(|(candidate, _)| *candidate != key);
+            parameters.push((key, value));
+        }
+        Some(parameters)
+    }
+
+    /// Parse an inner list, without the item parameters.
+    fn inner_list(&mut self) -> Option<Vec<Item>> {
+        if !self.eat('(') {
+            return None;
+        }
+        let mut items = Vec::new();
+        loop {
+            self.rest = self.rest.trim_start_matches(' ');
+            if self.eat(')') {
+                return Some(items);
+            }
+            items.push(self.item()?);
+            if self.rest.starts_with(';') {
+                return None;
+            }
+            if !self.rest.starts_with([' ', ')']) {
+                return None;
+            }
+        }
+    }
+
+    /// Parse a bare item.
+    fn item(&mut self) -> Option<Item> {
+        let first = self.rest.chars().next()?;
+        match first {
+            '-' | '0'..='9' => {
+                let negative = self.eat('-');
+                let digits = self.take_while(|char| char.is_ascii_digit());
+                if digits.is_empty() || digits.len() > 15 || self.rest.starts_with('.') {
+                    return None;
+                }
+                let value: i64 = digits.parse().ok()?;
+                Some(Item::Integer(if negative { -value } else { value }))
+            }
+            '"' => {
+                self.eat('"');
+                let mut value = String::new();
+                let mut chars = self.rest.char_indices();
+                while let Some((pos, char)) = chars.next() {
+                    match char {
+                        '"' => {
+                            self.rest = &self.rest[pos + 1..];
+                            return Some(Item::String(value));
+                        }
+                        '\\' => match chars.next()? {
+                            (_, escaped @ ('"' | '\\')) => value.push(escaped),
+                            _ => return None,
+                        },
+                        ' '..='~' => value.push(char),
+                        _ => return None,
+                    }
+                }
+                None
+            }
+            ':' => {
+                self.eat(':');
+                let encoded =
+                    self.take_while(|char| char.is_ascii_alphanumeric() || "+/=".contains(char));
+                if !self.eat(':') {
+                    return None;
+                }
+                let value = base64::engine::general_purpose::STANDARD
+                    .decode(encoded)
+                    .ok()?;
+                Some(Item::Bytes(value))
+            }
+            '?' => {
+                self.eat('?');
+                if self.eat('1') {
+                    Some(Item::Boolean(true))
+                } else if self.eat('0') {
+                    Some(Item::Boolean(false))
+                } else {
+                    None
+                }
+            }
+            'a'..='z' | 'A'..='Z' | '*' => {
+                let token = self.take_while(|char| {
+                    char.is_ascii_alphanumeric() || ":/!#$%&'*+-.^_`|~".contains(char)
+                });
+                Some(Item::Token(token.into()))
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,312 @@
+//! The tests for the schemes.
+
+use http_request_validator::{sign_blocking, validate_blocking, SyncValidator};
+
+use crate::{Error, GitHub, HmacHeader, MessageSignature, StandardWebhooks, Stripe};
+
+fn request(body: &'static [u8]) -> http::Request<bytes::Bytes> {
+    http::Request::post("/")
+        .body(bytes::Bytes::from_static(body))
+        .unwrap()
+}
+
+#[test]
+fn github() {
+    let scheme = GitHub::new("It's a Secret to Everybody");
+
+    let req = sign_blocking(&scheme, request(b"Hello, World!")).unwrap();
+    // The example from the GitHub documentation.
+    assert_eq!(
+        req.headers()[GitHub::HEADER],
+        "sha256=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17"
+    );
+    let mut req = validate_blocking(&scheme, req).unwrap();
+
+    *req.body_mut() = bytes::Bytes::from_static(b"Hello, World?");
+    assert_eq!(
+        validate_blocking(&scheme, req).unwrap_err(),
+        Error::SignatureMismatch
+    );
+}
+
+#[test]
+fn github_pre_validate() {
+    let scheme = GitHub::new("secret");
+
+    let (mut parts, _) = request(b"{}").into_parts();
+    assert_eq!(
+        SyncValidator::<bytes::Bytes>::pre_validate(&scheme, &mut parts),
+        Err(Error::MissingSignature)
+    );
+
+    parts
+        .headers
+        .insert(GitHub::HEADER, "sha1=abc".try_into().unwrap());
+    assert_eq!(
+        SyncValidator::<bytes::Bytes>::pre_validate(&scheme, &mut parts),
+        Err(Error::MalformedSignature)
+    );
+
+    parts
+        .headers
+        .insert(GitHub::HEADER, "sha256=abc".try_into().unwrap());
+    assert_eq!(
+        SyncValidator::<bytes::Bytes>::pre_validate(&scheme, &mut parts),
+        Ok(())
+    );
+}
+
+#[test]
+fn stripe() {
+    let signer = Stripe::new("whsec_test").with_now(1_700_000_000);
+    let req = sign_blocking(&signer, request(b"{}")).unwrap();
+
+    let (parts, body) = req.into_parts();
+
+    let diagnosis = signer
+        .clone()
+        .with_now(1_700_000_010)
+        .diagnose(&parts, &body);
+    assert_eq!(diagnosis.problem, None);
+    assert_eq!(diagnosis.timestamp_skew, Some(10));
+    assert_eq!(diagnosis.canonical, b"1700000000.{}");
+    assert_eq!(
+        diagnosis.received.as_deref(),
+        Some(diagnosis.expected.as_str())
+    );
+
+    let diagnosis = signer
+        .clone()
+        .with_now(1_700_001_000)
+        .diagnose(&parts, &body);
+    assert_eq!(diagnosis.problem, Some(Error::TimestampOutOfTolerance));
+    assert_eq!(diagnosis.timestamp_skew, Some(1000));
+
+    let diagnosis = signer
+        .clone()
+        .with_now(1_700_001_000)
+        .with_tolerance(1000)
+        .diagnose(&parts, &body);
+    assert_eq!(diagnosis.problem, None);
+
+    let (parts, ()) = http::Request::post("/").body(()).unwrap().into_parts();
+    let diagnosis = signer.diagnose(&parts, &body);
+    assert_eq!(diagnosis.problem, Some(Error::MissingSignature));
+    assert_eq!(diagnosis.timestamp_skew, None);
+}
+
+#[test]
+fn stripe_pre_validate() {
+    let signer = Stripe::new("whsec_test").with_now(1_700_000_000);
+    let (mut parts, _) = sign_blocking(&signer, request(b"{}")).unwrap().into_parts();
+
+    assert_eq!(
+        SyncValidator::<bytes::Bytes>::pre_validate(&signer, &mut parts),
+        Ok(())
+    );
+
+    // The stale requests are rejected without looking at the body.
+    let later = signer.clone().with_now(1_700_001_000);
+    assert_eq!(
+        SyncValidator::<bytes::Bytes>::pre_validate(&later, &mut parts),
+        Err(Error::TimestampOutOfTolerance)
+    );
+
+    parts
+        .headers
+        .insert(Stripe::HEADER, "t=now,v1=abc".try_into().unwrap());
+    assert_eq!(
+        SyncValidator::<bytes::Bytes>::pre_validate(&signer, &mut parts),
+        Err(Error::MalformedSignature)
+    );
+}
+
+#[test]
+fn system_time() {
+    let scheme = Stripe::new("whsec_test");
+    let req = sign_blocking(&scheme, request(b"{}")).unwrap();
+    assert!(validate_blocking(&scheme, req).is_ok());
+}
+
+#[test]
+fn hmac_header() {
+    let scheme = HmacHeader::new("x-signature", "It's a Secret to Everybody").with_prefix("v1=");
+
+    let req = sign_blocking(&scheme, request(b"Hello, World!")).unwrap();
+    // The same HMAC as in the GitHub example, under another header and prefix.
+    assert_eq!(
+        req.headers()["x-signature"],
+        "v1=757107ea0eb2509fc211221cce984b8a37570b6d7586c22c46f4379c8b043e17"
+    );
+    let (mut parts, body) = validate_blocking(&scheme, req).unwrap().into_parts();
+    assert_eq!(scheme.diagnose(&parts, &body).problem, None);
+
+    assert_eq!(
+        scheme.diagnose(&parts, b"Hello, World?").problem,
+        Some(Error::SignatureMismatch)
+    );
+    parts.headers.remove("x-signature");
+    assert_eq!(
+        SyncValidator::<bytes::Bytes>::pre_validate(&scheme, &mut parts),
+        Err(Error::MissingSignature)
+    );
+}
+
+#[test]
+fn standard_webhooks() {
+    // The example from the Standard Webhooks test suite.
+    let scheme = StandardWebhooks::from_secret("whsec_MfKQ9r8GKYqrTwjUPD8ILPZIo2LaLaSw")
+        .unwrap()
+        .with_now(1_614_265_330);
+    let message_id = ("webhook-id", "msg_p5jXN8AQM9LWM0D4loKWxJek");
+    let req = || {
+        http::Request::post("/")
+            .header(message_id.0, message_id.1)
+            .body(bytes::Bytes::from_static(br#"{"test": 2432232314}"#))
+            .unwrap()
+    };
+
+    let req = sign_blocking(&scheme, req()).unwrap();
+    assert_eq!(
+        req.headers()[StandardWebhooks::TIMESTAMP_HEADER],
+        "1614265330"
+    );
+    assert_eq!(
+        req.headers()[StandardWebhooks::HEADER],
+        "v1,g0hM9SsE+OTPJTGt/tmIKtSyZlE3uFJELVlNIOLJ1OE="
+    );
+    let (mut parts, body) = validate_blocking(&scheme, req).unwrap().into_parts();
+
+    // The signatures of the other versions and keys are skipped.
+    parts.headers.insert(
+        StandardWebhooks::HEADER,
+        "v1a,abc v1,bm90IHRoaXM= v1,g0hM9SsE+OTPJTGt/tmIKtSyZlE3uFJELVlNIOLJ1OE="
+            .try_into()
+            .unwrap(),
+    );
+    assert_eq!(scheme.diagnose(&parts, &body).problem, None);
+
+    let later = scheme.clone().with_now(1_614_266_330);
+    assert_eq!(
+        SyncValidator::<bytes::Bytes>::pre_validate(&later, &mut parts),
+        Err(Error::TimestampOutOfTolerance)
+    );
+    let diagnosis = later.diagnose(&parts, &body);
+    assert_eq!(diagnosis.timestamp_skew, Some(1000));
+    assert_eq!(
+        diagnosis.canonical,
+        br#"msg_p5jXN8AQM9LWM0D4loKWxJek.1614265330.{"test": 2432232314}"#
+    );
+
+    parts
+        .headers
+        .insert(message_id.0, "msg_other".try_into().unwrap());
+    assert_eq!(
+        scheme.diagnose(&parts, &body).problem,
+        Some(Error::SignatureMismatch)
+    );
+    parts.headers.remove(message_id.0);
+    assert_eq!(
+        SyncValidator::<bytes::Bytes>::pre_validate(&scheme, &mut parts),
+        Err(Error::MissingMessageId)
+    );
+
+    // The sender has to pick the message ID.
+    assert_eq!(
+        sign_blocking(&scheme, request(b"{}")).unwrap_err(),
+        Error::MissingMessageId
+    );
+    assert_eq!(
+        StandardWebhooks::from_secret("whsec_not base64").unwrap_err(),
+        crate::InvalidSecret
+    );
+}
+
+#[test]
+fn message_signature() {
+    let scheme = MessageSignature::new("test-key", "test-shared-secret").with_now(1_618_884_473);
+    let req = http::Request::post("https://Example.com/foo?param=Value&Pet=dog")
+        .header("content-type", "application/json")
+        .body(bytes::Bytes::from_static(br#"{"hello": "world"}"#))
+        .unwrap();
+
+    let req = sign_blocking(&scheme, req).unwrap();
+    // The example from RFC 9530.
+    assert_eq!(
+        req.headers()[MessageSignature::DIGEST_HEADER],
+        "sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:"
+    );
+    let parameters = r#"("@method" "@authority" "@path" "@query" "content-digest");created=1618884473;keyid="test-key";alg="hmac-sha256""#;
+    assert_eq!(
+        req.headers()[MessageSignature::INPUT_HEADER],
+        format!("sig1={parameters}").as_str()
+    );
+
+    let (mut parts, body) = validate_blocking(&scheme, req).unwrap().into_parts();
+    let diagnosis = scheme.diagnose(&parts, &body);
+    assert_eq!(diagnosis.problem, None);
+    assert_eq!(
+        String::from_utf8(diagnosis.canonical).unwrap(),
+        format!(
+            "\"@method\": POST\n\
+             \"@authority\": example.com\n\
+             \"@path\": /foo\n\
+             \"@query\": ?param=Value&Pet=dog\n\
+             \"content-digest\": sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:\n\
+             \"@signature-params\": {parameters}"
+        )
+    );
+
+    // The server gets the authority from the `Host` header.
+    let mut server_parts = parts.clone();
+    server_parts.uri = "/foo?param=Value&Pet=dog".parse().unwrap();
+    server_parts
+        .headers
+        .insert(http::header::HOST, "example.com".try_into().unwrap());
+    assert_eq!(scheme.diagnose(&server_parts, &body).problem, None);
+    server_parts.uri = "/foo?param=Value&Pet=cat".parse().unwrap();
+    assert_eq!(
+        scheme.diagnose(&server_parts, &body).problem,
+        Some(Error::SignatureMismatch)
+    );
+
+    assert_eq!(
+        scheme.diagnose(&parts, b"{}").problem,
+        Some(Error::SignatureMismatch)
+    );
+    assert_eq!(
+        MessageSignature::new("other-key", "test-shared-secret")
+            .with_now(1_618_884_473)
+            .diagnose(&parts, &body)
+            .problem,
+        Some(Error::MissingSignature)
+    );
+
+    let later = scheme.clone().with_now(1_618_885_473);
+    assert_eq!(
+        SyncValidator::<bytes::Bytes>::pre_validate(&later, &mut parts),
+        Err(Error::TimestampOutOfTolerance)
+    );
+
+    // The signatures that do not cover the body are not accepted.
+    parts.headers.insert(
+        MessageSignature::INPUT_HEADER,
+        r#"sig1=("@method" "@authority" "@path" "@query");created=1618884473;keyid="test-key""#
+            .try_into()
+            .unwrap(),
+    );
+    assert_eq!(
+        SyncValidator::<bytes::Bytes>::pre_validate(&scheme, &mut parts),
+        Err(Error::MalformedSignature)
+    );
+    parts.headers.insert(
+        MessageSignature::INPUT_HEADER,
+        r#"sig1=("@method";req "@authority" "@path" "@query" "content-digest");created=1"#
+            .try_into()
+            .unwrap(),
+    );
+    assert_eq!(
+        SyncValidator::<bytes::Bytes>::pre_validate(&scheme, &mut parts),
+        Err(Error::MalformedSignature)
+    );
+}